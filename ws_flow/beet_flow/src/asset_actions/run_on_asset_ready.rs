@@ -16,7 +16,7 @@ impl<A: Asset, P: RunPayload> Default for RunOnAssetReadyPlugin<A, P> {
 
 impl<A: Asset, P: RunPayload> Plugin for RunOnAssetReadyPlugin<A, P> {
 	fn build(&self, app: &mut App) {
-		app.add_systems(Update, run_on_asset_ready::<A, P>);
+		app.add_systems(TickSchedule::get(app), run_on_asset_ready::<A, P>);
 	}
 }
 
@@ -0,0 +1,245 @@
+use crate::prelude::*;
+use anyhow::Result;
+use bevy::asset::processor::LoadTransformAndSave;
+use bevy::asset::transformer::IdentityAssetTransformer;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::reflect::PartialReflect;
+use bevy::reflect::TypeRegistry;
+use bevy::scene::DynamicScene;
+use bevy::scene::DynamicSceneBuilder;
+use bevy::scene::ron;
+use bevy::scene::serde::SceneDeserializer;
+use serde::de::DeserializeSeed;
+use std::any::TypeId;
+
+/// Registers the [`BehaviorTreeAsset`], [`BehaviorTreeLoader`] and
+/// [`BehaviorTreeSaver`], and spawns the tree for each
+/// [`BehaviorTreeInstance`].
+/// This requires the bevy `AssetPlugin`.
+#[derive(Debug, Default, Clone)]
+pub struct BehaviorTreePlugin;
+
+/// Processes `.tree.ron` files by loading and saving them again,
+/// normalizing hand written trees.
+pub type BehaviorTreeProcessor = LoadTransformAndSave<
+	BehaviorTreeLoader,
+	IdentityAssetTransformer<BehaviorTreeAsset>,
+	BehaviorTreeSaver,
+>;
+
+impl Plugin for BehaviorTreePlugin {
+	fn build(&self, app: &mut App) {
+		let saver = BehaviorTreeSaver::from_world(app.world_mut());
+		app.init_asset::<BehaviorTreeAsset>()
+			.init_asset_loader::<BehaviorTreeLoader>()
+			.register_asset_processor::<BehaviorTreeProcessor>(
+				LoadTransformAndSave::new(
+					IdentityAssetTransformer::new(),
+					saver,
+				),
+			)
+			.set_default_asset_processor::<BehaviorTreeProcessor>("tree.ron")
+			// asset events are read every frame, the tick schedule may not
+			// run in a frame and its reader would miss them
+			.add_systems(Update, spawn_behavior_trees);
+	}
+}
+
+/// A serializable action hierarchy, stored as the reflected components
+/// of each action. Any component that is registered in the
+/// [`AppTypeRegistry`] and has `#[reflect(Component)]` will be included,
+/// except for runtime state like [`Running`] and [`RunTimer`].
+///
+/// Entity references are not remapped, so actions like [`RunNext`]
+/// that point to other entities should not be used in tree assets.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// let root = world
+/// 	.spawn((Name::new("root"), Sequence))
+/// 	.with_child(ReturnWith(RunResult::Success))
+/// 	.id();
+/// let asset = BehaviorTreeAsset::from_world(&world, root);
+/// let registry = world.resource::<AppTypeRegistry>().read();
+/// let ron = asset.to_ron(&registry).unwrap();
+/// let asset = BehaviorTreeAsset::from_ron(ron.as_bytes(), &registry).unwrap();
+/// ```
+#[derive(Asset, TypePath)]
+pub struct BehaviorTreeAsset {
+	/// The reflected components of the root action and all of its descendants.
+	pub scene: DynamicScene,
+}
+
+impl BehaviorTreeAsset {
+	/// Extract the action at `root` and all of its descendants.
+	/// If the root has a parent, ie an agent, that relationship is not included.
+	pub fn from_world(world: &World, root: Entity) -> Self {
+		let entities = EntityTree::new_with_world(root, world).flatten();
+		let mut scene = DynamicSceneBuilder::from_world(world)
+			.deny_component::<Running>()
+			.deny_component::<RunTimer>()
+			.extract_entities(entities.into_iter())
+			.build();
+		if let Some(root) = scene
+			.entities
+			.iter_mut()
+			.find(|scene_entity| scene_entity.entity == root)
+		{
+			root.components
+				.retain(|component| !is_type::<ChildOf>(component.as_ref()));
+		}
+		Self { scene }
+	}
+
+	/// Serialize the tree to the bevy scene RON format.
+	pub fn to_ron(&self, registry: &TypeRegistry) -> Result<String> {
+		Ok(self.scene.serialize(registry)?)
+	}
+
+	/// Deserialize a tree from the bevy scene RON format.
+	pub fn from_ron(bytes: &[u8], registry: &TypeRegistry) -> Result<Self> {
+		let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+		let scene = SceneDeserializer {
+			type_registry: registry,
+		}
+		.deserialize(&mut deserializer)
+		.map_err(|err| deserializer.span_error(err))?;
+		Ok(Self { scene })
+	}
+
+	/// The entity in the [`Self::scene`] without a parent.
+	pub fn root(&self) -> Option<Entity> {
+		self.scene
+			.entities
+			.iter()
+			.find(|scene_entity| {
+				!scene_entity
+					.components
+					.iter()
+					.any(|component| is_type::<ChildOf>(component.as_ref()))
+			})
+			.map(|scene_entity| scene_entity.entity)
+	}
+
+	/// Spawn a new instance of the tree, returning the root action.
+	///
+	/// If an `agent` is provided the root action will be added as its child,
+	/// and a [`RunOnSpawn`] on the root will use the agent as its
+	/// [`origin`](OnRun::origin).
+	pub fn spawn(
+		&self,
+		world: &mut World,
+		agent: Option<Entity>,
+	) -> Result<Entity> {
+		let root = self
+			.root()
+			.ok_or_else(|| anyhow::anyhow!("Behavior tree has no root"))?;
+		let mut entity_map = EntityHashMap::default();
+		self.scene.write_to_world(world, &mut entity_map)?;
+		let root = entity_map[&root];
+		if let Some(agent) = agent {
+			world.entity_mut(agent).add_child(root);
+			let mut root_entity = world.entity_mut(root);
+			if root_entity.contains::<RunOnSpawn>() {
+				root_entity.insert(RunOnSpawn::new(OnRunAction::new(
+					root,
+					agent,
+					(),
+				)));
+			}
+		}
+		Ok(root)
+	}
+}
+
+fn is_type<T: 'static>(component: &dyn PartialReflect) -> bool {
+	component
+		.get_represented_type_info()
+		.map(|info| info.type_id() == TypeId::of::<T>())
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn round_trip() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+
+		let root = world
+			.spawn((Name::new("root"), Sequence, RunOnSpawn::default()))
+			.with_child((Name::new("child1"), ReturnWith(RunResult::Success)))
+			.with_child((
+				Name::new("child2"),
+				Repeat::if_failure(),
+				SucceedTimes::new(1),
+			))
+			.id();
+		let ron = {
+			let registry = world.resource::<AppTypeRegistry>().read();
+			BehaviorTreeAsset::from_world(world, root)
+				.to_ron(&registry)
+				.unwrap()
+		};
+		expect(&ron)
+			.to_contain("beet_flow::control_flow_actions::sequence::Sequence");
+		expect(&ron).not().to_contain("Running");
+		world.entity_mut(root).despawn();
+
+		let asset = {
+			let registry = world.resource::<AppTypeRegistry>().read();
+			BehaviorTreeAsset::from_ron(ron.as_bytes(), &registry).unwrap()
+		};
+		let agent = world.spawn(Name::new("agent")).id();
+		let root = asset.spawn(world, Some(agent)).unwrap();
+
+		expect(world.get::<ChildOf>(root).unwrap().parent()).to_be(agent);
+		expect(world.get::<Name>(root)).to_be(Some(&Name::new("root")));
+		expect(world.get::<Children>(root).unwrap().len()).to_be(2);
+		expect(world.get::<RunOnSpawn>(root).unwrap().action.clone())
+			.to_be(OnRunAction::new(root, agent, ()));
+
+		let on_result = collect_on_result(world);
+		app.update();
+		app.world_mut().flush();
+		expect(on_result()).to_be(vec![
+			("child1".to_string(), RunResult::Success),
+			("child2".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+	}
+
+	#[test]
+	fn skips_runtime_state() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let root = world
+			.spawn(Retry::new(2))
+			.with_children(|parent| {
+				parent
+					.spawn(Parallel::default())
+					.with_child(ReturnWith(RunResult::Failure));
+			})
+			.flush_trigger(OnRun::local())
+			.id();
+		expect(world.get::<Retry>(root).unwrap().retries()).to_be(2);
+
+		let registry = world.resource::<AppTypeRegistry>().read();
+		let ron = BehaviorTreeAsset::from_world(world, root)
+			.to_ron(&registry)
+			.unwrap();
+		expect(&ron).to_contain("max: 2");
+		expect(&ron).to_contain("success_policy");
+		expect(&ron).not().to_contain("retries");
+		expect(&ron).not().to_contain("successes");
+		expect(&ron).not().to_contain("completed");
+	}
+}
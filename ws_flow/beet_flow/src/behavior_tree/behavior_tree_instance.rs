@@ -0,0 +1,129 @@
+use crate::prelude::*;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+/// Spawns a [`BehaviorTreeAsset`] as a child of this entity once it
+/// has loaded, which will usually be the agent. The tree is despawned
+/// and respawned whenever the asset is modified, allowing trees to be
+/// hot-reloaded. See [`BehaviorTreeAsset::spawn`] for how the tree
+/// is attached.
+#[derive(Debug, Clone, Component)]
+pub struct BehaviorTreeInstance {
+	/// The tree to spawn.
+	pub handle: Handle<BehaviorTreeAsset>,
+	root: Option<Entity>,
+}
+
+impl BehaviorTreeInstance {
+	/// Spawn the tree when the asset is ready.
+	pub fn new(handle: Handle<BehaviorTreeAsset>) -> Self {
+		Self { handle, root: None }
+	}
+	/// The root action of the currently spawned tree, if any.
+	pub fn root(&self) -> Option<Entity> { self.root }
+}
+
+pub(crate) fn spawn_behavior_trees(
+	mut commands: Commands,
+	mut events: EventReader<AssetEvent<BehaviorTreeAsset>>,
+	added: Query<Entity, Added<BehaviorTreeInstance>>,
+	instances: Query<(Entity, &BehaviorTreeInstance)>,
+) {
+	let changed = events
+		.read()
+		.filter_map(|ev| match ev {
+			AssetEvent::Added { id }
+			| AssetEvent::LoadedWithDependencies { id }
+			| AssetEvent::Modified { id } => Some(*id),
+			_ => None,
+		})
+		.collect::<HashSet<_>>();
+	for (agent, instance) in instances.iter() {
+		if added.contains(agent) || changed.contains(&instance.handle.id()) {
+			commands.queue(move |world: &mut World| respawn(world, agent));
+		}
+	}
+}
+
+/// Despawn the previous tree, if any, and spawn a new one from the
+/// current asset. Does nothing if the asset has not yet loaded.
+fn respawn(world: &mut World, agent: Entity) {
+	let Some(instance) = world.get::<BehaviorTreeInstance>(agent) else {
+		return;
+	};
+	let handle = instance.handle.clone();
+	let prev_root = instance.root;
+	world.resource_scope(|world, assets: Mut<Assets<BehaviorTreeAsset>>| {
+		let Some(asset) = assets.get(&handle) else {
+			return;
+		};
+		if let Some(entity) =
+			prev_root.and_then(|root| world.get_entity_mut(root).ok())
+		{
+			entity.despawn();
+		}
+		match asset.spawn(world, Some(agent)) {
+			Ok(root) => {
+				if let Some(mut instance) =
+					world.get_mut::<BehaviorTreeInstance>(agent)
+				{
+					instance.root = Some(root);
+				}
+			}
+			Err(err) => log::error!("Failed to spawn behavior tree: {err}"),
+		}
+	});
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn tree(world: &mut World, name: &str) -> BehaviorTreeAsset {
+		let root = world.spawn((Name::new(name.to_string()), Sequence)).id();
+		let asset = BehaviorTreeAsset::from_world(world, root);
+		world.entity_mut(root).despawn();
+		asset
+	}
+
+	#[test]
+	fn hot_reload() {
+		let mut app = App::new();
+		app.add_plugins((
+			TaskPoolPlugin::default(),
+			AssetPlugin::default(),
+			BeetFlowPlugin::default(),
+			BehaviorTreePlugin,
+		));
+		let world = app.world_mut();
+		let asset = tree(world, "first");
+		let handle =
+			world.resource_mut::<Assets<BehaviorTreeAsset>>().add(asset);
+		let agent = world.spawn(BehaviorTreeInstance::new(handle.clone())).id();
+		app.update();
+		app.update();
+
+		let world = app.world_mut();
+		let first = world.get::<BehaviorTreeInstance>(agent).unwrap().root();
+		let first = first.unwrap();
+		expect(world.get::<ChildOf>(first).unwrap().parent()).to_be(agent);
+		expect(world.get::<Name>(first)).to_be(Some(&Name::new("first")));
+
+		let asset = tree(world, "second");
+		world
+			.resource_mut::<Assets<BehaviorTreeAsset>>()
+			.insert(&handle, asset);
+		app.update();
+		app.update();
+
+		let world = app.world_mut();
+		let second = world.get::<BehaviorTreeInstance>(agent).unwrap().root();
+		let second = second.unwrap();
+		expect(second).not().to_be(first);
+		expect(world.get_entity(first).is_err()).to_be_true();
+		expect(world.get::<Name>(second)).to_be(Some(&Name::new("second")));
+		expect(world.get::<Children>(agent).unwrap().len()).to_be(1);
+	}
+}
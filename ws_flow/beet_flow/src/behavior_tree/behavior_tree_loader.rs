@@ -0,0 +1,81 @@
+use crate::prelude::*;
+use bevy::asset::AssetLoader;
+use bevy::asset::AsyncWriteExt;
+use bevy::asset::LoadContext;
+use bevy::asset::io::Reader;
+use bevy::asset::io::Writer;
+use bevy::asset::saver::AssetSaver;
+use bevy::asset::saver::SavedAsset;
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistryArc;
+use bevy::tasks::ConditionalSendFuture;
+
+/// Loads a [`BehaviorTreeAsset`] from a `.tree.ron` file,
+/// using the [`AppTypeRegistry`] to resolve action types.
+pub struct BehaviorTreeLoader {
+	type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for BehaviorTreeLoader {
+	fn from_world(world: &mut World) -> Self {
+		Self {
+			type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+		}
+	}
+}
+
+impl AssetLoader for BehaviorTreeLoader {
+	type Asset = BehaviorTreeAsset;
+	type Settings = ();
+	type Error = anyhow::Error;
+
+	fn load(
+		&self,
+		reader: &mut dyn Reader,
+		_settings: &Self::Settings,
+		_load_context: &mut LoadContext,
+	) -> impl ConditionalSendFuture<Output = Result<Self::Asset, Self::Error>>
+	{
+		Box::pin(async move {
+			let mut bytes = Vec::new();
+			reader.read_to_end(&mut bytes).await?;
+			BehaviorTreeAsset::from_ron(&bytes, &self.type_registry.read())
+		})
+	}
+
+	fn extensions(&self) -> &[&str] { &["tree.ron"] }
+}
+
+/// Saves a [`BehaviorTreeAsset`] in the format read by [`BehaviorTreeLoader`],
+/// for use with asset processing.
+pub struct BehaviorTreeSaver {
+	type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for BehaviorTreeSaver {
+	fn from_world(world: &mut World) -> Self {
+		Self {
+			type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+		}
+	}
+}
+
+impl AssetSaver for BehaviorTreeSaver {
+	type Asset = BehaviorTreeAsset;
+	type Settings = ();
+	type OutputLoader = BehaviorTreeLoader;
+	type Error = anyhow::Error;
+
+	fn save(
+		&self,
+		writer: &mut Writer,
+		asset: SavedAsset<'_, Self::Asset>,
+		_settings: &Self::Settings,
+	) -> impl ConditionalSendFuture<Output = Result<(), Self::Error>> {
+		Box::pin(async move {
+			let ron = asset.to_ron(&self.type_registry.read())?;
+			writer.write_all(ron.as_bytes()).await?;
+			Ok(())
+		})
+	}
+}
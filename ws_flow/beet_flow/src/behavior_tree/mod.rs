@@ -0,0 +1,8 @@
+//! Save and load action hierarchies as RON files using bevy reflection,
+//! allowing trees to be authored outside of Rust and hot-reloaded.
+mod behavior_tree_asset;
+mod behavior_tree_instance;
+mod behavior_tree_loader;
+pub use behavior_tree_asset::*;
+pub use behavior_tree_instance::*;
+pub use behavior_tree_loader::*;
@@ -0,0 +1,149 @@
+use crate::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// A key-value store for sharing typed data between actions.
+///
+/// A blackboard can be placed in two locations:
+/// - On an [`ActionEntity`], usually the tree root, where it is
+/// 	shared by that action and all of its descendants.
+/// - On the [`origin`](OnRun::origin) entity, where it overrides
+/// 	the tree blackboard for that agent only.
+///
+/// Use [`BlackboardQuery`] to resolve values from both locations.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn((
+/// 		Blackboard::default().with("hungry", true),
+/// 		RunIfBlackboard::new("hungry", BlackboardPredicate::Equals(true.into())),
+/// 	))
+/// 	.with_child(ReturnWith(RunResult::Success))
+/// 	.trigger(OnRun::local());
+/// ```
+#[derive(
+	Debug, Default, Clone, PartialEq, Deref, DerefMut, Component, Reflect,
+)]
+#[reflect(Default, Component)]
+pub struct Blackboard(pub HashMap<String, BlackboardValue>);
+
+impl Blackboard {
+	/// Insert a value, returning the blackboard for chaining.
+	pub fn with(
+		mut self,
+		key: impl Into<String>,
+		value: impl Into<BlackboardValue>,
+	) -> Self {
+		self.set(key, value);
+		self
+	}
+
+	/// Insert a value, returning the previous value if it existed.
+	pub fn set(
+		&mut self,
+		key: impl Into<String>,
+		value: impl Into<BlackboardValue>,
+	) -> Option<BlackboardValue> {
+		self.0.insert(key.into(), value.into())
+	}
+}
+
+/// Where a [`Blackboard`] value should be written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum BlackboardScope {
+	/// Write to the [`origin`](OnRun::origin) entity, this is
+	/// usually the agent so each agent gets its own copy.
+	#[default]
+	Origin,
+	/// Write to the nearest [`Blackboard`] on the action or its ancestors,
+	/// shared by every agent running the tree. If none exists
+	/// one will be added to the root of the tree.
+	Tree,
+}
+
+/// Resolve [`Blackboard`] values for an action, checking the
+/// [`origin`](OnRun::origin) blackboard before the tree blackboard.
+#[derive(SystemParam)]
+pub struct BlackboardQuery<'w, 's> {
+	parents: Query<'w, 's, &'static ChildOf>,
+	blackboards: Query<'w, 's, &'static Blackboard>,
+}
+
+impl BlackboardQuery<'_, '_> {
+	/// Find the nearest entity with a [`Blackboard`], starting with the
+	/// action and walking up its ancestors.
+	pub fn tree_blackboard(&self, action: Entity) -> Option<Entity> {
+		std::iter::once(action)
+			.chain(self.parents.iter_ancestors(action))
+			.find(|entity| self.blackboards.contains(*entity))
+	}
+
+	/// The entity that a [`BlackboardScope`] should write to.
+	pub fn scope_target(
+		&self,
+		scope: BlackboardScope,
+		action: Entity,
+		origin: Entity,
+	) -> Entity {
+		match scope {
+			BlackboardScope::Origin => origin,
+			BlackboardScope::Tree => self
+				.tree_blackboard(action)
+				.unwrap_or_else(|| self.parents.root_ancestor(action)),
+		}
+	}
+
+	/// Get a value, the [`origin`](OnRun::origin) blackboard takes precedence
+	/// over the tree blackboard.
+	pub fn get(
+		&self,
+		action: Entity,
+		origin: Entity,
+		key: &str,
+	) -> Option<&BlackboardValue> {
+		self.blackboards
+			.get(origin)
+			.ok()
+			.and_then(|blackboard| blackboard.get(key))
+			.or_else(|| {
+				self.tree_blackboard(action)
+					.and_then(|entity| self.blackboards.get(entity).ok())
+					.and_then(|blackboard| blackboard.get(key))
+			})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::ecs::system::SystemState;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn resolves() {
+		let mut world = World::new();
+		let agent = world.spawn(Blackboard::default().with("speed", 2.)).id();
+		let mut child = Entity::PLACEHOLDER;
+		let root = world
+			.spawn(Blackboard::default().with("speed", 1.).with("name", "root"))
+			.with_children(|parent| {
+				child = parent.spawn_empty().id();
+			})
+			.id();
+		let mut state = SystemState::<BlackboardQuery>::new(&mut world);
+		let query = state.get(&world);
+
+		expect(query.tree_blackboard(child)).to_be(Some(root));
+		// tree
+		expect(query.get(child, child, "speed")).to_be(Some(&1.0.into()));
+		// origin overrides tree
+		expect(query.get(child, agent, "speed")).to_be(Some(&2.0.into()));
+		// falls back to tree
+		expect(query.get(child, agent, "name")).to_be(Some(&"root".into()));
+		expect(query.get(child, agent, "missing")).to_be_none();
+	}
+}
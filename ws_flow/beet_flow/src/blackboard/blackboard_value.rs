@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+/// A single value stored in a [`Blackboard`](crate::prelude::Blackboard).
+/// Values are a closed set of reflectable types so that blackboards
+/// can be inspected, serialized and compared without knowing their
+/// contents ahead of time.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let value = BlackboardValue::from(3.);
+/// assert_eq!(value.as_f32(), Some(3.));
+/// ```
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum BlackboardValue {
+	/// A boolean flag.
+	Bool(bool),
+	/// A signed integer.
+	Int(i64),
+	/// A floating point number.
+	Float(f32),
+	/// A string of text.
+	String(String),
+	/// A 3D vector, often a position or direction.
+	Vec3(Vec3),
+	/// A reference to another entity.
+	Entity(Entity),
+}
+
+impl BlackboardValue {
+	/// Returns the value if this is a [`Bool`](Self::Bool).
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			BlackboardValue::Bool(value) => Some(*value),
+			_ => None,
+		}
+	}
+	/// Returns the value if this is an [`Int`](Self::Int).
+	pub fn as_i64(&self) -> Option<i64> {
+		match self {
+			BlackboardValue::Int(value) => Some(*value),
+			_ => None,
+		}
+	}
+	/// Returns the value as a float if it is numeric,
+	/// booleans are converted to `0.` or `1.`.
+	pub fn as_f32(&self) -> Option<f32> {
+		match self {
+			BlackboardValue::Float(value) => Some(*value),
+			BlackboardValue::Int(value) => Some(*value as f32),
+			BlackboardValue::Bool(value) => Some(if *value { 1. } else { 0. }),
+			_ => None,
+		}
+	}
+	/// Returns the value if this is a [`String`](Self::String).
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			BlackboardValue::String(value) => Some(value.as_str()),
+			_ => None,
+		}
+	}
+	/// Returns the value if this is a [`Vec3`](Self::Vec3).
+	pub fn as_vec3(&self) -> Option<Vec3> {
+		match self {
+			BlackboardValue::Vec3(value) => Some(*value),
+			_ => None,
+		}
+	}
+	/// Returns the value if this is an [`Entity`](Self::Entity).
+	pub fn as_entity(&self) -> Option<Entity> {
+		match self {
+			BlackboardValue::Entity(value) => Some(*value),
+			_ => None,
+		}
+	}
+}
+
+impl From<bool> for BlackboardValue {
+	fn from(value: bool) -> Self { Self::Bool(value) }
+}
+impl From<i64> for BlackboardValue {
+	fn from(value: i64) -> Self { Self::Int(value) }
+}
+impl From<i32> for BlackboardValue {
+	fn from(value: i32) -> Self { Self::Int(value as i64) }
+}
+impl From<f32> for BlackboardValue {
+	fn from(value: f32) -> Self { Self::Float(value) }
+}
+impl From<f64> for BlackboardValue {
+	fn from(value: f64) -> Self { Self::Float(value as f32) }
+}
+impl From<String> for BlackboardValue {
+	fn from(value: String) -> Self { Self::String(value) }
+}
+impl From<&str> for BlackboardValue {
+	fn from(value: &str) -> Self { Self::String(value.to_string()) }
+}
+impl From<Vec3> for BlackboardValue {
+	fn from(value: Vec3) -> Self { Self::Vec3(value) }
+}
+impl From<Entity> for BlackboardValue {
+	fn from(value: Entity) -> Self { Self::Entity(value) }
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		expect(BlackboardValue::from(true).as_bool()).to_be(Some(true));
+		expect(BlackboardValue::from(2).as_i64()).to_be(Some(2));
+		expect(BlackboardValue::from(2).as_f32()).to_be(Some(2.));
+		expect(BlackboardValue::from(true).as_f32()).to_be(Some(1.));
+		expect(BlackboardValue::from("foo").as_str()).to_be(Some("foo"));
+		expect(BlackboardValue::from("foo").as_f32()).to_be_none();
+	}
+}
@@ -0,0 +1,144 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::reflect::GetPath;
+
+/// A check to perform against a reflected component field,
+/// see [`Guard::component`].
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct ComponentPredicate {
+	/// The full type path of the component,
+	/// it must be registered with `#[reflect(Component)]`.
+	pub type_path: String,
+	/// The reflect path to the field, ie `.0` for a tuple struct,
+	/// or an empty string to check the component itself.
+	pub field_path: String,
+	/// The check to perform on the field, [`BlackboardPredicate::Exists`]
+	/// and [`BlackboardPredicate::Missing`] check if the field is present.
+	pub predicate: BlackboardPredicate,
+}
+
+impl ComponentPredicate {
+	/// Check a field of the component with the type path of `T`,
+	/// ie `ComponentPredicate::new::<Health>(".0", BlackboardPredicate::LessThan(10.))`.
+	pub fn new<T: Component + TypePath>(
+		field_path: impl Into<String>,
+		predicate: BlackboardPredicate,
+	) -> Self {
+		Self {
+			type_path: T::type_path().to_string(),
+			field_path: field_path.into(),
+			predicate,
+		}
+	}
+
+	/// Check the predicate against the component on the entity.
+	pub fn passes(&self, world: &World, entity: Entity) -> bool {
+		let registry = world.resource::<AppTypeRegistry>().read();
+		let component = registry
+			.get_with_type_path(&self.type_path)
+			.and_then(|registration| registration.data::<ReflectComponent>())
+			.zip(world.get_entity(entity).ok())
+			.and_then(|(reflect, entity)| reflect.reflect(entity));
+		let field = component.and_then(|component| {
+			component.reflect_path(self.field_path.as_str()).ok()
+		});
+		match (&self.predicate, field) {
+			(BlackboardPredicate::Exists, field) => field.is_some(),
+			(BlackboardPredicate::Missing, field) => field.is_none(),
+			(predicate, field) => predicate
+				.passes(field.and_then(reflect_to_blackboard_value).as_ref()),
+		}
+	}
+}
+
+/// Convert a reflected primitive, [`String`], [`Vec3`] or [`Entity`]
+/// to a [`BlackboardValue`], returning `None` for any other type.
+pub fn reflect_to_blackboard_value(
+	value: &dyn PartialReflect,
+) -> Option<BlackboardValue> {
+	value
+		.try_downcast_ref::<bool>()
+		.map(|v| BlackboardValue::Bool(*v))
+		.or_else(|| {
+			value
+				.try_downcast_ref::<f32>()
+				.map(|v| BlackboardValue::Float(*v))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<f64>()
+				.map(|v| BlackboardValue::Float(*v as f32))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<i64>()
+				.map(|v| BlackboardValue::Int(*v))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<i32>()
+				.map(|v| BlackboardValue::Int(*v as i64))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<u32>()
+				.map(|v| BlackboardValue::Int(*v as i64))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<usize>()
+				.map(|v| BlackboardValue::Int(*v as i64))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<String>()
+				.map(|v| BlackboardValue::String(v.clone()))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<Vec3>()
+				.map(|v| BlackboardValue::Vec3(*v))
+		})
+		.or_else(|| {
+			value
+				.try_downcast_ref::<Entity>()
+				.map(|v| BlackboardValue::Entity(*v))
+		})
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[derive(Component, Reflect)]
+	#[reflect(Component)]
+	struct Health(i32);
+
+	#[test]
+	fn component_predicate() {
+		let mut world = World::new();
+		world.init_resource::<AppTypeRegistry>();
+		world
+			.resource::<AppTypeRegistry>()
+			.write()
+			.register::<Health>();
+		let entity = world.spawn(Health(5)).id();
+		let empty = world.spawn_empty().id();
+
+		let low = ComponentPredicate::new::<Health>(
+			".0",
+			BlackboardPredicate::LessThan(10.),
+		);
+		expect(low.passes(&world, entity)).to_be_true();
+		expect(low.passes(&world, empty)).to_be_false();
+		world.get_mut::<Health>(entity).unwrap().0 = 20;
+		expect(low.passes(&world, entity)).to_be_false();
+
+		let missing =
+			ComponentPredicate::new::<Health>("", BlackboardPredicate::Missing);
+		expect(missing.passes(&world, entity)).to_be_false();
+		expect(missing.passes(&world, empty)).to_be_true();
+	}
+}
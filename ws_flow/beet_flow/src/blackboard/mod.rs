@@ -0,0 +1,30 @@
+//! Typed data shared between the actions of a tree.
+//!
+//! A [`Blackboard`] placed on a tree root is visible to every action
+//! in that tree, and a [`Blackboard`] placed on the [`origin`](OnRun::origin)
+//! will override it, allowing the same tree to run against many agents.
+mod blackboard;
+mod blackboard_value;
+mod component_predicate;
+mod read_blackboard_into_score;
+mod run_if_blackboard;
+mod set_blackboard;
+#[allow(unused, reason = "docs")]
+use crate::prelude::*;
+use bevy::prelude::*;
+pub use blackboard::*;
+pub use blackboard_value::*;
+pub use component_predicate::*;
+pub use read_blackboard_into_score::*;
+pub use run_if_blackboard::*;
+pub use set_blackboard::*;
+
+
+/// Registers the blackboard types so they can be reflected,
+/// ie saved in a behavior tree asset.
+pub fn blackboard_plugin(app: &mut App) {
+	app.register_type::<Blackboard>()
+		.register_type::<SetBlackboard>()
+		.register_type::<ReadBlackboardIntoScore>()
+		.register_type::<RunIfBlackboard>();
+}
@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Respond to a [`RequestScore`] with a numeric [`Blackboard`] value,
+/// see [`BlackboardValue::as_f32`] for conversion rules.
+/// ## Tags
+/// - [Scoring](ActionTag::Scoring)
+/// ## Example
+/// The agent with the highest `hunger` will choose to eat.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn((
+/// 		Blackboard::default()
+/// 			.with("hunger", 0.8)
+/// 			.with("fatigue", 0.2),
+/// 		HighestScore::default(),
+/// 	))
+/// 	.with_child((
+/// 		Name::new("Eat"),
+/// 		ReadBlackboardIntoScore::new("hunger"),
+/// 		ReturnWith(RunResult::Success),
+/// 	))
+/// 	.with_child((
+/// 		Name::new("Sleep"),
+/// 		ReadBlackboardIntoScore::new("fatigue"),
+/// 		ReturnWith(RunResult::Success),
+/// 	))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(read_blackboard_into_score)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+pub struct ReadBlackboardIntoScore {
+	/// The key to read.
+	pub key: String,
+	/// The score to return if the key is missing or not numeric.
+	pub fallback: ScoreValue,
+}
+
+impl ReadBlackboardIntoScore {
+	/// Read the given key, falling back to [`ScoreValue::FAIL`].
+	pub fn new(key: impl Into<String>) -> Self {
+		Self {
+			key: key.into(),
+			fallback: ScoreValue::FAIL,
+		}
+	}
+	/// Specify the score to return if the key is missing or not numeric.
+	pub fn with_fallback(mut self, fallback: ScoreValue) -> Self {
+		self.fallback = fallback;
+		self
+	}
+}
+
+fn read_blackboard_into_score(
+	ev: Trigger<OnRun<RequestScore>>,
+	mut commands: Commands,
+	query: Query<&ReadBlackboardIntoScore>,
+	blackboards: BlackboardQuery,
+) {
+	let action = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	let score = blackboards
+		.get(ev.action, ev.origin, &action.key)
+		.and_then(|value| value.as_f32())
+		.map(ScoreValue)
+		.unwrap_or(action.fallback);
+	ev.trigger_result(&mut commands, score);
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_score = observe_triggers::<OnResultAction<ScoreValue>>(world);
+
+		let action = world
+			.spawn((
+				Blackboard::default().with("hunger", 0.8),
+				ReadBlackboardIntoScore::new("hunger"),
+			))
+			.flush_trigger(OnRunAction::local(RequestScore))
+			.id();
+		let fallback = world
+			.spawn(
+				ReadBlackboardIntoScore::new("hunger")
+					.with_fallback(ScoreValue::NEUTRAL),
+			)
+			.flush_trigger(OnRunAction::local(RequestScore))
+			.id();
+
+		expect(&on_score).to_have_returned_nth_with(
+			0,
+			&OnResultAction::global(action, ScoreValue(0.8)),
+		);
+		expect(&on_score).to_have_returned_nth_with(
+			1,
+			&OnResultAction::global(fallback, ScoreValue::NEUTRAL),
+		);
+	}
+}
@@ -0,0 +1,218 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// A check to perform against a [`Blackboard`] value.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum BlackboardPredicate {
+	/// The key has any value.
+	Exists,
+	/// The key does not have a value.
+	Missing,
+	/// The value is equal to the provided value.
+	Equals(BlackboardValue),
+	/// The value is numeric and greater than the provided value.
+	GreaterThan(f32),
+	/// The value is numeric and less than the provided value.
+	LessThan(f32),
+}
+
+impl BlackboardPredicate {
+	/// Check the predicate against a value, which may be missing.
+	pub fn passes(&self, value: Option<&BlackboardValue>) -> bool {
+		match (self, value) {
+			(BlackboardPredicate::Exists, value) => value.is_some(),
+			(BlackboardPredicate::Missing, value) => value.is_none(),
+			(BlackboardPredicate::Equals(expected), Some(value)) => {
+				expected == value
+			}
+			(BlackboardPredicate::GreaterThan(threshold), Some(value)) => value
+				.as_f32()
+				.map(|value| value > *threshold)
+				.unwrap_or(false),
+			(BlackboardPredicate::LessThan(threshold), Some(value)) => value
+				.as_f32()
+				.map(|value| value < *threshold)
+				.unwrap_or(false),
+			(_, None) => false,
+		}
+	}
+}
+
+/// A single key and predicate for a [`RunIfBlackboard`].
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct BlackboardCondition {
+	/// The key to read.
+	pub key: String,
+	/// The check to perform on the value.
+	pub predicate: BlackboardPredicate,
+}
+
+impl BlackboardCondition {
+	/// Check the predicate against the value resolved by [`BlackboardQuery::get`].
+	pub fn passes(
+		&self,
+		blackboards: &BlackboardQuery,
+		action: Entity,
+		origin: Entity,
+	) -> bool {
+		self.predicate
+			.passes(blackboards.get(action, origin, &self.key))
+	}
+}
+
+/// Reads one or more [`Blackboard`] keys before running its first child.
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// ## Logic
+/// - If all conditions pass it will run the first child and bubble its result,
+/// 	or succeed immediately if there are no children.
+/// - If any condition fails it will fail without running any children.
+///
+/// To run several children wrap them in a [`Sequence`] or similar.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn((
+/// 		Blackboard::default().with("health", 10),
+/// 		RunIfBlackboard::new("health", BlackboardPredicate::GreaterThan(5.))
+/// 			.and("enemy", BlackboardPredicate::Missing),
+/// 	))
+/// 	.with_child((Name::new("Explore"), ReturnWith(RunResult::Success)))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(run_if_blackboard)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(BubbleResult)]
+pub struct RunIfBlackboard {
+	/// All conditions must pass for the child to run.
+	pub conditions: Vec<BlackboardCondition>,
+}
+
+impl RunIfBlackboard {
+	/// Create a new action with a single condition.
+	pub fn new(key: impl Into<String>, predicate: BlackboardPredicate) -> Self {
+		Self {
+			conditions: vec![BlackboardCondition {
+				key: key.into(),
+				predicate,
+			}],
+		}
+	}
+	/// Add another condition that must pass.
+	pub fn and(
+		mut self,
+		key: impl Into<String>,
+		predicate: BlackboardPredicate,
+	) -> Self {
+		self.conditions.push(BlackboardCondition {
+			key: key.into(),
+			predicate,
+		});
+		self
+	}
+}
+
+fn run_if_blackboard(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	query: Query<(&RunIfBlackboard, Option<&Children>)>,
+	blackboards: BlackboardQuery,
+) {
+	let (action, children) = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	let passes = action
+		.conditions
+		.iter()
+		.all(|condition| condition.passes(&blackboards, ev.action, ev.origin));
+	if !passes {
+		ev.trigger_result(&mut commands, RunResult::Failure);
+	} else if let Some(child) =
+		children.and_then(|children| children.iter().next())
+	{
+		ev.trigger_next(&mut commands, child);
+	} else {
+		ev.trigger_result(&mut commands, RunResult::Success);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn predicates() {
+		let value = BlackboardValue::from(3);
+		expect(BlackboardPredicate::Exists.passes(Some(&value))).to_be_true();
+		expect(BlackboardPredicate::Missing.passes(None)).to_be_true();
+		expect(BlackboardPredicate::Equals(3.into()).passes(Some(&value)))
+			.to_be_true();
+		expect(BlackboardPredicate::GreaterThan(2.).passes(Some(&value)))
+			.to_be_true();
+		expect(BlackboardPredicate::LessThan(2.).passes(Some(&value)))
+			.to_be_false();
+		expect(BlackboardPredicate::GreaterThan(2.).passes(None)).to_be_false();
+	}
+
+	fn tree(world: &mut World) -> Entity {
+		world
+			.spawn((
+				Name::new("root"),
+				RunIfBlackboard::new(
+					"health",
+					BlackboardPredicate::GreaterThan(5.),
+				),
+			))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Success)))
+			.id()
+	}
+
+	#[test]
+	fn runs_child() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let action = tree(world);
+		world
+			.entity_mut(action)
+			.insert(Blackboard::default().with("health", 10))
+			.flush_trigger(OnRun::local());
+
+		expect(on_result()).to_be(vec![
+			("child".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+	}
+
+	#[test]
+	fn per_agent() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		// one tree, many agents
+		let action = tree(world);
+		world
+			.entity_mut(action)
+			.insert(Blackboard::default().with("health", 10));
+		let healthy = world.spawn_empty().id();
+		let injured = world.spawn(Blackboard::default().with("health", 1)).id();
+
+		world.flush_trigger(OnRunAction::new(action, healthy, ()));
+		world.flush_trigger(OnRunAction::new(action, injured, ()));
+
+		expect(on_result()).to_be(vec![
+			("child".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Failure),
+		]);
+	}
+}
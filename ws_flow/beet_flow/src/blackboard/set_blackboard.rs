@@ -0,0 +1,117 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Write a value to a [`Blackboard`] and succeed.
+/// By default the value is written to the [`origin`](OnRun::origin),
+/// adding a [`Blackboard`] if it does not already have one.
+/// ## Tags
+/// - [MutateOrigin](ActionTag::MutateOrigin)
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn(SetBlackboard::new("found_food", true))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(set_blackboard)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+pub struct SetBlackboard {
+	/// The key to write to.
+	pub key: String,
+	/// The value to write.
+	pub value: BlackboardValue,
+	/// The blackboard to write to.
+	pub scope: BlackboardScope,
+}
+
+impl SetBlackboard {
+	/// Write the value to the [`origin`](OnRun::origin) blackboard.
+	pub fn new(
+		key: impl Into<String>,
+		value: impl Into<BlackboardValue>,
+	) -> Self {
+		Self {
+			key: key.into(),
+			value: value.into(),
+			scope: default(),
+		}
+	}
+	/// Specify the [`BlackboardScope`] to write to.
+	pub fn with_scope(mut self, scope: BlackboardScope) -> Self {
+		self.scope = scope;
+		self
+	}
+}
+
+fn set_blackboard(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	query: Query<&SetBlackboard>,
+	blackboards: BlackboardQuery,
+) {
+	let action = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	let target = blackboards.scope_target(action.scope, ev.action, ev.origin);
+	let key = action.key.clone();
+	let value = action.value.clone();
+	commands
+		.entity(target)
+		.entry::<Blackboard>()
+		.or_default()
+		.and_modify(move |mut blackboard| {
+			blackboard.insert(key, value);
+		});
+	ev.trigger_result(&mut commands, RunResult::Success);
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn origin() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+
+		let agent = world.spawn_empty().id();
+		let action = world.spawn(SetBlackboard::new("health", 3)).id();
+		world.flush_trigger(OnRunAction::new(action, agent, ()));
+
+		expect(world.get::<Blackboard>(agent))
+			.to_be(Some(&Blackboard::default().with("health", 3)));
+		expect(world.get::<Blackboard>(action)).to_be_none();
+	}
+
+	#[test]
+	fn tree() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+
+		let agent = world.spawn_empty().id();
+		let mut child = Entity::PLACEHOLDER;
+		let root = world
+			.spawn(Sequence)
+			.with_children(|parent| {
+				child = parent
+					.spawn(
+						SetBlackboard::new("health", 3)
+							.with_scope(BlackboardScope::Tree),
+					)
+					.id();
+			})
+			.id();
+		world.flush_trigger(OnRunAction::new(root, agent, ()));
+
+		expect(world.get::<Blackboard>(root))
+			.to_be(Some(&Blackboard::default().with("health", 3)));
+		expect(world.get::<Blackboard>(child)).to_be_none();
+		expect(world.get::<Blackboard>(agent)).to_be_none();
+	}
+}
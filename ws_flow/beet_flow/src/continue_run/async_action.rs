@@ -0,0 +1,339 @@
+use crate::prelude::*;
+use bevy::ecs::world::DeferredWorld;
+use bevy::prelude::*;
+use bevy::tasks::BoxedFuture;
+use bevy::tasks::ConditionalSendFuture;
+use bevy::tasks::IoTaskPool;
+use bevy::tasks::Task;
+use bevy::tasks::TaskPool;
+use bevy::tasks::block_on;
+use bevy::tasks::futures_lite::future;
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+
+/// The entities available to the future spawned by an [`AsyncAction`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AsyncActionContext {
+	/// The action entity the [`AsyncAction`] is attached to.
+	pub action: Entity,
+	/// The origin of the [`OnRun`] that spawned the task.
+	pub origin: Entity,
+}
+
+type AsyncActionFn = dyn 'static
+	+ Send
+	+ Sync
+	+ Fn(AsyncActionContext) -> BoxedFuture<'static, RunResult>;
+
+/// Spawns a future on the [`IoTaskPool`] when [`OnRun`] is triggered,
+/// the action will keep [`Running`] until the future resolves,
+/// then return its [`RunResult`].
+/// If [`Running`] is removed before the future resolves, ie the action
+/// is interrupted, the task will be cancelled.
+///
+/// Tasks are driven by the bevy task pools so the [`TaskPoolPlugin`]
+/// is required, it is included in both `DefaultPlugins` and `MinimalPlugins`.
+/// The future is not required to be `Send` on wasm targets.
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # use bevy::tasks::IoTaskPool;
+/// # IoTaskPool::get_or_init(Default::default);
+/// # let mut world = world();
+/// world
+/// 	.spawn((
+/// 		Name::new("Fetch"),
+/// 		AsyncAction::new(|_cx| async move {
+/// 			// await some io here
+/// 			RunResult::Success
+/// 		}),
+/// 	))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(spawn_async_action)]
+#[derive(Clone, Component)]
+#[require(ContinueRun)]
+pub struct AsyncAction {
+	func: Arc<AsyncActionFn>,
+}
+
+impl std::fmt::Debug for AsyncAction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("AsyncAction").finish_non_exhaustive()
+	}
+}
+
+impl AsyncAction {
+	/// Create an action from a function returning a future
+	/// that resolves to a [`RunResult`].
+	pub fn new<F, Fut>(func: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(AsyncActionContext) -> Fut,
+		Fut: 'static + ConditionalSendFuture<Output = RunResult>,
+	{
+		Self {
+			func: Arc::new(move |cx| Box::pin(func(cx))),
+		}
+	}
+
+	/// Create an action from a function returning a fallible future.
+	/// `Ok` will succeed and `Err` will fail, logging the error.
+	pub fn from_result<F, Fut, T, E>(func: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(AsyncActionContext) -> Fut,
+		Fut: 'static + ConditionalSendFuture<Output = Result<T, E>>,
+		T: 'static,
+		E: 'static + Display,
+	{
+		Self::new(move |cx| {
+			let fut = func(cx);
+			async move {
+				match fut.await {
+					Ok(_) => RunResult::Success,
+					Err(err) => {
+						log::warn!("AsyncAction {} failed: {err}", cx.action);
+						RunResult::Failure
+					}
+				}
+			}
+		})
+	}
+}
+
+fn spawn_async_action(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	query: Query<&AsyncAction>,
+) {
+	let action = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	let future = (action.func)(AsyncActionContext {
+		action: ev.action,
+		origin: ev.origin,
+	});
+	let task = AsyncActionTask::new(IoTaskPool::get(), async move {
+		let result = future.await;
+		AsyncActionOutput::new(move |commands, action, running| {
+			running.trigger_result(commands, action, result);
+		})
+	});
+	// any previous task is dropped and cancelled
+	commands.entity(ev.action).insert(task);
+}
+
+/// Applied to the action once the future of an [`AsyncActionTask`]
+/// resolves, if the action is still [`Running`].
+pub struct AsyncActionOutput(
+	Box<dyn 'static + Send + FnOnce(&mut Commands, Entity, &Running)>,
+);
+
+impl AsyncActionOutput {
+	/// Create an output from a function receiving the action entity
+	/// and its [`Running`] component.
+	pub fn new(
+		func: impl 'static + Send + FnOnce(&mut Commands, Entity, &Running),
+	) -> Self {
+		Self(Box::new(func))
+	}
+}
+
+/// The pending task spawned by an [`AsyncAction`], dropping this
+/// component will cancel the task.
+/// Other long running actions may insert this component directly
+/// when their output is more than a [`RunResult`], ie running a child.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub struct AsyncActionTask {
+	task: Task<Option<AsyncActionOutput>>,
+	token: CancelToken,
+}
+
+impl AsyncActionTask {
+	/// Spawn the future on the provided pool, its output is applied
+	/// to the action this component is inserted on.
+	pub fn new<Fut>(pool: &TaskPool, future: Fut) -> Self
+	where
+		Fut: 'static + ConditionalSendFuture<Output = AsyncActionOutput>,
+	{
+		let token = CancelToken::default();
+		let task = pool.spawn(Cancellable {
+			future: Box::pin(future),
+			token: token.clone(),
+		});
+		Self { task, token }
+	}
+}
+
+impl Drop for AsyncActionTask {
+	fn drop(&mut self) { self.token.cancel(); }
+}
+
+/// Polls each [`AsyncActionTask`], applying the output of any that
+/// have resolved.
+pub(crate) fn poll_async_actions(
+	mut commands: Commands,
+	mut query: Populated<(Entity, &Running, &mut AsyncActionTask)>,
+) {
+	for (entity, running, mut task) in query.iter_mut() {
+		// a cancelled task resolves to `None`, this is handled by `cancel_async_action`
+		if let Some(Some(output)) = block_on(future::poll_once(&mut task.task))
+		{
+			commands.entity(entity).remove::<AsyncActionTask>();
+			(output.0)(&mut commands, entity, running);
+		}
+	}
+}
+
+/// Cancel the task whenever [`Running`] is removed, either because
+/// the action returned a result or was interrupted.
+/// This is called by the [`Running`] `on_remove` hook.
+pub(crate) fn cancel_async_action(world: &mut DeferredWorld, entity: Entity) {
+	if world.entity(entity).contains::<AsyncActionTask>() {
+		world
+			.commands()
+			.entity(entity)
+			.try_remove::<AsyncActionTask>();
+	}
+}
+
+/// Shared flag used to cancel a future, on native dropping a [`Task`] is
+/// enough but on wasm the future will keep running.
+#[derive(Default, Clone)]
+struct CancelToken(Arc<Mutex<CancelState>>);
+
+#[derive(Default)]
+struct CancelState {
+	cancelled: bool,
+	waker: Option<Waker>,
+}
+
+impl CancelToken {
+	fn cancel(&self) {
+		let mut state = self.0.lock().unwrap();
+		state.cancelled = true;
+		if let Some(waker) = state.waker.take() {
+			waker.wake();
+		}
+	}
+}
+
+/// Resolves to `None` as soon as the token is cancelled.
+struct Cancellable<F> {
+	future: F,
+	token: CancelToken,
+}
+
+impl<F: Future + Unpin> Future for Cancellable<F> {
+	type Output = Option<F::Output>;
+
+	fn poll(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Self::Output> {
+		{
+			let mut state = self.token.0.lock().unwrap();
+			if state.cancelled {
+				return Poll::Ready(None);
+			}
+			state.waker = Some(cx.waker().clone());
+		}
+		Pin::new(&mut self.future).poll(cx).map(Some)
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use bevy::tasks::futures_lite::future;
+	use sweet::prelude::*;
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.add_plugins((TaskPoolPlugin::default(), BeetFlowPlugin::default()));
+		app
+	}
+
+	/// tasks may resolve on another thread so give them a few frames
+	fn update_until_result(app: &mut App, entity: Entity) {
+		for _ in 0..100 {
+			app.update();
+			if app.world().get::<Running>(entity).is_none() {
+				return;
+			}
+			std::thread::sleep(std::time::Duration::from_millis(1));
+		}
+	}
+
+	#[test]
+	fn succeeds() {
+		let mut app = app();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let entity = world
+			.spawn((
+				Name::new("root"),
+				AsyncAction::new(|_| async { RunResult::Success }),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+		expect(app.world().get::<Running>(entity)).to_be_some();
+
+		update_until_result(&mut app, entity);
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Success)]);
+		expect(app.world().get::<AsyncActionTask>(entity)).to_be_none();
+	}
+
+	#[test]
+	fn fails_on_err() {
+		let mut app = app();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let entity = world
+			.spawn((
+				Name::new("root"),
+				AsyncAction::from_result(|_| async {
+					Result::<(), _>::Err("oops")
+				}),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+
+		update_until_result(&mut app, entity);
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Failure)]);
+	}
+
+	#[test]
+	fn cancels_on_interrupt() {
+		let mut app = app();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let entity = world
+			.spawn((Name::new("root"), AsyncAction::new(|_| future::pending())))
+			.flush_trigger(OnRun::local())
+			.id();
+		app.update();
+		expect(app.world().get::<AsyncActionTask>(entity)).to_be_some();
+
+		app.world_mut().entity_mut(entity).remove::<Running>();
+		app.world_mut().flush();
+		expect(app.world().get::<AsyncActionTask>(entity)).to_be_none();
+		app.update();
+		expect(on_result()).to_be(vec![]);
+	}
+}
@@ -47,7 +47,7 @@ fn insert_running(ev: Trigger<OnRun>, mut commands: Commands) {
 /// ```
 /// As this is frequently added and removed, it is `SparseSet`.
 #[derive(Debug, Copy, Clone, Component, PartialEq, Reflect)]
-#[component(storage = "SparseSet",on_add = on_add_running)]
+#[component(storage = "SparseSet",on_add = on_add_running, on_remove = on_remove_running)]
 #[reflect(Component)]
 #[require(RunTimer)] // mostly for tests where we added running directly, usually this is required by `ContinueRun`
 pub struct Running {
@@ -64,6 +64,11 @@ fn on_add_running(mut world: DeferredWorld, cx: HookContext) {
 	}
 }
 
+/// Cancel any [`AsyncAction`] when the action stops running.
+fn on_remove_running(mut world: DeferredWorld, cx: HookContext) {
+	cancel_async_action(&mut world, cx.entity);
+}
+
 impl Running {
 	/// Create a new instance of `Running` with the provided origin.
 	pub fn new(origin: Entity) -> Self { Self { origin } }
@@ -2,6 +2,7 @@
 //! The core of long running actions in Beet is
 //! systems that filter by the [Running] component.
 /// For usage see the [Running] component.
+mod async_action;
 mod continue_run;
 mod insert;
 mod remove;
@@ -9,6 +10,7 @@ mod return_in_duration;
 mod run_timer;
 use crate::prelude::*;
 use bevy::prelude::*;
+pub use async_action::*;
 pub use continue_run::*;
 pub use insert::*;
 pub use remove::*;
@@ -19,15 +21,20 @@ pub use run_timer::*;
 /// Registers systems and observers required for long running actions.
 pub fn continue_run_plugin(app: &mut App) {
 	app.add_systems(
-		Update,
+		TickSchedule::get(app),
 		(
 			tick_run_timers,
 			// return_in_duration must be after tick_run_timers
 			return_in_duration::<RunResult>,
+			poll_async_actions,
 		)
 			.chain()
 			.in_set(TickSet),
 	)
 	.add_observer(reset_run_time_started)
-	.add_observer(reset_run_timer_stopped);
+	.add_observer(reset_run_timer_stopped)
+	.register_type::<ContinueRun>()
+	.register_type::<Running>()
+	.register_type::<RunTimer>()
+	.register_type::<ReturnInDuration<RunResult>>();
 }
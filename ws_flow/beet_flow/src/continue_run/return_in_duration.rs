@@ -18,6 +18,7 @@ use std::time::Duration;
 ///
 /// ```
 #[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
 #[require(ContinueRun)]
 pub struct ReturnInDuration<T: ResultPayload = RunResult> {
 	/// The length of time the action will run for before triggering the event.
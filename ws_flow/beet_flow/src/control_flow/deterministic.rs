@@ -0,0 +1,208 @@
+use anyhow::Result;
+use anyhow::bail;
+use bevy::ecs::schedule::InternedScheduleLabel;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::time::TimePlugin;
+use bevy::time::TimeUpdateStrategy;
+use std::fmt::Debug;
+use std::time::Duration;
+use sweet::prelude::RandomSource;
+
+/// The schedule that beet systems are added to, defaults to [`Update`].
+/// Plugins read this when they are built so it must be inserted before
+/// any beet plugins are added, usually via [`BeetFlowPlugin::deterministic`].
+#[derive(Debug, Clone, Deref, Resource)]
+pub struct TickSchedule(pub InternedScheduleLabel);
+
+impl Default for TickSchedule {
+	fn default() -> Self { Self(Update.intern()) }
+}
+
+impl TickSchedule {
+	/// The schedule for this app, or [`Update`] if no [`TickSchedule`]
+	/// has been inserted.
+	pub fn get(app: &App) -> InternedScheduleLabel {
+		app.world()
+			.get_resource::<Self>()
+			.map(|schedule| schedule.0)
+			.unwrap_or_else(|| Update.intern())
+	}
+}
+
+/// Opt-in mode for reproducible simulations and tests,
+/// usually added by [`BeetFlowPlugin::deterministic`]:
+/// - beet systems run in [`FixedUpdate`], so [`Time`] is the fixed timestep
+/// - the [`RandomSource`] is seeded
+///
+/// Two runs with the same seed and inputs will produce identical results,
+/// see [`check_deterministic`].
+#[derive(Debug, Clone)]
+pub struct DeterministicPlugin {
+	/// The seed for the [`RandomSource`].
+	pub seed: u64,
+	/// The duration of each [`FixedUpdate`] step.
+	pub timestep: Duration,
+}
+
+impl DeterministicPlugin {
+	/// Create a deterministic plugin with the default 64hz timestep.
+	pub fn new(seed: u64) -> Self {
+		Self {
+			seed,
+			timestep: Duration::from_micros(15625),
+		}
+	}
+}
+
+impl Plugin for DeterministicPlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(TickSchedule(FixedUpdate.intern()))
+			.insert_resource(RandomSource::from_seed(self.seed))
+			.insert_resource(Time::<Fixed>::from_duration(self.timestep));
+	}
+}
+
+/// Run two apps for the given number of updates, returning an error if
+/// `snapshot` differs between them after any update.
+///
+/// Each app is created with the [`TimePlugin`] advancing by exactly
+/// one fixed timestep per update, then passed to `build` which must
+/// add [`BeetFlowPlugin::deterministic`] before any other beet plugins.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// check_deterministic(
+/// 	10,
+/// 	|app| {
+/// 		app.add_plugins(BeetFlowPlugin::deterministic(0));
+/// 		app.world_mut()
+/// 			.spawn(ReturnInDuration::with_secs(RunResult::Success, 1))
+/// 			.trigger(OnRun::local());
+/// 	},
+/// 	|world| world.query::<&RunTimer>().single(world).unwrap().last_started.elapsed(),
+/// )
+/// .unwrap();
+/// ```
+/// # Errors
+/// If `build` did not add [`BeetFlowPlugin::deterministic`] or the runs
+/// diverged.
+pub fn check_deterministic<T: PartialEq + Debug>(
+	updates: usize,
+	build: impl Fn(&mut App),
+	mut snapshot: impl FnMut(&mut World) -> T,
+) -> Result<()> {
+	let mut run = || -> Result<Vec<T>> {
+		let mut app = App::new();
+		app.add_plugins(TimePlugin);
+		build(&mut app);
+		if TickSchedule::get(&app) != FixedUpdate.intern() {
+			bail!("check_deterministic requires BeetFlowPlugin::deterministic");
+		}
+		let timestep = app.world().resource::<Time<Fixed>>().timestep();
+		app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+		Ok((0..updates)
+			.map(|_| {
+				app.update();
+				snapshot(app.world_mut())
+			})
+			.collect())
+	};
+	let first = run()?;
+	let second = run()?;
+	for (index, (first, second)) in first.iter().zip(second.iter()).enumerate()
+	{
+		if first != second {
+			bail!(
+				"runs diverged after update {index}:\nfirst: {first:?}\nsecond: {second:?}"
+			);
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::ecs::schedule::ScheduleLabel;
+	use bevy::prelude::*;
+	use bevy::time::TimePlugin;
+	use bevy::time::TimeUpdateStrategy;
+	use sweet::prelude::*;
+
+	#[test]
+	fn fixed_update() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::deterministic(0));
+		expect(TickSchedule::get(&app)).to_be(FixedUpdate.intern());
+		expect(
+			app.world_mut()
+				.resource_mut::<RandomSource>()
+				.random::<u32>(),
+		)
+		.to_be(RandomSource::from_seed(0).random::<u32>());
+
+		let timestep = app.world().resource::<Time<Fixed>>().timestep();
+		app.add_plugins(TimePlugin)
+			.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+		let action = app
+			.world_mut()
+			.spawn(ReturnInDuration::with_secs(RunResult::Success, 1))
+			.flush_trigger(OnRun::local())
+			.id();
+		// time is not advanced on the first update
+		for _ in 0..11 {
+			app.update();
+		}
+		expect(
+			app.world()
+				.get::<RunTimer>(action)
+				.unwrap()
+				.last_started
+				.elapsed(),
+		)
+		.to_be(timestep * 10);
+	}
+
+	#[test]
+	fn deterministic() {
+		check_deterministic(
+			20,
+			|app| {
+				app.add_plugins(BeetFlowPlugin::deterministic(7));
+				app.world_mut()
+					.spawn((Repeat::default(), Sequence))
+					.with_child(ReturnInDuration::with_millis(
+						RunResult::Success,
+						100,
+					))
+					.trigger(OnRun::local());
+			},
+			|world| {
+				let rng = world.resource_mut::<RandomSource>().random::<u64>();
+				let elapsed = world
+					.query::<&RunTimer>()
+					.iter(world)
+					.map(|timer| timer.last_started.elapsed())
+					.collect::<Vec<_>>();
+				(rng, elapsed)
+			},
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn requires_deterministic() {
+		expect(
+			check_deterministic(
+				1,
+				|app| {
+					app.add_plugins(BeetFlowPlugin::default());
+				},
+				|_| (),
+			)
+			.is_err(),
+		)
+		.to_be_true();
+	}
+}
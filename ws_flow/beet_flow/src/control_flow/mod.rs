@@ -4,6 +4,7 @@
 mod action_event;
 mod action_observers;
 mod beet_debug_plugin;
+mod deterministic;
 pub mod expect_action;
 mod on_result;
 mod on_run;
@@ -12,7 +13,9 @@ use crate::prelude::*;
 pub use action_event::*;
 pub use action_observers::*;
 pub use beet_debug_plugin::*;
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
+pub use deterministic::*;
 pub use on_result::*;
 pub use on_run::*;
 pub use run_on_spawn::*;
@@ -24,15 +27,34 @@ use std::fmt::Debug;
 
 /// Sets up the base functionality for [`OnRun`] and [`OnResult`] routing.
 pub(crate) fn control_flow_plugin(app: &mut App) {
+	let schedule = TickSchedule::get(app);
+	// the sets are also configured in Update for any
+	// systems that are not using the TickSchedule
+	for label in [Update.intern(), schedule] {
+		app.configure_sets(label, PreTickSet)
+			.configure_sets(label, TickSet.after(PreTickSet))
+			.configure_sets(label, PostTickSet.after(TickSet));
+	}
 	app.init_resource::<ActionObserverMap>()
 		.add_plugins((
 			run_plugin::<(), RunResult>,
 			run_plugin::<RequestScore, ScoreValue>,
 		))
-		.configure_sets(Update, PreTickSet)
-		.configure_sets(Update, TickSet.after(PreTickSet))
-		.configure_sets(Update, PostTickSet.after(TickSet))
-		.add_systems(Update, run_on_spawn.in_set(PreTickSet));
+		.add_systems(schedule, run_on_spawn.in_set(PreTickSet))
+		.register_type::<NoBubble>()
+		.register_type::<NoInterrupt>()
+		.register_type::<RunOnSpawn>()
+		.register_type::<TargetEntity>()
+		.register_type::<Fallback>()
+		.register_type::<HighestScore>()
+		.register_type::<LogNameOnRun>()
+		.register_type::<LogOnRun>()
+		.register_type::<Parallel>()
+		.register_type::<Repeat>()
+		.register_type::<ReturnWith<RunResult>>()
+		.register_type::<ReturnWith<ScoreValue>>()
+		.register_type::<Sequence>()
+		.register_type::<SucceedTimes>();
 }
 /// Any [RunTimer] will be ticked, runs before [`TickSet`]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
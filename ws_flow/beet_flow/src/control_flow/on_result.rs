@@ -181,6 +181,7 @@ impl<T: ResultPayload> OnChildResult<T> {
 /// The Success/Failure pattern is commonly used by control flow actions in
 /// the behavior tree pattern.
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+#[cfg_attr(feature = "reflect", derive(serde::Serialize, serde::Deserialize))]
 pub enum RunResult {
 	/// The action was successful.
 	#[default]
@@ -189,6 +190,16 @@ pub enum RunResult {
 	Failure,
 }
 
+impl std::ops::Not for RunResult {
+	type Output = Self;
+	fn not(self) -> Self::Output {
+		match self {
+			RunResult::Success => RunResult::Failure,
+			RunResult::Failure => RunResult::Success,
+		}
+	}
+}
+
 /// Add this to an entity to prevent the run result from bubbling up.
 /// Any action that requires this needs to manually call OnChildResult
 /// on the parent entity. For an example, see [`Repeat`].
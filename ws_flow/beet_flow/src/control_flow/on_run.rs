@@ -10,7 +10,7 @@ use std::fmt::Debug;
 /// - If [`Self::action`] is [`Entity::PLACEHOLDER`], the entity this was triggered on will be used.
 /// - If the action is local and the trigger is global, ie `commands.trigger(OnRunAction::local(()))`
 /// 	this will result in a panic.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Event)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Event, Reflect)]
 pub struct OnRunAction<T = ()> {
 	/// The payload of the run.
 	/// By analogy if an action is a function, this would be the arguments.
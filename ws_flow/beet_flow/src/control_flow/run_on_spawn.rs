@@ -16,8 +16,9 @@ use bevy::prelude::*;
 /// ```
 /// ## Notes
 /// This component is SparsSet as it is frequently added and removed.
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, Reflect)]
 #[component(storage = "SparseSet")]
+#[reflect(Component)]
 pub struct RunOnSpawn<T = ()> {
 	/// The payload of the run.
 	/// By analogy if an action is a function, this would be the arguments.
@@ -0,0 +1,127 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Runs its first child, unless it last stopped running more recently
+/// than the given duration, as tracked by the [`RunTimer::last_stopped`].
+/// The cooldown is not active until the action has run at least once.
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// ## Logic
+/// - If the cooldown is active it will fail without running any children.
+/// - Otherwise it will run the first child and bubble its result,
+/// 	or succeed immediately if there are no children.
+/// ## Example
+/// Only attack once per second.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # use std::time::Duration;
+/// # let mut world = world();
+/// world
+/// 	.spawn(Cooldown(Duration::from_secs(1)))
+/// 	.with_child((Name::new("Attack"), ReturnWith(RunResult::Success)))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(cooldown_on_run, cooldown_on_child_result)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(RunTimer = cooled_down_timer())]
+pub struct Cooldown(pub Duration);
+
+impl Cooldown {
+	/// Specify the cooldown in seconds.
+	pub fn from_secs(secs: u64) -> Self { Self(Duration::from_secs(secs)) }
+	/// Specify the cooldown in milliseconds.
+	pub fn from_millis(millis: u64) -> Self {
+		Self(Duration::from_millis(millis))
+	}
+}
+
+/// The [`RunTimer`] counts from spawn if the action has never stopped,
+/// so start it as already cooled down. If the entity already has a
+/// [`RunTimer`] it is left unchanged.
+fn cooled_down_timer() -> RunTimer {
+	let mut timer = RunTimer::default();
+	// large enough for any cooldown, small enough to keep ticking
+	timer.last_stopped.set_elapsed(Duration::MAX / 2);
+	timer
+}
+
+fn cooldown_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	query: Query<(&Cooldown, &RunTimer, Option<&Children>)>,
+) {
+	let (cooldown, timer, children) = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	if timer.last_stopped.elapsed() < cooldown.0 {
+		// not inserting Running here means the
+		// cooldown is not reset by this failure
+		ev.trigger_result(&mut commands, RunResult::Failure);
+		return;
+	}
+	// the cooldown starts when Running is removed by the result
+	commands.entity(ev.action).insert(Running::new(ev.origin));
+	if let Some(child) = children.and_then(|children| children.iter().next()) {
+		ev.trigger_next(&mut commands, child);
+	} else {
+		ev.trigger_result(&mut commands, RunResult::Success);
+	}
+}
+
+fn cooldown_on_child_result(ev: Trigger<OnChildResult>, commands: Commands) {
+	ev.trigger_bubble(commands);
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let on_result = observe_triggers::<OnResultAction>(world);
+
+		let action = world
+			.spawn(Cooldown::from_secs(2))
+			.with_child(ReturnWith(RunResult::Success))
+			.id();
+		let child = world.entity(action).get::<Children>().unwrap()[0];
+
+		let run = |app: &mut App| {
+			app.world_mut()
+				.entity_mut(action)
+				.flush_trigger(OnRun::local());
+		};
+
+		// first run is not on cooldown
+		run(&mut app);
+		expect(&on_result).to_have_returned_nth_with(
+			1,
+			&OnResultAction::new(action, action, RunResult::Success),
+		);
+		app.update_with_secs(1);
+		run(&mut app);
+		expect(&on_result).to_have_been_called_times(3);
+		expect(&on_result).to_have_returned_nth_with(
+			2,
+			&OnResultAction::new(action, action, RunResult::Failure),
+		);
+		// failing does not reset the cooldown
+		app.update_with_secs(1);
+		run(&mut app);
+		expect(&on_result).to_have_returned_nth_with(
+			3,
+			&OnResultAction::new(child, action, RunResult::Success),
+		);
+		expect(&on_result).to_have_returned_nth_with(
+			4,
+			&OnResultAction::new(action, action, RunResult::Success),
+		);
+	}
+}
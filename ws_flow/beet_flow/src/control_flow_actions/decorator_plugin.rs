@@ -0,0 +1,17 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Registers the systems and types required for the decorator actions
+/// [`Cooldown`], [`Guard`], [`Invert`], [`Retry`] and [`Timeout`].
+pub fn decorator_plugin(app: &mut App) {
+	app.add_systems(
+		TickSchedule::get(app),
+		// timed actions must be after tick_run_timers
+		(timeout, guard).after(tick_run_timers).in_set(TickSet),
+	)
+	.register_type::<Cooldown>()
+	.register_type::<Guard>()
+	.register_type::<Invert>()
+	.register_type::<Retry>()
+	.register_type::<Timeout>();
+}
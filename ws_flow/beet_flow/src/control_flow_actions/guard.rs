@@ -0,0 +1,258 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Like [`RunIfBlackboard`] but the conditions are checked again every tick
+/// while running. If a condition stops passing the guard fails and
+/// its running children are interrupted, unless they have [`NoInterrupt`].
+///
+/// Conditions can check either a [`Blackboard`] value or a reflected
+/// component field on the [`origin`](OnRun::origin),
+/// see [`ComponentPredicate`].
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// - [LongRunning](ActionTag::LongRunning)
+/// ## Logic
+/// - If any condition fails when run it will fail without running any children.
+/// - Otherwise it will run the first child and bubble its result.
+/// - If any condition fails while running it will fail.
+/// - If there are no children it will keep running until a condition fails.
+/// ## Example
+/// Chase the target for as long as it is visible.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn((
+/// 		Blackboard::default().with("target_visible", true),
+/// 		Guard::new("target_visible", BlackboardPredicate::Equals(true.into())),
+/// 	))
+/// 	.with_child((
+/// 		Name::new("Chase"),
+/// 		ReturnInDuration::with_secs(RunResult::Success, 10),
+/// 	))
+/// 	.trigger(OnRun::local());
+/// ```
+/// Flee until health recovers, `Health` must be registered
+/// with `#[reflect(Component)]`.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// #[derive(Component, Reflect)]
+/// #[reflect(Component)]
+/// struct Health(f32);
+///
+/// let agent = world.spawn(Health(3.)).id();
+/// let guard = world
+/// 	.spawn(Guard::component(ComponentPredicate::new::<Health>(
+/// 		".0",
+/// 		BlackboardPredicate::LessThan(5.),
+/// 	)))
+/// 	.with_child((
+/// 		Name::new("Flee"),
+/// 		ReturnInDuration::with_secs(RunResult::Success, 10),
+/// 	))
+/// 	.id();
+/// world.trigger(OnRunAction::new(guard, agent, ()));
+/// ```
+#[action(guard_on_run, guard_on_child_result)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(ContinueRun)]
+pub struct Guard {
+	/// All conditions must pass for the child to keep running.
+	pub conditions: Vec<BlackboardCondition>,
+	/// All component predicates must pass against the
+	/// [`origin`](OnRun::origin) for the child to keep running.
+	pub components: Vec<ComponentPredicate>,
+}
+
+impl Guard {
+	/// Create a new guard with a single condition.
+	pub fn new(key: impl Into<String>, predicate: BlackboardPredicate) -> Self {
+		Self {
+			conditions: vec![BlackboardCondition {
+				key: key.into(),
+				predicate,
+			}],
+			components: Vec::new(),
+		}
+	}
+	/// Create a new guard with a single component predicate.
+	pub fn component(predicate: ComponentPredicate) -> Self {
+		Self {
+			conditions: Vec::new(),
+			components: vec![predicate],
+		}
+	}
+	/// Add another condition that must pass.
+	pub fn and(
+		mut self,
+		key: impl Into<String>,
+		predicate: BlackboardPredicate,
+	) -> Self {
+		self.conditions.push(BlackboardCondition {
+			key: key.into(),
+			predicate,
+		});
+		self
+	}
+	/// Add a component predicate that must pass.
+	pub fn and_component(mut self, predicate: ComponentPredicate) -> Self {
+		self.components.push(predicate);
+		self
+	}
+
+	fn passes(
+		&self,
+		world: &World,
+		blackboards: &BlackboardQuery,
+		action: Entity,
+		origin: Entity,
+	) -> bool {
+		self.conditions
+			.iter()
+			.all(|condition| condition.passes(blackboards, action, origin))
+			&& self
+				.components
+				.iter()
+				.all(|predicate| predicate.passes(world, origin))
+	}
+}
+
+fn guard_on_run(
+	ev: Trigger<OnRun>,
+	world: &World,
+	mut commands: Commands,
+	query: Query<(&Guard, Option<&Children>)>,
+	blackboards: BlackboardQuery,
+) {
+	let (guard, children) = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	if !guard.passes(world, &blackboards, ev.action, ev.origin) {
+		ev.trigger_result(&mut commands, RunResult::Failure);
+	} else if let Some(child) =
+		children.and_then(|children| children.iter().next())
+	{
+		ev.trigger_next(&mut commands, child);
+	}
+}
+
+fn guard_on_child_result(ev: Trigger<OnChildResult>, commands: Commands) {
+	ev.trigger_bubble(commands);
+}
+
+/// Fail any running [`Guard`] whose conditions no longer pass.
+pub(crate) fn guard(
+	world: &World,
+	mut commands: Commands,
+	query: Populated<(Entity, &Running, &Guard)>,
+	blackboards: BlackboardQuery,
+) {
+	for (entity, running, guard) in query.iter() {
+		if !guard.passes(world, &blackboards, entity, running.origin) {
+			running.trigger_result(&mut commands, entity, RunResult::Failure);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn fails_on_run() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		world
+			.spawn((
+				Name::new("root"),
+				Guard::new("enemy", BlackboardPredicate::Missing),
+				Blackboard::default().with("enemy", true),
+			))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Success)))
+			.flush_trigger(OnRun::local());
+
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Failure)]);
+	}
+
+	#[test]
+	fn interrupts() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let agent = world.spawn(Blackboard::default().with("health", 10)).id();
+		let mut child = Entity::PLACEHOLDER;
+		let action = world
+			.spawn((
+				Name::new("root"),
+				Guard::new("health", BlackboardPredicate::GreaterThan(5.)),
+			))
+			.with_children(|parent| {
+				child = parent
+					.spawn((
+						Name::new("child"),
+						ReturnInDuration::with_secs(RunResult::Success, 10),
+					))
+					.id();
+			})
+			.id();
+		world.flush_trigger(OnRunAction::new(action, agent, ()));
+		app.update_with_secs(1);
+
+		expect(on_result()).to_be(vec![]);
+		expect(app.world().get::<Running>(child)).to_be_some();
+
+		app.world_mut()
+			.get_mut::<Blackboard>(agent)
+			.unwrap()
+			.set("health", 1);
+		app.update_with_secs(1);
+
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Failure)]);
+		expect(app.world().get::<Running>(child)).to_be_none();
+	}
+
+	#[derive(Component, Reflect)]
+	#[reflect(Component)]
+	struct Health(i32);
+
+	#[test]
+	fn component() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default())
+			.register_type::<Health>()
+			.insert_time();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let agent = world.spawn(Health(10)).id();
+		let action = world
+			.spawn((
+				Name::new("root"),
+				Guard::component(ComponentPredicate::new::<Health>(
+					".0",
+					BlackboardPredicate::GreaterThan(5.),
+				)),
+			))
+			.with_child(ReturnInDuration::with_secs(RunResult::Success, 10))
+			.id();
+		world.flush_trigger(OnRunAction::new(action, agent, ()));
+		app.update_with_secs(1);
+		expect(on_result()).to_be(vec![]);
+
+		app.world_mut().get_mut::<Health>(agent).unwrap().0 = 1;
+		app.update_with_secs(1);
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Failure)]);
+	}
+}
@@ -0,0 +1,72 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Runs its first child and bubbles up the opposite result.
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// ## Logic
+/// - If the child succeeds it will fail.
+/// - If the child fails it will succeed.
+/// - If there are no children it will fail, the inverse of an empty [`Sequence`].
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn(Invert)
+/// 	.with_child(ReturnWith(RunResult::Failure))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(invert_on_run, invert_on_child_result)]
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct Invert;
+
+fn invert_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	query: Query<&Children>,
+) {
+	if let Some(child) = query
+		.get(ev.action)
+		.ok()
+		.and_then(|children| children.iter().next())
+	{
+		ev.trigger_next(&mut commands, child);
+	} else {
+		ev.trigger_result(&mut commands, RunResult::Failure);
+	}
+}
+
+fn invert_on_child_result(ev: Trigger<OnChildResult>, commands: Commands) {
+	ev.trigger_bubble_with(commands, !ev.payload.clone());
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		world
+			.spawn((Name::new("root"), Invert))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Success)))
+			.flush_trigger(OnRun::local());
+		world
+			.spawn((Name::new("empty"), Invert))
+			.flush_trigger(OnRun::local());
+
+		expect(on_result()).to_be(vec![
+			("child".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Failure),
+			("empty".to_string(), RunResult::Failure),
+		]);
+	}
+}
@@ -15,6 +15,7 @@ use std::borrow::Cow;
 /// ```
 #[action(log_on_run)]
 #[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
 pub struct LogOnRun(pub Cow<'static, str>);
 
 impl LogOnRun {
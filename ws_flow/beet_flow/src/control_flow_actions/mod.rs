@@ -1,24 +1,36 @@
 //! A collection of built-in actions for controlling the flow of a tree.
 //! If you think that a missing action should be built-in, please open an issue.
 mod bubble_result;
+mod cooldown;
+mod decorator_plugin;
 mod fallback;
+mod guard;
 mod highest_score;
+mod invert;
 mod log_name_on_run;
 mod log_on_run;
 mod parallel;
 mod repeat;
+mod retry;
 mod return_with;
 mod run_next;
 mod sequence;
+mod timeout;
 pub use bubble_result::*;
+pub use cooldown::*;
+pub use decorator_plugin::*;
 pub use fallback::*;
+pub use guard::*;
 pub use highest_score::*;
+pub use invert::*;
 pub use log_name_on_run::*;
 pub use log_on_run::*;
 pub use parallel::*;
 pub use repeat::*;
+pub use retry::*;
 pub use return_with::*;
 pub use run_next::*;
 pub use sequence::*;
+pub use timeout::*;
 mod succeed_times;
 pub use succeed_times::*;
@@ -2,12 +2,52 @@ use crate::prelude::*;
 use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 
-/// An action that runs all of its children in parallel.
+/// The number of children that must return a given result
+/// for a [`Parallel`] action to return that result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ParallelPolicy {
+	/// The policy is met as soon as a single child returns the result.
+	RequireOne,
+	/// The policy is met when every child returns the result.
+	RequireAll,
+	/// The policy is met when `n` children return the result.
+	/// This is clamped to the number of children, so `RequireN(0)`
+	/// is met immediately and values greater than the number
+	/// of children behave like [`RequireAll`](Self::RequireAll).
+	RequireN(usize),
+}
+
+impl ParallelPolicy {
+	/// The number of matching results required to meet this policy.
+	pub fn num_required(&self, num_children: usize) -> usize {
+		match self {
+			ParallelPolicy::RequireOne => 1.min(num_children),
+			ParallelPolicy::RequireAll => num_children,
+			ParallelPolicy::RequireN(n) => (*n).min(num_children),
+		}
+	}
+
+	/// Whether `count` matching results are enough to meet this policy.
+	pub fn is_met(&self, count: usize, num_children: usize) -> bool {
+		count >= self.num_required(num_children)
+	}
+}
+
+/// An action that runs all of its children in parallel,
+/// returning a result once the [`ParallelPolicy`] for that result is met.
 /// ## Tags
 /// - [ControlFlow](ActionTag::ControlFlow)
 /// ## Logic
-/// - If a child fails it will fail immediately.
-/// - If all children succeed it will succeed.
+/// - If the [`success_policy`](Self::success_policy) is met it will succeed.
+/// - If the [`failure_policy`](Self::failure_policy) is met it will fail.
+/// - If every child has returned and neither policy is met it will fail.
+/// - Once a result is returned any further child results are ignored until the next run.
+///
+/// The default policy will fail if any child fails, and succeed when all children succeed.
+///
+/// Returning a result will interrupt any children that are still running,
+/// removing their [`Running`] component and resetting their [`RunTimer::last_stopped`].
+/// Add [`NoInterrupt`] to a child to let it keep running.
 /// ## Example
 /// Run two children in parallel
 /// ```
@@ -19,11 +59,111 @@ use bevy::prelude::*;
 ///		.with_child(ReturnWith(RunResult::Success))
 ///		.trigger(OnRun::local());
 /// ```
+/// Succeed as soon as any child succeeds
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+///		.spawn(Parallel::new(
+///			ParallelPolicy::RequireOne,
+///			ParallelPolicy::RequireAll,
+///		))
+///		.with_child(ReturnWith(RunResult::Failure))
+///		.with_child(ReturnWith(RunResult::Success))
+///		.trigger(OnRun::local());
+/// ```
 #[action(on_start, on_next)]
-#[derive(Default, Component, Deref, DerefMut, Reflect)]
+#[derive(Debug, Clone, Component, Reflect)]
 #[reflect(Default, Component)]
 // TODO sparseset
-pub struct Parallel(pub HashSet<Entity>);
+pub struct Parallel {
+	/// The number of children that must succeed for this action to succeed.
+	pub success_policy: ParallelPolicy,
+	/// The number of children that must fail for this action to fail.
+	pub failure_policy: ParallelPolicy,
+	/// Children that have succeeded during the current run.
+	#[reflect(ignore)]
+	successes: HashSet<Entity>,
+	/// Children that have failed during the current run.
+	#[reflect(ignore)]
+	failures: HashSet<Entity>,
+	/// Whether a result has been returned for the current run.
+	#[reflect(ignore)]
+	completed: bool,
+}
+
+impl Default for Parallel {
+	fn default() -> Self {
+		Self::new(ParallelPolicy::RequireAll, ParallelPolicy::RequireOne)
+	}
+}
+
+impl Parallel {
+	/// Specify the success and failure policies.
+	pub fn new(
+		success_policy: ParallelPolicy,
+		failure_policy: ParallelPolicy,
+	) -> Self {
+		Self {
+			success_policy,
+			failure_policy,
+			successes: default(),
+			failures: default(),
+			completed: false,
+		}
+	}
+
+	/// Children that have succeeded during the current run.
+	pub fn successes(&self) -> &HashSet<Entity> { &self.successes }
+	/// Children that have failed during the current run.
+	pub fn failures(&self) -> &HashSet<Entity> { &self.failures }
+
+	fn reset(&mut self) {
+		self.successes.clear();
+		self.failures.clear();
+		self.completed = false;
+	}
+
+	/// Record the result of a child, returning the result of this action
+	/// if either policy has been met.
+	fn record(
+		&mut self,
+		child: Entity,
+		result: &RunResult,
+		num_children: usize,
+	) -> Option<RunResult> {
+		if self.completed {
+			return None;
+		}
+		match result {
+			RunResult::Success => {
+				self.failures.remove(&child);
+				self.successes.insert(child);
+			}
+			RunResult::Failure => {
+				self.successes.remove(&child);
+				self.failures.insert(child);
+			}
+		}
+		let result = if self
+			.success_policy
+			.is_met(self.successes.len(), num_children)
+		{
+			Some(RunResult::Success)
+		} else if self
+			.failure_policy
+			.is_met(self.failures.len(), num_children)
+			|| self.successes.len() + self.failures.len() >= num_children
+		{
+			// failure policy met, or all children returned without success
+			Some(RunResult::Failure)
+		} else {
+			None
+		};
+		self.completed = result.is_some();
+		result
+	}
+}
 
 fn on_start(
 	ev: Trigger<OnRun>,
@@ -33,7 +173,7 @@ fn on_start(
 	let (mut action, children) = query
 		.get_mut(ev.action)
 		.expect(&expect_action::to_have_children(&ev));
-	action.clear();
+	action.reset();
 
 	for child in children {
 		ev.trigger_next(&mut commands.reborrow(), *child);
@@ -46,18 +186,14 @@ fn on_next(
 	commands: Commands,
 	mut query: Query<(&mut Parallel, &Children)>,
 ) {
-	if ev.payload == RunResult::Failure {
-		ev.trigger_bubble(commands);
-		return;
-	}
 	let (mut action, children) = query
 		.get_mut(ev.parent)
 		.expect(&expect_action::to_have_action(&ev));
-	action.insert(ev.child);
 
-	if action.len() == children.iter().len() {
-		ev.trigger_bubble(commands);
-		return;
+	if let Some(result) =
+		action.record(ev.child, &ev.payload, children.iter().len())
+	{
+		ev.trigger_bubble_with(commands, result);
 	}
 }
 
@@ -113,4 +249,196 @@ mod test {
 			&OnResultAction::global(action, RunResult::Success),
 		);
 	}
+
+	#[test]
+	fn policy() {
+		use ParallelPolicy::*;
+		let num_children = 4;
+		expect(RequireOne.num_required(num_children)).to_be(1);
+		expect(RequireAll.num_required(num_children)).to_be(4);
+		expect(RequireN(2).num_required(num_children)).to_be(2);
+		expect(RequireN(7).num_required(num_children)).to_be(4);
+		expect(RequireN(2).is_met(1, num_children)).to_be_false();
+		expect(RequireN(2).is_met(2, num_children)).to_be_true();
+	}
+
+	fn run_parallel(
+		parallel: Parallel,
+		results: Vec<RunResult>,
+	) -> Vec<(String, RunResult)> {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		world
+			.spawn((Name::new("root"), parallel))
+			.with_children(|parent| {
+				for (index, result) in results.into_iter().enumerate() {
+					parent.spawn((
+						Name::new(format!("child{}", index + 1)),
+						ReturnWith(result),
+					));
+				}
+			})
+			.flush_trigger(OnRun::local());
+		on_result()
+	}
+
+	#[test]
+	fn require_one_success() {
+		expect(run_parallel(
+			Parallel::new(
+				ParallelPolicy::RequireOne,
+				ParallelPolicy::RequireAll,
+			),
+			vec![RunResult::Failure, RunResult::Success, RunResult::Failure],
+		))
+		.to_be(vec![
+			("child1".to_string(), RunResult::Failure),
+			("child2".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+			// ignored after completion
+			("child3".to_string(), RunResult::Failure),
+		]);
+	}
+
+	#[test]
+	fn require_all_failure() {
+		expect(run_parallel(
+			Parallel::new(
+				ParallelPolicy::RequireOne,
+				ParallelPolicy::RequireAll,
+			),
+			vec![RunResult::Failure, RunResult::Failure],
+		))
+		.to_be(vec![
+			("child1".to_string(), RunResult::Failure),
+			("child2".to_string(), RunResult::Failure),
+			("root".to_string(), RunResult::Failure),
+		]);
+	}
+
+	#[test]
+	fn require_n() {
+		let parallel = || {
+			Parallel::new(
+				ParallelPolicy::RequireN(2),
+				ParallelPolicy::RequireN(2),
+			)
+		};
+		expect(run_parallel(parallel(), vec![
+			RunResult::Success,
+			RunResult::Failure,
+			RunResult::Success,
+			RunResult::Failure,
+		]))
+		.to_be(vec![
+			("child1".to_string(), RunResult::Success),
+			("child2".to_string(), RunResult::Failure),
+			("child3".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+			("child4".to_string(), RunResult::Failure),
+		]);
+		expect(run_parallel(parallel(), vec![
+			RunResult::Failure,
+			RunResult::Success,
+			RunResult::Failure,
+			RunResult::Success,
+		]))
+		.to_be(vec![
+			("child1".to_string(), RunResult::Failure),
+			("child2".to_string(), RunResult::Success),
+			("child3".to_string(), RunResult::Failure),
+			("root".to_string(), RunResult::Failure),
+			("child4".to_string(), RunResult::Success),
+		]);
+	}
+
+	#[test]
+	fn fails_if_undecided() {
+		expect(run_parallel(
+			Parallel::new(
+				ParallelPolicy::RequireAll,
+				ParallelPolicy::RequireAll,
+			),
+			vec![RunResult::Success, RunResult::Failure],
+		))
+		.to_be(vec![
+			("child1".to_string(), RunResult::Success),
+			("child2".to_string(), RunResult::Failure),
+			("root".to_string(), RunResult::Failure),
+		]);
+	}
+
+	#[test]
+	fn interrupts_running() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let mut long_running = Entity::PLACEHOLDER;
+		let mut no_interrupt = Entity::PLACEHOLDER;
+		world
+			.spawn((
+				Name::new("root"),
+				Parallel::new(
+					ParallelPolicy::RequireOne,
+					ParallelPolicy::RequireOne,
+				),
+			))
+			.with_children(|parent| {
+				long_running = parent
+					.spawn((
+						Name::new("child1"),
+						ReturnInDuration::with_secs(RunResult::Failure, 10),
+					))
+					.id();
+				no_interrupt = parent
+					.spawn((
+						Name::new("child2"),
+						NoInterrupt,
+						ReturnInDuration::with_secs(RunResult::Failure, 10),
+					))
+					.id();
+				parent.spawn((
+					Name::new("child3"),
+					ReturnInDuration::with_secs(RunResult::Success, 1),
+				));
+			})
+			.flush_trigger(OnRun::local());
+
+		let world = app.world();
+		expect(world.get::<Running>(long_running)).to_be_some();
+		expect(world.get::<Running>(no_interrupt)).to_be_some();
+
+		app.update_with_secs(2);
+		app.world_mut().flush();
+
+		let world = app.world();
+		expect(world.get::<Running>(long_running)).to_be_none();
+		expect(
+			world
+				.get::<RunTimer>(long_running)
+				.unwrap()
+				.last_stopped
+				.elapsed_secs(),
+		)
+		.to_be(0.);
+		expect(world.get::<Running>(no_interrupt)).to_be_some();
+		expect(on_result()).to_be(vec![
+			("child3".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+
+		// later results are ignored
+		app.update_with_secs(10);
+		app.world_mut().flush();
+		expect(on_result()).to_be(vec![
+			("child3".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+			("child2".to_string(), RunResult::Failure),
+		]);
+	}
 }
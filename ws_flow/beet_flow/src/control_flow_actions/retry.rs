@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Runs its first child, running it again if it fails,
+/// up to [`Self::max`] additional times.
+/// Unlike [`Repeat`], retries happen immediately instead of on the next tick.
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// ## Logic
+/// - If the child succeeds it will succeed.
+/// - If the child fails it will run it again, unless there are no retries left
+/// 	in which case it will fail.
+/// - If there are no children it will fail.
+/// ## Example
+/// Attempt to open a door three times in total.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn(Retry::new(2))
+/// 	.with_child((Name::new("Open Door"), ReturnWith(RunResult::Failure)))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(retry_on_run, retry_on_child_result)]
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct Retry {
+	/// The maximum number of times the child will be run again after failing.
+	pub max: usize,
+	/// The number of retries in the current run.
+	#[reflect(ignore)]
+	retries: usize,
+}
+
+impl Retry {
+	/// Specify the maximum number of retries.
+	pub fn new(max: usize) -> Self { Self { max, retries: 0 } }
+	/// The number of retries in the current run.
+	pub fn retries(&self) -> usize { self.retries }
+}
+
+fn retry_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	mut query: Query<(&mut Retry, Option<&Children>)>,
+) {
+	let (mut retry, children) = query
+		.get_mut(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	retry.retries = 0;
+	if let Some(child) = children.and_then(|children| children.iter().next()) {
+		ev.trigger_next(&mut commands, child);
+	} else {
+		ev.trigger_result(&mut commands, RunResult::Failure);
+	}
+}
+
+fn retry_on_child_result(
+	ev: Trigger<OnChildResult>,
+	commands: Commands,
+	mut query: Query<&mut Retry>,
+) {
+	let mut retry = query
+		.get_mut(ev.parent)
+		.expect(&expect_action::to_have_action(&ev));
+	if ev.payload == RunResult::Failure && retry.retries < retry.max {
+		retry.retries += 1;
+		ev.trigger_run(commands, ev.child, ());
+	} else {
+		ev.trigger_bubble(commands);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn retries() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let action = world
+			.spawn((Name::new("root"), Retry::new(2)))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Failure)))
+			.flush_trigger(OnRun::local())
+			.id();
+
+		expect(on_result()).to_be(vec![
+			("child".to_string(), RunResult::Failure),
+			("child".to_string(), RunResult::Failure),
+			("child".to_string(), RunResult::Failure),
+			("root".to_string(), RunResult::Failure),
+		]);
+		expect(world.get::<Retry>(action).unwrap().retries()).to_be(2);
+	}
+
+	#[test]
+	fn succeeds() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		world
+			.spawn((Name::new("root"), Retry::new(5)))
+			.with_children(|parent| {
+				// fails twice, then succeeds
+				parent.spawn((Name::new("child"), Invert)).with_child((
+					Name::new("grandchild"),
+					SucceedTimes::new(2),
+				));
+			})
+			.flush_trigger(OnRun::local());
+
+		expect(on_result()).to_be(vec![
+			("grandchild".to_string(), RunResult::Success),
+			("child".to_string(), RunResult::Failure),
+			("grandchild".to_string(), RunResult::Success),
+			("child".to_string(), RunResult::Failure),
+			("grandchild".to_string(), RunResult::Failure),
+			("child".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+	}
+}
@@ -18,7 +18,8 @@ use bevy::prelude::*;
 /// 	.trigger(OnRun::local());
 /// ```
 #[action(return_with::<T>)]
-#[derive(Debug, Component, PartialEq, Eq)]
+#[derive(Debug, Component, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
 pub struct ReturnWith<T: ResultPayload>(pub T);
 
 fn return_with<T: ResultPayload>(
@@ -0,0 +1,132 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Runs its first child, failing if it has not returned a result
+/// within the given duration. When the timeout is reached any running
+/// children are interrupted, unless they have [`NoInterrupt`].
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// - [LongRunning](ActionTag::LongRunning)
+/// ## Logic
+/// - If the child returns in time its result is bubbled up.
+/// - If the duration elapses first it will fail.
+/// - If there are no children it will fail when the duration elapses.
+/// ## Example
+/// Give up on a wander after 5 seconds.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # use std::time::Duration;
+/// # let mut world = world();
+/// world
+/// 	.spawn(Timeout(Duration::from_secs(5)))
+/// 	.with_child((
+/// 		Name::new("Wander"),
+/// 		ReturnInDuration::with_secs(RunResult::Success, 10),
+/// 	))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(timeout_on_run, timeout_on_child_result)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(ContinueRun)]
+pub struct Timeout(pub Duration);
+
+impl Timeout {
+	/// Specify the timeout in seconds.
+	pub fn from_secs(secs: u64) -> Self { Self(Duration::from_secs(secs)) }
+	/// Specify the timeout in milliseconds.
+	pub fn from_millis(millis: u64) -> Self {
+		Self(Duration::from_millis(millis))
+	}
+}
+
+fn timeout_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	query: Query<&Children>,
+) {
+	if let Some(child) = query
+		.get(ev.action)
+		.ok()
+		.and_then(|children| children.iter().next())
+	{
+		ev.trigger_next(&mut commands, child);
+	}
+}
+
+fn timeout_on_child_result(ev: Trigger<OnChildResult>, commands: Commands) {
+	ev.trigger_bubble(commands);
+}
+
+/// Fail any [`Timeout`] that has been running for longer than its duration.
+pub(crate) fn timeout(
+	mut commands: Commands,
+	query: Populated<(Entity, &Running, &RunTimer, &Timeout)>,
+) {
+	for (entity, running, timer, timeout) in query.iter() {
+		if timer.last_started.elapsed() >= timeout.0 {
+			running.trigger_result(&mut commands, entity, RunResult::Failure);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn child_returns() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		world
+			.spawn((Name::new("root"), Timeout::from_secs(2)))
+			.with_child((
+				Name::new("child"),
+				ReturnInDuration::with_secs(RunResult::Success, 1),
+			))
+			.flush_trigger(OnRun::local());
+		app.update_with_secs(1);
+		app.update_with_secs(2);
+
+		expect(on_result()).to_be(vec![
+			("child".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+	}
+
+	#[test]
+	fn times_out() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let mut child = Entity::PLACEHOLDER;
+		world
+			.spawn((Name::new("root"), Timeout::from_secs(1)))
+			.with_children(|parent| {
+				child = parent
+					.spawn((
+						Name::new("child"),
+						ReturnInDuration::with_secs(RunResult::Success, 2),
+					))
+					.id();
+			})
+			.flush_trigger(OnRun::local());
+		app.update_with_secs(1);
+
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Failure)]);
+		expect(app.world().get::<Running>(child)).to_be_none();
+
+		app.update_with_secs(2);
+		expect(on_result())
+			.to_be(vec![("root".to_string(), RunResult::Failure)]);
+	}
+}
@@ -4,9 +4,14 @@
 #![doc = include_str!("../README.md")]
 #[cfg(feature = "bevy_default")]
 pub mod asset_actions;
+#[cfg(feature = "scene")]
+pub mod behavior_tree;
+pub mod blackboard;
 pub mod continue_run;
 pub mod control_flow;
 pub mod control_flow_actions;
+pub mod state_machine;
+pub mod trace;
 pub mod tree;
 #[allow(unused, reason = "docs")]
 use crate::prelude::*;
@@ -17,13 +22,18 @@ use bevy::app::PluginGroupBuilder;
 pub mod prelude {
 	#[cfg(feature = "bevy_default")]
 	pub use crate::asset_actions::*;
+	#[cfg(feature = "scene")]
+	pub use crate::behavior_tree::*;
 	// required for macros to work internally
 	pub use super::ActionTag;
 	pub use super::BeetFlowPlugin;
 	pub use crate as beet_flow;
+	pub use crate::blackboard::*;
 	pub use crate::continue_run::*;
 	pub use crate::control_flow::*;
 	pub use crate::control_flow_actions::*;
+	pub use crate::state_machine::*;
+	pub use crate::trace::*;
 	pub use crate::tree::*;
 	pub use beet_flow_macros::*;
 }
@@ -52,21 +62,42 @@ pub mod doctest {
 /// observer router, ensuring the OnRun and OnResult events are propagated
 /// correctly.
 /// - [control_flow::control_flow_plugin]
+/// - [control_flow::DeterministicPlugin], if [`Self::deterministic`]
+/// - [blackboard::blackboard_plugin]
+/// - [control_flow_actions::decorator_plugin]
 /// - [continue_run::continue_run_plugin]
+/// - [state_machine::state_machine_plugin]
 #[derive(Default)]
 pub struct BeetFlowPlugin {
 	// lifecycle_plugin: lifecycle::LifecyclePlugin,
+	deterministic: Option<DeterministicPlugin>,
 }
 
-impl BeetFlowPlugin {}
+impl BeetFlowPlugin {
+	/// Run beet systems in [`FixedUpdate`](bevy::prelude::FixedUpdate)
+	/// with a seeded [`RandomSource`](sweet::prelude::RandomSource),
+	/// see [`DeterministicPlugin`].
+	/// This should be added before any other beet plugins.
+	pub fn deterministic(seed: u64) -> Self {
+		Self {
+			deterministic: Some(DeterministicPlugin::new(seed)),
+		}
+	}
+}
 
 
 impl PluginGroup for BeetFlowPlugin {
 	fn build(self) -> PluginGroupBuilder {
-		PluginGroupBuilder::start::<Self>()
+		let mut builder = PluginGroupBuilder::start::<Self>();
+		if let Some(deterministic) = self.deterministic {
+			builder = builder.add(deterministic);
+		}
+		builder
 			.add(control_flow::control_flow_plugin)
+			.add(blackboard::blackboard_plugin)
+			.add(control_flow_actions::decorator_plugin)
 			.add(continue_run::continue_run_plugin)
-			.build()
+			.add(state_machine::state_machine_plugin)
 	}
 }
 
@@ -95,4 +126,7 @@ pub enum ActionTag {
 	/// This action is concerned with providing output to the user or
 	/// receiving input.
 	InputOutput,
+	/// Actions that respond to a [RequestScore] with a [ScoreValue],
+	/// used by selectors like [HighestScore].
+	Scoring,
 }
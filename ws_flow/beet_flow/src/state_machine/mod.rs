@@ -0,0 +1,16 @@
+//! Finite state machines that can be nested in any tree,
+//! see [`StateMachine`].
+mod state_machine;
+mod transition;
+use crate::prelude::*;
+use bevy::prelude::*;
+pub use state_machine::*;
+pub use transition::*;
+
+
+/// Registers systems and types required for [`StateMachine`].
+pub fn state_machine_plugin(app: &mut App) {
+	app.add_systems(TickSchedule::get(app), state_machine.in_set(TickSet))
+		.register_type::<StateMachine>()
+		.register_type::<Transition>();
+}
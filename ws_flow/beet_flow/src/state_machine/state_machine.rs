@@ -0,0 +1,410 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// A finite state machine where each child action is a state,
+/// and each child [`Transition`] moves between them.
+/// Entering a state triggers [`OnRun`] on it, and exiting a state
+/// removes [`Running`] from it and its descendants, unless they have a
+/// [`NoInterrupt`].
+/// Because states are regular actions a state can itself be
+/// a [`Sequence`], another [`StateMachine`] etc.
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// - [LongRunning](ActionTag::LongRunning)
+/// ## Logic
+/// - When run the first child that is not a [`Transition`] is entered.
+/// - While running the transitions from the current state are checked every tick,
+/// 	see [`TransitionCondition`].
+/// - When a state finishes the first matching [`TransitionCondition::OnResult`]
+/// 	is taken, if there is none the result is bubbled up and the machine finishes.
+/// - If there are no states it will fail.
+/// ## Example
+/// Patrol until an enemy is spotted, then chase until it is lost.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// let agent = world.spawn(Blackboard::default()).id();
+/// let machine = world.spawn(StateMachine::default()).id();
+/// let patrol = world
+/// 	.spawn((
+/// 		ChildOf(machine),
+/// 		Name::new("Patrol"),
+/// 		ReturnInDuration::with_secs(RunResult::Success, 10),
+/// 	))
+/// 	.id();
+/// let chase = world
+/// 	.spawn((
+/// 		ChildOf(machine),
+/// 		Name::new("Chase"),
+/// 		ReturnInDuration::with_secs(RunResult::Success, 10),
+/// 	))
+/// 	.id();
+/// world.spawn((
+/// 	ChildOf(machine),
+/// 	Transition::new(
+/// 		patrol,
+/// 		chase,
+/// 		TransitionCondition::Blackboard(BlackboardCondition {
+/// 			key: "enemy".into(),
+/// 			predicate: BlackboardPredicate::Exists,
+/// 		}),
+/// 	),
+/// ));
+/// world.spawn((
+/// 	ChildOf(machine),
+/// 	Transition::on_result(chase, patrol, RunResult::Failure),
+/// ));
+/// world.trigger(OnRunAction::new(machine, agent, ()));
+/// ```
+#[action(
+	state_machine_on_run,
+	state_machine_on_child_result,
+	state_machine_on_child_score
+)]
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(ContinueRun)]
+pub struct StateMachine {
+	/// The state that is currently running, if any.
+	#[reflect(ignore)]
+	current: Option<Entity>,
+}
+
+impl StateMachine {
+	/// The state that is currently running, if any.
+	pub fn current(&self) -> Option<Entity> { self.current }
+}
+
+fn state_machine_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	mut query: Query<(&mut StateMachine, Option<&Children>)>,
+	transitions: Query<(), With<Transition>>,
+) {
+	let (mut machine, children) = query
+		.get_mut(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	machine.current = children.and_then(|children| {
+		children.iter().find(|child| !transitions.contains(*child))
+	});
+	match machine.current {
+		Some(initial) => ev.trigger_next(&mut commands, initial),
+		None => ev.trigger_result(&mut commands, RunResult::Failure),
+	}
+}
+
+fn state_machine_on_child_result(
+	ev: Trigger<OnChildResult>,
+	commands: Commands,
+	mut query: Query<(&mut StateMachine, &Children)>,
+	transitions: Query<&Transition>,
+) {
+	let (mut machine, children) = query
+		.get_mut(ev.parent)
+		.expect(&expect_action::to_have_action(&ev));
+	if machine.current != Some(ev.child) {
+		// a result from a state that has already exited
+		return;
+	}
+	let condition = TransitionCondition::OnResult(ev.payload.clone());
+	let next = children
+		.iter()
+		.filter_map(|child| transitions.get(child).ok())
+		.find(|transition| {
+			transition.from == ev.child && transition.condition == condition
+		});
+	if let Some(next) = next {
+		machine.current = Some(next.to);
+		ev.trigger_run(commands, next.to, ());
+	} else {
+		machine.current = None;
+		ev.trigger_bubble(commands);
+	}
+}
+
+fn state_machine_on_child_score(
+	ev: Trigger<OnChildResult<ScoreValue>>,
+	mut commands: Commands,
+	transitions: Query<&Transition>,
+) {
+	let Ok(transition) = transitions.get(ev.child) else {
+		return;
+	};
+	if let TransitionCondition::Score(threshold) = transition.condition
+		&& ev.payload.0 >= threshold
+	{
+		queue_transition(
+			&mut commands,
+			ev.parent,
+			ev.origin,
+			transition.from,
+			transition.to,
+		);
+	}
+}
+
+/// Check the transitions from the current state of every running [`StateMachine`].
+pub(crate) fn state_machine(
+	world: &World,
+	mut commands: Commands,
+	query: Query<(Entity, &Running, &StateMachine, &Children)>,
+	transitions: Query<&Transition>,
+	blackboards: BlackboardQuery,
+) {
+	for (entity, running, machine, children) in query.iter() {
+		let Some(current) = machine.current else {
+			continue;
+		};
+		for child in children.iter() {
+			let Ok(transition) = transitions.get(child) else {
+				continue;
+			};
+			if transition.from != current {
+				continue;
+			}
+			let passes = match &transition.condition {
+				TransitionCondition::OnResult(_) => false,
+				TransitionCondition::Blackboard(condition) => {
+					condition.passes(&blackboards, child, running.origin)
+				}
+				TransitionCondition::Component(predicate) => {
+					predicate.passes(world, running.origin)
+				}
+				TransitionCondition::Score(_) => {
+					commands.trigger(OnRunAction::new(
+						child,
+						running.origin,
+						RequestScore,
+					));
+					false
+				}
+			};
+			if passes {
+				queue_transition(
+					&mut commands,
+					entity,
+					running.origin,
+					transition.from,
+					transition.to,
+				);
+				break;
+			}
+		}
+	}
+}
+
+/// Exit `from` and enter `to`, unless the machine has already
+/// left `from` by the time the command is applied.
+fn queue_transition(
+	commands: &mut Commands,
+	machine: Entity,
+	origin: Entity,
+	from: Entity,
+	to: Entity,
+) {
+	commands.queue(move |world: &mut World| {
+		let Some(mut state_machine) = world.get_mut::<StateMachine>(machine)
+		else {
+			return;
+		};
+		if state_machine.current != Some(from) {
+			return;
+		}
+		state_machine.current = Some(to);
+		for entity in EntityTree::new_with_world(from, world).flatten() {
+			let mut entity = world.entity_mut(entity);
+			if entity.contains::<Running>() && !entity.contains::<NoInterrupt>()
+			{
+				entity.remove::<Running>();
+			}
+		}
+		world.trigger(OnRunAction::new(to, origin, ()));
+	});
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	struct Setup {
+		agent: Entity,
+		machine: Entity,
+		idle: Entity,
+		walk: Entity,
+	}
+
+	fn setup(world: &mut World) -> Setup {
+		let agent = world.spawn(Blackboard::default()).id();
+		let machine = world
+			.spawn((Name::new("machine"), StateMachine::default()))
+			.id();
+		let idle = world
+			.spawn((
+				ChildOf(machine),
+				Name::new("idle"),
+				ReturnInDuration::with_secs(RunResult::Failure, 10),
+			))
+			.id();
+		let walk = world
+			.spawn((
+				ChildOf(machine),
+				Name::new("walk"),
+				ReturnInDuration::with_secs(RunResult::Success, 2),
+			))
+			.id();
+		Setup {
+			agent,
+			machine,
+			idle,
+			walk,
+		}
+	}
+
+	#[test]
+	fn on_result() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+		let Setup {
+			agent,
+			machine,
+			idle,
+			walk,
+		} = setup(world);
+		world.spawn((
+			ChildOf(machine),
+			Transition::on_result(idle, walk, RunResult::Failure),
+		));
+		world.flush_trigger(OnRunAction::new(machine, agent, ()));
+		expect(world.get::<Running>(idle)).to_be_some();
+
+		app.update_with_secs(10);
+		let world = app.world();
+		expect(world.get::<StateMachine>(machine).unwrap().current())
+			.to_be(Some(walk));
+		expect(world.get::<Running>(walk)).to_be_some();
+		expect(world.get::<Running>(machine)).to_be_some();
+
+		// no transition from walk so its result is bubbled
+		app.update_with_secs(2);
+		expect(on_result()).to_be(vec![
+			("idle".to_string(), RunResult::Failure),
+			("walk".to_string(), RunResult::Success),
+			("machine".to_string(), RunResult::Success),
+		]);
+		expect(app.world().get::<Running>(machine)).to_be_none();
+	}
+
+	#[test]
+	fn blackboard() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let Setup {
+			agent,
+			machine,
+			idle,
+			walk,
+		} = setup(world);
+		world.spawn((
+			ChildOf(machine),
+			Transition::new(
+				idle,
+				walk,
+				TransitionCondition::Blackboard(BlackboardCondition {
+					key: "target".into(),
+					predicate: BlackboardPredicate::Exists,
+				}),
+			),
+		));
+		world.flush_trigger(OnRunAction::new(machine, agent, ()));
+		app.update_with_secs(1);
+		expect(app.world().get::<Running>(idle)).to_be_some();
+
+		app.world_mut()
+			.get_mut::<Blackboard>(agent)
+			.unwrap()
+			.set("target", true);
+		app.update_with_secs(1);
+		let world = app.world();
+		// idle is interrupted on exit
+		expect(world.get::<Running>(idle)).to_be_none();
+		expect(world.get::<Running>(walk)).to_be_some();
+	}
+
+	#[test]
+	fn score() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default()).insert_time();
+		let world = app.world_mut();
+		let Setup {
+			agent,
+			machine,
+			idle,
+			walk,
+		} = setup(world);
+		let transition = world
+			.spawn((
+				ChildOf(machine),
+				Transition::new(idle, walk, TransitionCondition::Score(0.5)),
+				ReturnWith(ScoreValue(0.2)),
+			))
+			.id();
+		world.flush_trigger(OnRunAction::new(machine, agent, ()));
+		app.update_with_secs(1);
+		expect(app.world().get::<Running>(idle)).to_be_some();
+
+		app.world_mut()
+			.entity_mut(transition)
+			.insert(ReturnWith(ScoreValue(0.8)));
+		app.update_with_secs(1);
+		let world = app.world();
+		expect(world.get::<Running>(idle)).to_be_none();
+		expect(world.get::<StateMachine>(machine).unwrap().current())
+			.to_be(Some(walk));
+	}
+
+	#[test]
+	fn nested() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+		let root = world.spawn((Name::new("root"), Sequence)).id();
+		let machine = world
+			.spawn((
+				ChildOf(root),
+				Name::new("machine"),
+				StateMachine::default(),
+			))
+			.id();
+		let state1 = world
+			.spawn((
+				ChildOf(machine),
+				Name::new("state1"),
+				ReturnWith(RunResult::Success),
+			))
+			.id();
+		let state2 = world
+			.spawn((
+				ChildOf(machine),
+				Name::new("state2"),
+				ReturnWith(RunResult::Failure),
+			))
+			.id();
+		world.spawn((
+			ChildOf(machine),
+			Transition::on_result(state1, state2, RunResult::Success),
+		));
+		world.entity_mut(root).flush_trigger(OnRun::local());
+		expect(on_result()).to_be(vec![
+			("state1".to_string(), RunResult::Success),
+			("state2".to_string(), RunResult::Failure),
+			("machine".to_string(), RunResult::Failure),
+			("root".to_string(), RunResult::Failure),
+		]);
+	}
+}
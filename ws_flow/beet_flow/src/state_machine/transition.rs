@@ -0,0 +1,77 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// A transition between two states of a [`StateMachine`].
+/// Transitions are children of the state machine alongside its states,
+/// and are checked in order, the first passing transition from the
+/// current state is taken.
+///
+/// Like [`RunNext`], entity references are not remapped so transitions
+/// should not be used in tree assets.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// let machine = world.spawn(StateMachine::default()).id();
+/// let idle = world.spawn((ChildOf(machine), Name::new("Idle"))).id();
+/// let attack = world.spawn((ChildOf(machine), Name::new("Attack"))).id();
+/// world.spawn((
+/// 	ChildOf(machine),
+/// 	Transition::new(
+/// 		idle,
+/// 		attack,
+/// 		TransitionCondition::Blackboard(BlackboardCondition {
+/// 			key: "enemy".into(),
+/// 			predicate: BlackboardPredicate::Exists,
+/// 		}),
+/// 	),
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+pub struct Transition {
+	/// The state this transition leaves.
+	pub from: Entity,
+	/// The state this transition enters.
+	pub to: Entity,
+	/// The condition that must pass for the transition to be taken.
+	pub condition: TransitionCondition,
+}
+
+impl Transition {
+	/// Create a new transition between two states.
+	pub fn new(
+		from: Entity,
+		to: Entity,
+		condition: TransitionCondition,
+	) -> Self {
+		Self {
+			from,
+			to,
+			condition,
+		}
+	}
+	/// Create a transition taken when `from` finishes with the given result.
+	pub fn on_result(from: Entity, to: Entity, result: RunResult) -> Self {
+		Self::new(from, to, TransitionCondition::OnResult(result))
+	}
+}
+
+/// The condition for a [`Transition`] to be taken.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum TransitionCondition {
+	/// The current state finished with this result.
+	OnResult(RunResult),
+	/// Checked every tick while the state is running,
+	/// the blackboard is resolved from the transition entity.
+	Blackboard(BlackboardCondition),
+	/// Checked every tick while the state is running,
+	/// the component is read from the [`origin`](OnRun::origin).
+	Component(ComponentPredicate),
+	/// Every tick while the state is running the transition entity is run
+	/// with [`RequestScore`], and the transition is taken if the score
+	/// is greater than or equal to this threshold.
+	/// The transition entity must respond with a [`ScoreValue`],
+	/// ie with [`ReturnWith`] or [`ReadBlackboardIntoScore`].
+	Score(f32),
+}
@@ -0,0 +1,260 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Tracks the [`LastRunResult`] of every action so that it can be
+/// included in a [`FlowSnapshot`].
+#[derive(Debug, Default, Clone)]
+pub struct FlowSnapshotPlugin;
+
+impl Plugin for FlowSnapshotPlugin {
+	fn build(&self, app: &mut App) {
+		app.register_type::<LastRunResult>()
+			.add_observer(insert_last_run_result);
+	}
+}
+
+/// The most recent [`RunResult`] of an action, added by the
+/// [`FlowSnapshotPlugin`].
+#[derive(Debug, Clone, PartialEq, Eq, Deref, Component, Reflect)]
+#[reflect(Component)]
+pub struct LastRunResult(pub RunResult);
+
+fn insert_last_run_result(ev: Trigger<OnResultAction>, mut commands: Commands) {
+	commands
+		.entity(ev.resolve_action())
+		.insert(LastRunResult(ev.payload.clone()));
+}
+
+/// The state of a single action and its descendants at the time
+/// a [`FlowSnapshot`] was taken.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionSnapshot {
+	/// The action entity.
+	pub entity: Entity,
+	/// The [`Name`] of the action if it has one.
+	pub name: Option<String>,
+	/// The short type names of each action component, ie `Sequence`.
+	pub actions: Vec<String>,
+	/// The [`Running::origin`] if the action is currently running.
+	pub running: Option<Entity>,
+	/// The [`LastRunResult`] if the action has one.
+	pub last_result: Option<RunResult>,
+	/// Seconds since [`RunTimer::last_started`], if the action has one.
+	pub last_started: Option<f32>,
+	/// Seconds since [`RunTimer::last_stopped`], if the action has one.
+	pub last_stopped: Option<f32>,
+	/// The child actions, in order.
+	pub children: Vec<ActionSnapshot>,
+}
+
+impl ActionSnapshot {
+	/// Create a snapshot of the action and all of its descendant actions.
+	pub fn new(world: &World, entity: Entity) -> Self {
+		let entity_ref = world.entity(entity);
+		let observer_map = world.resource::<ActionObserverMap>();
+		let actions = entity_ref
+			.archetype()
+			.components()
+			.filter(|id| observer_map.contains_key(id))
+			.filter_map(|id| world.components().get_info(id))
+			.map(|info| short_type_name(info.name()))
+			.collect();
+		let timer = entity_ref.get::<RunTimer>();
+		let children = entity_ref
+			.get::<Children>()
+			.map(|children| {
+				children
+					.iter()
+					.filter(|child| {
+						world.get::<ActionObservers>(*child).is_some()
+					})
+					.map(|child| Self::new(world, child))
+					.collect()
+			})
+			.unwrap_or_default();
+		Self {
+			entity,
+			name: entity_ref.get::<Name>().map(|name| name.to_string()),
+			actions,
+			running: entity_ref.get::<Running>().map(|running| running.origin),
+			last_result: entity_ref
+				.get::<LastRunResult>()
+				.map(|result| result.0.clone()),
+			last_started: timer.map(|timer| timer.last_started.elapsed_secs()),
+			last_stopped: timer.map(|timer| timer.last_stopped.elapsed_secs()),
+			children,
+		}
+	}
+
+	/// The name of the action, falling back to the entity.
+	pub fn label(&self) -> String {
+		self.name.clone().unwrap_or_else(|| self.entity.to_string())
+	}
+}
+
+/// A snapshot of every action hierarchy in the world, used for
+/// inspecting trees while they run, ie in a visualizer.
+/// A root is any action without an action parent.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let mut app = App::new();
+/// app.add_plugins((BeetFlowPlugin::default(), FlowSnapshotPlugin));
+/// app.world_mut()
+/// 	.spawn((Name::new("root"), Sequence))
+/// 	.with_child(ReturnWith(RunResult::Success))
+/// 	.trigger(OnRun::local());
+/// app.update();
+/// let snapshot = FlowSnapshot::new(app.world());
+/// assert_eq!(snapshot.roots[0].last_result, Some(RunResult::Success));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlowSnapshot {
+	/// The elapsed [`Time`] in seconds when the snapshot was taken.
+	pub time: f64,
+	/// Each root action, ordered by entity.
+	pub roots: Vec<ActionSnapshot>,
+}
+
+impl FlowSnapshot {
+	/// Create a snapshot of every action hierarchy in the world,
+	/// only entities with [`ActionObservers`] are visited.
+	pub fn new(world: &World) -> Self {
+		let mut roots = world
+			.try_query::<(Entity, Option<&ChildOf>, &ActionObservers)>()
+			.map(|mut query| {
+				query
+					.iter(world)
+					.filter(|(_, parent, _)| {
+						parent
+							.map(|parent| {
+								world
+									.get::<ActionObservers>(parent.parent())
+									.is_none()
+							})
+							.unwrap_or(true)
+					})
+					.map(|(entity, _, _)| entity)
+					.collect::<Vec<_>>()
+			})
+			.unwrap_or_default();
+		roots.sort();
+		Self {
+			time: world
+				.get_resource::<Time>()
+				.map(|time| time.elapsed_secs_f64())
+				.unwrap_or_default(),
+			roots: roots
+				.into_iter()
+				.map(|root| ActionSnapshot::new(world, root))
+				.collect(),
+		}
+	}
+}
+
+#[cfg(feature = "reflect")]
+impl FlowSnapshot {
+	/// Serialize the snapshot to json.
+	pub fn to_json(&self) -> serde_json::Result<String> {
+		serde_json::to_string(self)
+	}
+
+	/// Deserialize a snapshot from json.
+	pub fn from_json(json: &str) -> serde_json::Result<Self> {
+		serde_json::from_str(json)
+	}
+}
+
+/// Strip the module path from each segment of a type name,
+/// ie `beet_flow::ReturnWith<beet_flow::RunResult>` becomes
+/// `ReturnWith<RunResult>`.
+fn short_type_name(name: &str) -> String {
+	let mut out = String::with_capacity(name.len());
+	let mut segment = String::new();
+	for char in name.chars() {
+		match char {
+			'<' | '>' | ',' | ' ' | '(' | ')' | '[' | ']' | ';' | '&' => {
+				out.push_str(segment.rsplit("::").next().unwrap_or_default());
+				segment.clear();
+				out.push(char);
+			}
+			_ => segment.push(char),
+		}
+	}
+	out.push_str(segment.rsplit("::").next().unwrap_or_default());
+	out
+}
+
+#[cfg(test)]
+mod test {
+	use super::short_type_name;
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn type_names() {
+		expect(short_type_name("foo::Bar")).to_be("Bar");
+		expect(short_type_name("a::B<c::D, e::F<g::H>>")).to_be("B<D, F<H>>");
+	}
+
+	#[test]
+	fn snapshot() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), FlowSnapshotPlugin))
+			.insert_time();
+		let world = app.world_mut();
+		let agent = world.spawn(Name::new("agent")).id();
+		let root = world
+			.spawn((Name::new("root"), Sequence, ChildOf(agent)))
+			.with_child((Name::new("child1"), ReturnWith(RunResult::Success)))
+			.with_child((
+				Name::new("child2"),
+				ReturnInDuration::with_secs(RunResult::Success, 2),
+			))
+			.id();
+		world.flush_trigger(OnRunAction::new(root, agent, ()));
+		app.update_with_secs(1);
+
+		let snapshot = FlowSnapshot::new(app.world());
+		expect(snapshot.roots.len()).to_be(1);
+		let root = &snapshot.roots[0];
+		expect(root.label()).to_be("root");
+		expect(&root.actions).to_be(&vec!["Sequence".to_string()]);
+		expect(root.last_result.clone()).to_be_none();
+		expect(root.children.len()).to_be(2);
+
+		let child1 = &root.children[0];
+		expect(child1.last_result.clone()).to_be(Some(RunResult::Success));
+		expect(&child1.actions)
+			.to_be(&vec!["ReturnWith<RunResult>".to_string()]);
+
+		let child2 = &root.children[1];
+		expect(child2.running).to_be(Some(agent));
+		expect(child2.last_started).to_be(Some(1.));
+
+		app.update_with_secs(1);
+		let snapshot = FlowSnapshot::new(app.world());
+		let root = &snapshot.roots[0];
+		expect(root.last_result.clone()).to_be(Some(RunResult::Success));
+		expect(root.children[1].running).to_be_none();
+	}
+
+	#[cfg(feature = "reflect")]
+	#[test]
+	fn json() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), FlowSnapshotPlugin));
+		app.world_mut()
+			.spawn((Name::new("root"), Sequence))
+			.with_child(ReturnWith(RunResult::Success))
+			.trigger(OnRun::local());
+		app.update();
+		let snapshot = FlowSnapshot::new(app.world());
+		let json = snapshot.to_json().unwrap();
+		expect(&json).to_contain("\"name\":\"root\"");
+		expect(FlowSnapshot::from_json(&json).unwrap()).to_be(snapshot);
+	}
+}
@@ -0,0 +1,11 @@
+//! Structured recording of tree execution, for debugging and
+//! reproducing bugs in tests. See [`TracePlugin`] and [`TraceReplay`].
+//! For inspecting the current state of each tree see [`FlowSnapshot`].
+mod flow_snapshot;
+mod trace;
+mod trace_plugin;
+mod trace_replay;
+pub use flow_snapshot::*;
+pub use trace::*;
+pub use trace_plugin::*;
+pub use trace_replay::*;
@@ -0,0 +1,104 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// The kind of transition recorded in a [`TraceEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(serde::Serialize, serde::Deserialize))]
+pub enum TraceEventKind {
+	/// An [`OnRunAction`] was triggered.
+	Run,
+	/// An [`OnResultAction`] was triggered.
+	Result(RunResult),
+	/// The [`Running`] component was added.
+	RunningStart,
+	/// The [`Running`] component was removed.
+	RunningEnd,
+}
+
+/// A single recorded transition of an action.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceEvent {
+	/// The frame in which the event occured, counted from when
+	/// the [`TracePlugin`] was added.
+	pub frame: u32,
+	/// The elapsed [`Time`] in seconds when the event occured.
+	pub time: f64,
+	/// The transition that occured.
+	pub kind: TraceEventKind,
+	/// The action entity.
+	pub action: Entity,
+	/// The [`origin`](OnRun::origin) the action was run with,
+	/// for [`Running`] transitions this is the [`Running::origin`].
+	pub origin: Entity,
+	/// The [`Name`] of the action if it has one.
+	pub name: Option<String>,
+}
+
+impl TraceEvent {
+	/// The name of the action, falling back to the entity.
+	pub fn label(&self) -> String {
+		self.name.clone().unwrap_or_else(|| self.action.to_string())
+	}
+}
+
+/// A list of [`TraceEvent`] in the order they occured,
+/// usually created by [`TraceRecorder::trace`].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trace {
+	/// The recorded events.
+	pub events: Vec<TraceEvent>,
+}
+
+#[cfg(feature = "reflect")]
+impl Trace {
+	/// Serialize the trace to json.
+	pub fn to_json(&self) -> serde_json::Result<String> {
+		serde_json::to_string_pretty(self)
+	}
+
+	/// Deserialize a trace from json.
+	pub fn from_json(json: &str) -> serde_json::Result<Self> {
+		serde_json::from_str(json)
+	}
+
+	/// Serialize the trace to the
+	/// [Chrome trace event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+	/// which can be opened in `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+	///
+	/// [`Running`] spans are shown as duration events on a track per action,
+	/// and runs and results are shown as instant events.
+	pub fn to_chrome_trace(&self) -> serde_json::Result<String> {
+		let events = self
+			.events
+			.iter()
+			.map(|event| {
+				let (phase, name) = match &event.kind {
+					TraceEventKind::Run => ("i", "OnRun".to_string()),
+					TraceEventKind::Result(result) => {
+						("i", format!("{result:?}"))
+					}
+					TraceEventKind::RunningStart => ("B", event.label()),
+					TraceEventKind::RunningEnd => ("E", event.label()),
+				};
+				serde_json::json!({
+					"name": name,
+					"cat": "beet_flow",
+					"ph": phase,
+					// microseconds
+					"ts": event.time * 1_000_000.,
+					"pid": 0,
+					"tid": event.action.index(),
+					"s": "t",
+					"args": {
+						"action": event.label(),
+						"origin": event.origin.to_string(),
+						"frame": event.frame,
+					},
+				})
+			})
+			.collect::<Vec<_>>();
+		serde_json::to_string(&serde_json::json!({ "traceEvents": events }))
+	}
+}
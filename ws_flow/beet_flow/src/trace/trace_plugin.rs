@@ -0,0 +1,256 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Records every [`OnRunAction`], [`OnResultAction`] and [`Running`]
+/// transition into the [`TraceRecorder`] resource.
+/// Only the default [`RunResult`] payloads are recorded.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let mut app = App::new();
+/// app.add_plugins((BeetFlowPlugin::default(), TracePlugin::default()));
+/// app.world_mut()
+/// 	.spawn((Name::new("root"), Sequence))
+/// 	.with_child(ReturnWith(RunResult::Success))
+/// 	.trigger(OnRun::local());
+/// app.update();
+/// let trace = app.world().resource::<TraceRecorder>().trace();
+/// assert_eq!(trace.events.len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TracePlugin {
+	/// The maximum number of events to keep, older events are discarded.
+	pub capacity: usize,
+}
+
+impl Default for TracePlugin {
+	fn default() -> Self { Self { capacity: 10_000 } }
+}
+
+impl Plugin for TracePlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(TraceRecorder::new(self.capacity))
+			.add_systems(First, increment_trace_frame)
+			.add_observer(trace_on_run)
+			.add_observer(trace_on_result)
+			.add_observer(trace_running_start)
+			.add_observer(trace_running_end);
+	}
+}
+
+/// A ring buffer of [`TraceEvent`], added by the [`TracePlugin`].
+#[derive(Debug, Clone, Resource)]
+pub struct TraceRecorder {
+	/// Whether new events should be recorded.
+	pub recording: bool,
+	capacity: usize,
+	frame: u32,
+	events: VecDeque<TraceEvent>,
+}
+
+impl TraceRecorder {
+	/// Create a new recorder with the given capacity.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			recording: true,
+			capacity,
+			frame: 0,
+			events: VecDeque::with_capacity(capacity),
+		}
+	}
+	/// The current frame, incremented in the [`First`] schedule.
+	pub fn frame(&self) -> u32 { self.frame }
+	/// The recorded events, oldest first.
+	pub fn events(&self) -> impl Iterator<Item = &TraceEvent> {
+		self.events.iter()
+	}
+	/// Copy the recorded events into a [`Trace`].
+	pub fn trace(&self) -> Trace {
+		Trace {
+			events: self.events.iter().cloned().collect(),
+		}
+	}
+	/// Remove all recorded events.
+	pub fn clear(&mut self) { self.events.clear(); }
+
+	/// Add an event, discarding the oldest if at capacity.
+	pub fn push(&mut self, event: TraceEvent) {
+		if !self.recording || self.capacity == 0 {
+			return;
+		}
+		if self.events.len() == self.capacity {
+			self.events.pop_front();
+		}
+		self.events.push_back(event);
+	}
+
+	fn record(
+		&mut self,
+		time: Option<Res<Time>>,
+		names: &Query<&Name>,
+		kind: TraceEventKind,
+		action: Entity,
+		origin: Entity,
+	) {
+		self.push(TraceEvent {
+			frame: self.frame,
+			time: time.map(|time| time.elapsed_secs_f64()).unwrap_or_default(),
+			kind,
+			action,
+			origin,
+			name: names.get(action).ok().map(|name| name.to_string()),
+		});
+	}
+}
+
+fn increment_trace_frame(mut recorder: ResMut<TraceRecorder>) {
+	recorder.frame += 1;
+}
+
+fn trace_on_run(
+	ev: Trigger<OnRunAction>,
+	mut recorder: ResMut<TraceRecorder>,
+	time: Option<Res<Time>>,
+	names: Query<&Name>,
+) {
+	recorder.record(
+		time,
+		&names,
+		TraceEventKind::Run,
+		ev.resolve_action(),
+		ev.resolve_origin(),
+	);
+}
+
+fn trace_on_result(
+	ev: Trigger<OnResultAction>,
+	mut recorder: ResMut<TraceRecorder>,
+	time: Option<Res<Time>>,
+	names: Query<&Name>,
+) {
+	recorder.record(
+		time,
+		&names,
+		TraceEventKind::Result(ev.payload.clone()),
+		ev.resolve_action(),
+		ev.resolve_origin(),
+	);
+}
+
+fn trace_running_start(
+	ev: Trigger<OnAdd, Running>,
+	mut recorder: ResMut<TraceRecorder>,
+	time: Option<Res<Time>>,
+	names: Query<&Name>,
+	running: Query<&Running>,
+) {
+	let action = ev.target();
+	let origin = running
+		.get(action)
+		.map(|running| running.origin)
+		.unwrap_or(action);
+	recorder.record(time, &names, TraceEventKind::RunningStart, action, origin);
+}
+
+fn trace_running_end(
+	ev: Trigger<OnRemove, Running>,
+	mut recorder: ResMut<TraceRecorder>,
+	time: Option<Res<Time>>,
+	names: Query<&Name>,
+	running: Query<&Running>,
+) {
+	let action = ev.target();
+	let origin = running
+		.get(action)
+		.map(|running| running.origin)
+		.unwrap_or(action);
+	recorder.record(time, &names, TraceEventKind::RunningEnd, action, origin);
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn records() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), TracePlugin::default()))
+			.insert_time();
+		let world = app.world_mut();
+
+		let root = world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((
+				Name::new("child"),
+				ReturnInDuration::with_secs(RunResult::Success, 1),
+			))
+			.id();
+		app.update();
+		app.world_mut()
+			.entity_mut(root)
+			.flush_trigger(OnRun::local());
+		app.update_with_secs(1);
+
+		let events = app
+			.world()
+			.resource::<TraceRecorder>()
+			.events()
+			.map(|event| (event.frame, event.label(), event.kind.clone()))
+			.collect::<Vec<_>>();
+		expect(events).to_be(vec![
+			(1, "root".into(), TraceEventKind::Run),
+			(1, "child".into(), TraceEventKind::Run),
+			(1, "child".into(), TraceEventKind::RunningStart),
+			(
+				2,
+				"child".into(),
+				TraceEventKind::Result(RunResult::Success),
+			),
+			(2, "child".into(), TraceEventKind::RunningEnd),
+			(2, "root".into(), TraceEventKind::Result(RunResult::Success)),
+		]);
+	}
+
+	#[test]
+	fn ring_buffer() {
+		let mut recorder = TraceRecorder::new(2);
+		for frame in 0..3 {
+			recorder.push(TraceEvent {
+				frame,
+				time: 0.,
+				kind: TraceEventKind::Run,
+				action: Entity::PLACEHOLDER,
+				origin: Entity::PLACEHOLDER,
+				name: None,
+			});
+		}
+		expect(
+			recorder
+				.events()
+				.map(|event| event.frame)
+				.collect::<Vec<_>>(),
+		)
+		.to_be(vec![1, 2]);
+	}
+
+	#[cfg(feature = "reflect")]
+	#[test]
+	fn export() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), TracePlugin::default()));
+		app.world_mut()
+			.spawn((Name::new("root"), ContinueRun))
+			.flush_trigger(OnRun::local());
+		let trace = app.world().resource::<TraceRecorder>().trace();
+
+		let json = trace.to_json().unwrap();
+		expect(Trace::from_json(&json).unwrap()).to_be(trace.clone());
+
+		let chrome = trace.to_chrome_trace().unwrap();
+		expect(&chrome).to_contain(r#""traceEvents""#);
+		expect(&chrome).to_contain(r#""ph":"B""#);
+	}
+}
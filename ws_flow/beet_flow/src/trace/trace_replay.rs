@@ -0,0 +1,285 @@
+use crate::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// Replays the external inputs of a recorded [`Trace`] onto a tree,
+/// at the same frame offsets they were recorded at. The inputs are:
+/// - Runs of the tree root, except those caused by a [`Repeat`] on the root.
+/// - Results of leaf actions that completed in a later frame than they
+/// 	were run, ie long running actions whose result depends on the world.
+///
+/// All other events are caused by the tree itself so are reproduced by its
+/// control flow. Recorded actions are matched to the tree by [`Name`],
+/// so each action in the tree should have a unique name. Leaf actions are
+/// usually spawned without their behavior so only the recorded results occur.
+///
+/// Add the [`TraceReplay`] as a resource and call [`replay_trace`] each tick,
+/// for example by adding the [`TraceReplayPlugin`].
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let trace = Trace::default();
+/// let mut app = App::new();
+/// app.add_plugins((BeetFlowPlugin::default(), TraceReplayPlugin));
+/// let root = app
+/// 	.world_mut()
+/// 	.spawn((Name::new("root"), Sequence))
+/// 	.with_child(Name::new("child"))
+/// 	.id();
+/// let replay = TraceReplay::new(&trace, app.world(), root);
+/// app.insert_resource(replay);
+/// app.update();
+/// ```
+#[derive(Debug, Clone, Resource)]
+pub struct TraceReplay {
+	events: Vec<TraceEvent>,
+	entity_map: HashMap<Entity, Entity>,
+	root: Entity,
+	start_frame: u32,
+	frame: u32,
+	next_index: usize,
+}
+
+impl TraceReplay {
+	/// Prepare the external inputs of the `trace` for replaying onto the tree
+	/// at `root`. Recorded actions that cannot be matched by name are skipped.
+	pub fn new(trace: &Trace, world: &World, root: Entity) -> Self {
+		let names = EntityTree::new_with_world(root, world)
+			.flatten()
+			.into_iter()
+			.filter_map(|entity| {
+				world
+					.get::<Name>(entity)
+					.map(|name| (name.to_string(), entity))
+			})
+			.collect::<HashMap<_, _>>();
+		let entity_map = trace
+			.events
+			.iter()
+			.filter_map(|event| {
+				let name = event.name.as_ref()?;
+				names.get(name).map(|entity| (event.action, *entity))
+			})
+			.collect::<HashMap<_, _>>();
+
+		let root_repeat = world.get::<Repeat>(root);
+		let mut last_root_result = None;
+		let mut last_run = HashMap::<Entity, u32>::default();
+		let mut events = Vec::new();
+		for event in trace.events.iter() {
+			let Some(action) = entity_map.get(&event.action) else {
+				continue;
+			};
+			match &event.kind {
+				TraceEventKind::Run => {
+					last_run.insert(event.action, event.frame);
+					let is_root = world.get::<ChildOf>(*action).is_none()
+						|| *action == root;
+					// the root re-runs itself after a result,
+					// replaying it as well would run the tree twice
+					let is_repeat = *action == root
+						&& root_repeat
+							.zip(last_root_result.take())
+							.is_some_and(|(repeat, result)| {
+								repeat
+									.if_result_matches
+									.as_ref()
+									.is_none_or(|matches| *matches == result)
+							});
+					if is_root && !is_repeat {
+						events.push(event.clone());
+					}
+				}
+				TraceEventKind::Result(result) if *action == root => {
+					last_root_result = Some(result.clone());
+				}
+				TraceEventKind::Result(_) => {
+					let is_leaf = world
+						.get::<Children>(*action)
+						.map(|children| children.is_empty())
+						.unwrap_or(true);
+					let is_long_running = last_run
+						.get(&event.action)
+						.map(|frame| *frame < event.frame)
+						.unwrap_or(false);
+					if is_leaf && is_long_running {
+						events.push(event.clone());
+					}
+				}
+				_ => {}
+			}
+		}
+
+		Self {
+			start_frame: events.first().map(|event| event.frame).unwrap_or(0),
+			events,
+			entity_map,
+			root,
+			frame: 0,
+			next_index: 0,
+		}
+	}
+
+	/// Map a recorded entity to an entity in this world, ie the origin
+	/// of the recorded runs. Unmapped origins will use the tree root.
+	pub fn with_entity(mut self, recorded: Entity, entity: Entity) -> Self {
+		self.entity_map.insert(recorded, entity);
+		self
+	}
+
+	/// The events that will be replayed.
+	pub fn events(&self) -> &[TraceEvent] { &self.events }
+
+	/// Whether all events have been replayed.
+	pub fn is_finished(&self) -> bool { self.next_index >= self.events.len() }
+
+	fn map(&self, entity: Entity) -> Entity {
+		self.entity_map.get(&entity).copied().unwrap_or(self.root)
+	}
+}
+
+/// Adds the [`replay_trace`] system, which does nothing
+/// until a [`TraceReplay`] resource is inserted.
+pub struct TraceReplayPlugin;
+
+impl Plugin for TraceReplayPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			TickSchedule::get(app),
+			replay_trace
+				.run_if(resource_exists::<TraceReplay>)
+				.in_set(PreTickSet),
+		);
+	}
+}
+
+/// Trigger the events of the [`TraceReplay`] for the current frame.
+/// The first recorded event is replayed in the first tick.
+pub fn replay_trace(mut commands: Commands, mut replay: ResMut<TraceReplay>) {
+	let frame = replay.start_frame + replay.frame;
+	while let Some(event) = replay.events.get(replay.next_index).cloned() {
+		if event.frame > frame {
+			break;
+		}
+		let action = replay.map(event.action);
+		let origin = replay.map(event.origin);
+		match event.kind {
+			TraceEventKind::Run => {
+				commands.trigger(OnRunAction::new(action, origin, ()));
+			}
+			TraceEventKind::Result(result) => {
+				commands.trigger(OnResultAction::new(action, origin, result));
+			}
+			_ => {}
+		}
+		replay.next_index += 1;
+	}
+	replay.frame += 1;
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn labels(world: &World) -> Vec<(String, TraceEventKind)> {
+		world
+			.resource::<TraceRecorder>()
+			.events()
+			.filter(|event| event.kind != TraceEventKind::RunningEnd)
+			.map(|event| (event.label(), event.kind.clone()))
+			.collect()
+	}
+
+	#[test]
+	fn replays() {
+		// record a tree with a long running child
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), TracePlugin::default()))
+			.insert_time();
+		app.world_mut()
+			.spawn((Name::new("root"), Sequence, RunOnSpawn::default()))
+			.with_child((
+				Name::new("child1"),
+				ReturnInDuration::with_secs(RunResult::Success, 2),
+			))
+			.with_child((Name::new("child2"), ReturnWith(RunResult::Failure)));
+		for _ in 0..4 {
+			app.update_with_secs(1);
+		}
+		let trace = app.world().resource::<TraceRecorder>().trace();
+		let recorded = labels(app.world());
+
+		// replay onto the same tree, without the long running behavior
+		let mut app = App::new();
+		app.add_plugins((
+			BeetFlowPlugin::default(),
+			TracePlugin::default(),
+			TraceReplayPlugin,
+		));
+		let root = app
+			.world_mut()
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("child1"), ContinueRun))
+			.with_child((Name::new("child2"), ReturnWith(RunResult::Failure)))
+			.id();
+		let replay = TraceReplay::new(&trace, app.world(), root);
+		expect(replay.events().len()).to_be(2);
+		app.insert_resource(replay);
+		for _ in 0..4 {
+			app.update();
+		}
+
+		expect(app.world().resource::<TraceReplay>().is_finished())
+			.to_be_true();
+		expect(labels(app.world())).to_be(recorded);
+	}
+
+	#[test]
+	fn skips_repeated_runs() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), TracePlugin::default()));
+		app.world_mut()
+			.spawn((
+				Name::new("root"),
+				Repeat::default(),
+				Sequence,
+				RunOnSpawn::default(),
+			))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Success)));
+		for _ in 0..3 {
+			app.update();
+		}
+		let trace = app.world().resource::<TraceRecorder>().trace();
+		let recorded = labels(app.world());
+		expect(
+			recorded
+				.iter()
+				.filter(|(label, kind)| {
+					label == "root" && *kind == TraceEventKind::Run
+				})
+				.count(),
+		)
+		.to_be(3);
+
+		let mut app = App::new();
+		app.add_plugins((
+			BeetFlowPlugin::default(),
+			TracePlugin::default(),
+			TraceReplayPlugin,
+		));
+		let root = app
+			.world_mut()
+			.spawn((Name::new("root"), Repeat::default(), Sequence))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Success)))
+			.id();
+		let replay = TraceReplay::new(&trace, app.world(), root);
+		expect(replay.events().len()).to_be(1);
+		app.insert_resource(replay);
+		for _ in 0..3 {
+			app.update();
+		}
+		expect(labels(app.world())).to_be(recorded);
+	}
+}
@@ -1,5 +1,6 @@
 use super::frozen_lake_map::FrozenLakeMap;
 use crate::prelude::ActionSpace;
+use crate::prelude::StateVector;
 use bevy::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
@@ -46,6 +47,11 @@ impl From<UVec2> for GridPos {
 	fn from(pos: UVec2) -> Self { Self(pos) }
 }
 
+impl StateVector for GridPos {
+	const LEN: usize = 2;
+	fn to_vec(&self) -> Vec<f32> { vec![self.x as f32, self.y as f32] }
+}
+
 #[derive(
 	Debug,
 	Default,
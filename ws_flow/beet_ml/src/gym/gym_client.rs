@@ -0,0 +1,123 @@
+use crate::prelude::*;
+use anyhow::Result;
+use serde_json::Value;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+
+/// A client for a gym server, mirroring the methods of a
+/// gymnasium environment. Mostly used for testing, external trainers
+/// will usually implement their own client.
+pub struct GymClient<R, W> {
+	reader: R,
+	writer: W,
+}
+
+impl GymClient<BufReader<TcpStream>, TcpStream> {
+	/// Connect to a server started with [`serve_tcp`].
+	pub fn connect_tcp(addr: impl ToSocketAddrs) -> Result<Self> {
+		let stream = TcpStream::connect(addr)?;
+		Ok(Self::new(BufReader::new(stream.try_clone()?), stream))
+	}
+}
+
+impl<R: BufRead, W: Write> GymClient<R, W> {
+	pub fn new(reader: R, writer: W) -> Self { Self { reader, writer } }
+
+	/// Send a request and wait for the response,
+	/// [`GymResponse::Error`] is returned as an error.
+	pub fn request(&mut self, request: &GymRequest) -> Result<GymResponse> {
+		write_message(&mut self.writer, request)?;
+		match read_message(&mut self.reader)? {
+			Some(GymResponse::Error { message }) => anyhow::bail!(message),
+			Some(response) => Ok(response),
+			None => anyhow::bail!("gym server closed the connection"),
+		}
+	}
+
+	pub fn spec(&mut self) -> Result<GymSpec> {
+		match self.request(&GymRequest::Spec)? {
+			GymResponse::Spec(spec) => Ok(spec),
+			other => unexpected(other),
+		}
+	}
+
+	pub fn reset(&mut self, seed: Option<u64>) -> Result<Value> {
+		match self.request(&GymRequest::Reset { seed })? {
+			GymResponse::Reset { observation } => Ok(observation),
+			other => unexpected(other),
+		}
+	}
+
+	pub fn step(&mut self, action: impl Into<Value>) -> Result<GymStep> {
+		let action = action.into();
+		match self.request(&GymRequest::Step { action })? {
+			GymResponse::Step(step) => Ok(step),
+			other => unexpected(other),
+		}
+	}
+
+	pub fn render(&mut self) -> Result<Option<String>> {
+		match self.request(&GymRequest::Render)? {
+			GymResponse::Render { frame } => Ok(frame),
+			other => unexpected(other),
+		}
+	}
+
+	/// End the session, the server will stop handling this connection.
+	pub fn close(mut self) -> Result<()> {
+		match self.request(&GymRequest::Close)? {
+			GymResponse::Closed => Ok(()),
+			other => unexpected(other),
+		}
+	}
+}
+
+fn unexpected<T>(response: GymResponse) -> Result<T> {
+	anyhow::bail!("unexpected gym response: {response:?}")
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use std::net::TcpListener;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap();
+		let server = std::thread::spawn(move || {
+			let mut gym = EnvironmentGym::new(
+				CartPoleEnv::default(),
+				CartPoleState::default(),
+				500,
+			);
+			let (stream, _) = listener.accept().unwrap();
+			serve_stream(&mut gym, stream).unwrap();
+		});
+
+		let mut client = GymClient::connect_tcp(addr).unwrap();
+		expect(client.spec().unwrap().action_space)
+			.to_be(GymSpace::Discrete { n: 2 });
+		client.reset(Some(0)).unwrap();
+		let mut total_reward = 0.;
+		loop {
+			let step = client.step(0).unwrap();
+			total_reward += step.reward;
+			if step.terminated || step.truncated {
+				break;
+			}
+		}
+		// always pushing left drops the pole quickly
+		expect(total_reward).to_be_greater_than(1.);
+		expect(total_reward).to_be_less_than(50.);
+		expect(client.render().unwrap()).to_be_some();
+		expect(client.step(5).is_err()).to_be_true();
+		client.close().unwrap();
+		server.join().unwrap();
+	}
+}
@@ -0,0 +1,397 @@
+use crate::prelude::*;
+use anyhow::Result;
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use serde_json::Value;
+use sweet::prelude::*;
+
+/// An environment that can be driven by an external trainer
+/// with [`GymRequest`] messages.
+/// Observations and actions are passed as json values so
+/// this trait is object safe.
+pub trait GymEnv {
+	/// The observation and action spaces.
+	fn spec(&self) -> GymSpec;
+	/// Start a new episode, returning the initial observation.
+	fn reset(&mut self, seed: Option<u64>) -> Result<Value>;
+	/// Apply an action, returning the next observation and reward.
+	fn step(&mut self, action: Value) -> Result<GymStep>;
+	/// A text representation of the current state, if any.
+	fn render(&self) -> Option<String> { None }
+
+	/// Handle a single request, errors are returned as a
+	/// [`GymResponse::Error`].
+	fn handle(&mut self, request: GymRequest) -> GymResponse {
+		let result = match request {
+			GymRequest::Spec => Ok(GymResponse::Spec(self.spec())),
+			GymRequest::Reset { seed } => self
+				.reset(seed)
+				.map(|observation| GymResponse::Reset { observation }),
+			GymRequest::Step { action } => {
+				self.step(action).map(GymResponse::Step)
+			}
+			GymRequest::Render => Ok(GymResponse::Render {
+				frame: self.render(),
+			}),
+			GymRequest::Close => Ok(GymResponse::Closed),
+		};
+		result.unwrap_or_else(|err| GymResponse::Error {
+			message: err.to_string(),
+		})
+	}
+}
+
+/// Parse the index of a [`DiscreteActionSpace`].
+fn parse_discrete_action<A: DiscreteActionSpace>(action: Value) -> Result<A> {
+	let Some(index) = action.as_u64() else {
+		anyhow::bail!("expected action index, got {action}");
+	};
+	let index = index as usize;
+	if index >= A::num_actions() {
+		anyhow::bail!(
+			"action index {index} out of range 0..{}",
+			A::num_actions()
+		);
+	}
+	Ok(A::from_index(index))
+}
+
+/// Parse a [`ContinuousSpace`] from an array of numbers.
+fn parse_continuous_action<A: ContinuousSpace>(action: Value) -> Result<A> {
+	let values: Vec<f32> = serde_json::from_value(action)?;
+	if values.len() != A::LEN {
+		anyhow::bail!(
+			"expected {} action values, got {}",
+			A::LEN,
+			values.len()
+		);
+	}
+	Ok(A::from_vec(&values))
+}
+
+fn observation(values: Vec<f32>) -> Result<Value> {
+	Ok(serde_json::to_value(values)?)
+}
+
+/// Exposes an [`Environment`] with a discrete action space, each episode
+/// starts with [`Environment::reset`] of the `initial_state`, using the
+/// reset seed if provided.
+pub struct EnvironmentGym<E: Environment>
+where
+	E::State: StateVector,
+	E::Action: DiscreteActionSpace,
+{
+	pub env: E,
+	pub initial_state: E::State,
+	/// Episodes are truncated after this many steps.
+	pub max_steps: u32,
+	rng: RandomSource,
+	state: Option<E::State>,
+	step: u32,
+}
+
+impl<E: Environment> EnvironmentGym<E>
+where
+	E::State: StateVector,
+	E::Action: DiscreteActionSpace,
+{
+	pub fn new(env: E, initial_state: E::State, max_steps: u32) -> Self {
+		Self {
+			env,
+			initial_state,
+			max_steps,
+			rng: RandomSource::default(),
+			state: None,
+			step: 0,
+		}
+	}
+}
+
+impl<E: Environment> GymEnv for EnvironmentGym<E>
+where
+	E::State: StateVector,
+	E::Action: DiscreteActionSpace,
+{
+	fn spec(&self) -> GymSpec {
+		GymSpec {
+			observation_space: GymSpace::unbounded(E::State::LEN),
+			action_space: GymSpace::Discrete {
+				n: E::Action::num_actions(),
+			},
+		}
+	}
+
+	fn reset(&mut self, seed: Option<u64>) -> Result<Value> {
+		if let Some(seed) = seed {
+			self.rng = RandomSource::from_seed(seed);
+		}
+		self.step = 0;
+		let state = self.env.reset(&self.initial_state, &mut self.rng.0);
+		let value = observation(state.to_vec())?;
+		self.state = Some(state);
+		Ok(value)
+	}
+
+	fn step(&mut self, action: Value) -> Result<GymStep> {
+		let action = parse_discrete_action::<E::Action>(action)?;
+		let Some(state) = &self.state else {
+			anyhow::bail!("step called before reset");
+		};
+		let outcome = self.env.step(state, &action);
+		self.step += 1;
+		let step = GymStep {
+			observation: observation(outcome.state.to_vec())?,
+			reward: outcome.reward,
+			terminated: outcome.done,
+			truncated: !outcome.done && self.step >= self.max_steps,
+		};
+		self.state = Some(outcome.state);
+		Ok(step)
+	}
+
+	fn render(&self) -> Option<String> {
+		self.state.as_ref().map(|state| format!("{state:?}"))
+	}
+}
+
+/// Exposes a [`ContinuousEnvironment`], the reset seed is used for the
+/// random initial state.
+pub struct ContinuousGym<E: ContinuousEnvironment> {
+	pub env: E,
+	/// Episodes are truncated after this many steps.
+	pub max_steps: u32,
+	rng: RandomSource,
+	state: Option<E::State>,
+	step: u32,
+}
+
+impl<E: ContinuousEnvironment> ContinuousGym<E> {
+	pub fn new(env: E, max_steps: u32) -> Self {
+		Self {
+			env,
+			max_steps,
+			rng: RandomSource::default(),
+			state: None,
+			step: 0,
+		}
+	}
+}
+
+impl<E: ContinuousEnvironment> GymEnv for ContinuousGym<E> {
+	fn spec(&self) -> GymSpec {
+		GymSpec {
+			observation_space: GymSpace::unbounded(E::State::LEN),
+			action_space: GymSpace::normalized(E::Action::LEN),
+		}
+	}
+
+	fn reset(&mut self, seed: Option<u64>) -> Result<Value> {
+		if let Some(seed) = seed {
+			self.rng = RandomSource::from_seed(seed);
+		}
+		self.step = 0;
+		let state = self.env.reset(&mut self.rng.0);
+		let value = observation(state.to_vec())?;
+		self.state = Some(state);
+		Ok(value)
+	}
+
+	fn step(&mut self, action: Value) -> Result<GymStep> {
+		let action = parse_continuous_action::<E::Action>(action)?;
+		let Some(state) = &self.state else {
+			anyhow::bail!("step called before reset");
+		};
+		let outcome = self.env.step(state, &action);
+		self.step += 1;
+		let step = GymStep {
+			observation: observation(outcome.state.to_vec())?,
+			reward: outcome.reward,
+			terminated: outcome.done,
+			truncated: !outcome.done && self.step >= self.max_steps,
+		};
+		self.state = Some(outcome.state);
+		Ok(step)
+	}
+
+	fn render(&self) -> Option<String> {
+		self.state.as_ref().map(|state| format!("{state:?}"))
+	}
+}
+
+/// Exposes an agent in a live bevy [`App`], the agent entity has the
+/// `E`, `E::State` and `E::Action` components like one
+/// using [`StepEnvironment`].
+///
+/// Each step the action is written to the agent, the environment determines
+/// the reward and the app is updated so that systems like
+/// [`TranslateGrid`] can run. The observation is the state component
+/// after the update, allowing the simulation to have the final say.
+pub struct AppGym<E: Environment + Component<Mutability = Mutable>>
+where
+	E::State: StateVector,
+	E::Action: DiscreteActionSpace,
+{
+	pub app: App,
+	pub agent: Entity,
+	pub initial_state: E::State,
+	/// Episodes are truncated after this many steps.
+	pub max_steps: u32,
+	step: u32,
+}
+
+impl<E: Environment + Component<Mutability = Mutable>> AppGym<E>
+where
+	E::State: StateVector,
+	E::Action: DiscreteActionSpace,
+{
+	pub fn new(
+		app: App,
+		agent: Entity,
+		initial_state: E::State,
+		max_steps: u32,
+	) -> Self {
+		Self {
+			app,
+			agent,
+			initial_state,
+			max_steps,
+			step: 0,
+		}
+	}
+
+	fn state(&self) -> Result<E::State> {
+		self.app
+			.world()
+			.get::<E::State>(self.agent)
+			.cloned()
+			.ok_or_else(|| anyhow::anyhow!("agent has no state component"))
+	}
+}
+
+impl<E: Environment + Component<Mutability = Mutable>> GymEnv for AppGym<E>
+where
+	E::State: StateVector,
+	E::Action: DiscreteActionSpace,
+{
+	fn spec(&self) -> GymSpec {
+		GymSpec {
+			observation_space: GymSpace::unbounded(E::State::LEN),
+			action_space: GymSpace::Discrete {
+				n: E::Action::num_actions(),
+			},
+		}
+	}
+
+	fn reset(&mut self, seed: Option<u64>) -> Result<Value> {
+		let world = self.app.world_mut();
+		if let Some(seed) = seed
+			&& let Some(mut rng) = world.get_resource_mut::<RandomSource>()
+		{
+			*rng = RandomSource::from_seed(seed);
+		}
+		world
+			.get_entity_mut(self.agent)?
+			.insert(self.initial_state.clone());
+		self.step = 0;
+		observation(self.initial_state.to_vec())
+	}
+
+	fn step(&mut self, action: Value) -> Result<GymStep> {
+		let action = parse_discrete_action::<E::Action>(action)?;
+		let state = self.state()?;
+		let mut entity = self.app.world_mut().get_entity_mut(self.agent)?;
+		let outcome = entity
+			.get_mut::<E>()
+			.ok_or_else(|| anyhow::anyhow!("agent has no environment"))?
+			.step(&state, &action);
+		entity.insert((action, outcome.state));
+		self.app.update();
+		self.step += 1;
+		Ok(GymStep {
+			observation: observation(self.state()?.to_vec())?,
+			reward: outcome.reward,
+			terminated: outcome.done,
+			truncated: !outcome.done && self.step >= self.max_steps,
+		})
+	}
+
+	fn render(&self) -> Option<String> {
+		self.state().ok().map(|state| format!("{state:?}"))
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use serde_json::json;
+	use sweet::prelude::*;
+
+	fn frozen_lake() -> (QTableEnv<GridPos, GridDirection>, GridPos) {
+		let map = FrozenLakeMap::default_four_by_four();
+		(
+			QTableEnv::new(map.transition_outcomes()),
+			map.agent_position(),
+		)
+	}
+
+	#[test]
+	fn environment() {
+		let (env, initial_state) = frozen_lake();
+		let mut gym = EnvironmentGym::new(env, initial_state, 100);
+		expect(gym.spec().action_space).to_be(GymSpace::Discrete { n: 4 });
+		expect(gym.handle(GymRequest::Step { action: json!(0) })).to_be(
+			GymResponse::Error {
+				message: "step called before reset".into(),
+			},
+		);
+		expect(gym.reset(None).unwrap()).to_be(json!([0., 0.]));
+		let index = GridDirection::Right.to_index();
+		let step = gym.step(json!(index)).unwrap();
+		expect(step.observation).to_be(json!([1., 0.]));
+		expect(step.terminated).to_be_false();
+		expect(gym.step(json!(9)).is_err()).to_be_true();
+	}
+
+	#[test]
+	fn truncates() {
+		let mut gym = EnvironmentGym::new(CartPoleEnv::default(), default(), 2);
+		gym.reset(None).unwrap();
+		expect(gym.step(json!(0)).unwrap().truncated).to_be_false();
+		expect(gym.step(json!(1)).unwrap().truncated).to_be_true();
+	}
+
+	#[test]
+	fn seeded_reset() {
+		let mut gym =
+			EnvironmentGym::new(CartPoleEnv::default(), default(), 100);
+		let first = gym.reset(Some(1)).unwrap();
+		expect(&first).not().to_be(&json!([0., 0., 0., 0.]));
+		expect(gym.reset(None).unwrap()).not().to_be(first.clone());
+		expect(gym.reset(Some(1)).unwrap()).to_be(first);
+	}
+
+	#[test]
+	fn app() {
+		let (env, initial_state) = frozen_lake();
+		let mut app = App::new();
+		app.add_plugins(MinimalPlugins);
+		let agent = app
+			.world_mut()
+			.spawn((env, initial_state, GridDirection::default()))
+			.id();
+		let mut gym = AppGym::<QTableEnv<GridPos, GridDirection>>::new(
+			app,
+			agent,
+			initial_state,
+			100,
+		);
+		gym.reset(Some(0)).unwrap();
+		let index = GridDirection::Up.to_index();
+		let step = gym.step(json!(index)).unwrap();
+		expect(step.observation).to_be(json!([0., 1.]));
+		expect(gym.app.world().get::<GridDirection>(agent))
+			.to_be(Some(&GridDirection::Up));
+	}
+}
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::io::BufRead;
+use std::io::Write;
+
+/// A message sent by the trainer to the environment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GymRequest {
+	/// Request the observation and action spaces.
+	Spec,
+	/// Start a new episode, optionally seeding the environment.
+	Reset {
+		#[serde(default)]
+		seed: Option<u64>,
+	},
+	/// Apply an action and advance the environment by one step.
+	Step { action: Value },
+	/// Request a text representation of the environment.
+	Render,
+	/// End the connection.
+	Close,
+}
+
+/// A message sent by the environment in response to a [`GymRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GymResponse {
+	Spec(GymSpec),
+	Reset {
+		observation: Value,
+	},
+	Step(GymStep),
+	Render {
+		frame: Option<String>,
+	},
+	Closed,
+	/// The request could not be handled, the connection remains open.
+	Error {
+		message: String,
+	},
+}
+
+/// The outcome of a [`GymRequest::Step`], matching the gymnasium
+/// `(observation, reward, terminated, truncated)` tuple.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GymStep {
+	pub observation: Value,
+	pub reward: f32,
+	/// The episode reached a terminal state.
+	pub terminated: bool,
+	/// The episode was cut short, ie by a step limit.
+	pub truncated: bool,
+}
+
+/// The shape of the observations and actions of an environment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GymSpec {
+	pub observation_space: GymSpace,
+	pub action_space: GymSpace,
+}
+
+/// Mirrors the gymnasium `Discrete` and `Box` spaces.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GymSpace {
+	/// An integer in the range `0..n`.
+	Discrete { n: usize },
+	/// A flat vector of floats, unbounded if `low` and `high` are `None`.
+	Box {
+		shape: Vec<usize>,
+		low: Option<f32>,
+		high: Option<f32>,
+	},
+}
+
+impl GymSpace {
+	/// An unbounded vector of the given length.
+	pub fn unbounded(len: usize) -> Self {
+		Self::Box {
+			shape: vec![len],
+			low: None,
+			high: None,
+		}
+	}
+	/// A vector of the given length with values in `-1..=1`.
+	pub fn normalized(len: usize) -> Self {
+		Self::Box {
+			shape: vec![len],
+			low: Some(-1.),
+			high: Some(1.),
+		}
+	}
+}
+
+/// Write a message as a single line of json.
+pub fn write_message(
+	writer: &mut impl Write,
+	message: &impl Serialize,
+) -> Result<()> {
+	serde_json::to_writer(&mut *writer, message)?;
+	writer.write_all(b"\n")?;
+	writer.flush()?;
+	Ok(())
+}
+
+/// Read a single line of json, returning `None` if the stream has ended.
+pub fn read_message<T: DeserializeOwned>(
+	reader: &mut impl BufRead,
+) -> Result<Option<T>> {
+	let mut line = String::new();
+	loop {
+		line.clear();
+		if reader.read_line(&mut line)? == 0 {
+			return Ok(None);
+		}
+		// skip blank lines
+		if !line.trim().is_empty() {
+			return Ok(Some(serde_json::from_str(&line)?));
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn format() {
+		expect(
+			serde_json::from_str::<GymRequest>(r#"{"type":"reset","seed":1}"#)
+				.unwrap(),
+		)
+		.to_be(GymRequest::Reset { seed: Some(1) });
+		expect(
+			serde_json::from_str::<GymRequest>(r#"{"type":"step","action":2}"#)
+				.unwrap(),
+		)
+		.to_be(GymRequest::Step { action: 2.into() });
+		expect(serde_json::to_string(&GymResponse::Closed).unwrap())
+			.to_be(r#"{"type":"closed"}"#.to_string());
+	}
+
+	#[test]
+	fn framing() {
+		let mut bytes = Vec::new();
+		write_message(&mut bytes, &GymRequest::Render).unwrap();
+		write_message(&mut bytes, &GymRequest::Close).unwrap();
+		let mut reader = std::io::Cursor::new(bytes);
+		expect(read_message::<GymRequest>(&mut reader).unwrap())
+			.to_be(Some(GymRequest::Render));
+		expect(read_message::<GymRequest>(&mut reader).unwrap())
+			.to_be(Some(GymRequest::Close));
+		expect(read_message::<GymRequest>(&mut reader).unwrap()).to_be_none();
+	}
+}
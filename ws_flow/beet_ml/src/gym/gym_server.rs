@@ -0,0 +1,119 @@
+use crate::prelude::*;
+use anyhow::Result;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+
+/// Handle requests until a [`GymRequest::Close`] is received or
+/// the reader is exhausted.
+/// Malformed requests are answered with a [`GymResponse::Error`],
+/// only io errors will end the session early.
+pub fn serve(
+	env: &mut impl GymEnv,
+	mut reader: impl BufRead,
+	mut writer: impl Write,
+) -> Result<()> {
+	loop {
+		let request = match read_message::<GymRequest>(&mut reader) {
+			Ok(Some(request)) => request,
+			Ok(None) => return Ok(()),
+			Err(err) if err.is::<serde_json::Error>() => {
+				write_message(&mut writer, &GymResponse::Error {
+					message: err.to_string(),
+				})?;
+				continue;
+			}
+			Err(err) => return Err(err),
+		};
+		let response = env.handle(request);
+		write_message(&mut writer, &response)?;
+		if response == GymResponse::Closed {
+			return Ok(());
+		}
+	}
+}
+
+/// Serve over stdin and stdout, for trainers that spawn the
+/// simulation as a subprocess.
+/// Any logging must be written to stderr to avoid corrupting the stream.
+pub fn serve_stdio(env: &mut impl GymEnv) -> Result<()> {
+	serve(env, std::io::stdin().lock(), std::io::stdout().lock())
+}
+
+/// Serve a single tcp connection.
+pub fn serve_stream(env: &mut impl GymEnv, stream: TcpStream) -> Result<()> {
+	let reader = BufReader::new(stream.try_clone()?);
+	serve(env, reader, stream)
+}
+
+/// Listen on the given address and serve each connection in turn,
+/// the environment is shared between connections.
+pub fn serve_tcp(
+	env: &mut impl GymEnv,
+	addr: impl ToSocketAddrs,
+) -> Result<()> {
+	let listener = TcpListener::bind(addr)?;
+	log::info!("gym server listening on {}", listener.local_addr()?);
+	for stream in listener.incoming() {
+		if let Err(err) = serve_stream(env, stream?) {
+			log::warn!("gym connection closed: {err}");
+		}
+	}
+	Ok(())
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use serde_json::json;
+	use std::io::Cursor;
+	use sweet::prelude::*;
+
+	fn run(requests: &str) -> Vec<GymResponse> {
+		let mut gym = EnvironmentGym::new(
+			CartPoleEnv::default(),
+			CartPoleState::default(),
+			500,
+		);
+		let mut output = Vec::new();
+		serve(&mut gym, Cursor::new(requests.to_string()), &mut output)
+			.unwrap();
+		let mut reader = Cursor::new(output);
+		let mut responses = Vec::new();
+		while let Some(response) = read_message(&mut reader).unwrap() {
+			responses.push(response);
+		}
+		responses
+	}
+
+	#[test]
+	fn works() {
+		let responses = run(r#"{"type":"reset"}
+{"type":"step","action":1}
+{"type":"close"}
+{"type":"render"}
+"#);
+		expect(responses.len()).to_be(3);
+		expect(&responses[0]).to_be(&GymResponse::Reset {
+			observation: json!([0., 0., 0., 0.]),
+		});
+		let GymResponse::Step(step) = &responses[1] else {
+			panic!("expected step, got {:?}", responses[1]);
+		};
+		expect(step.reward).to_be(1.);
+		expect(step.terminated).to_be_false();
+		expect(&responses[2]).to_be(&GymResponse::Closed);
+	}
+
+	#[test]
+	fn malformed() {
+		let responses = run("not json\n{\"type\":\"spec\"}\n");
+		expect(responses.len()).to_be(2);
+		expect(matches!(responses[0], GymResponse::Error { .. })).to_be_true();
+		expect(matches!(responses[1], GymResponse::Spec(_))).to_be_true();
+	}
+}
@@ -0,0 +1,27 @@
+//! A gymnasium-like protocol for driving environments from an external
+//! trainer, ie a python process using existing tooling.
+//!
+//! Messages are newline delimited json objects tagged by `type`,
+//! each [`GymRequest`] is answered by exactly one [`GymResponse`]:
+//!
+//! ```text
+//! > {"type":"spec"}
+//! < {"type":"spec","observation_space":{...},"action_space":{...}}
+//! > {"type":"reset","seed":0}
+//! < {"type":"reset","observation":[0.0,0.0,0.0,0.0]}
+//! > {"type":"step","action":1}
+//! < {"type":"step","observation":[...],"reward":1.0,...}
+//! > {"type":"close"}
+//! < {"type":"closed"}
+//! ```
+//!
+//! Environments are served over stdio or tcp, see [`EnvironmentGym`],
+//! [`ContinuousGym`] and [`AppGym`] for exposing existing environments.
+mod gym_client;
+pub use self::gym_client::*;
+mod gym_env;
+pub use self::gym_env::*;
+mod gym_protocol;
+pub use self::gym_protocol::*;
+mod gym_server;
+pub use self::gym_server::*;
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use sha2::Digest;
+use sha2::Sha256;
+
+/// A bundle of named files with a sha256 checksum for each,
+/// used for distributing models to machines without network access.
+///
+/// The `.beetmodel` format is little endian:
+/// - `BEETMODL` magic bytes
+/// - `u32` format version
+/// - `u32` number of entries
+/// - for each entry:
+///   - `u32` name length, followed by the utf8 name
+///   - `[u8; 32]` sha256 of the data
+///   - `u64` data length, followed by the data
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BeetModelArchive {
+	entries: Vec<(String, Vec<u8>)>,
+}
+
+impl BeetModelArchive {
+	const MAGIC: &'static [u8; 8] = b"BEETMODL";
+	const VERSION: u32 = 1;
+
+	/// Create an empty archive.
+	pub fn new() -> Self { Self::default() }
+
+	/// Add an entry, replacing any existing entry with the same name.
+	pub fn with_entry(
+		mut self,
+		name: impl Into<String>,
+		data: impl Into<Vec<u8>>,
+	) -> Self {
+		self.insert(name, data);
+		self
+	}
+
+	/// Add an entry, replacing any existing entry with the same name.
+	pub fn insert(
+		&mut self,
+		name: impl Into<String>,
+		data: impl Into<Vec<u8>>,
+	) {
+		let name = name.into();
+		let data = data.into();
+		if let Some(entry) = self.entries.iter_mut().find(|e| e.0 == name) {
+			entry.1 = data;
+		} else {
+			self.entries.push((name, data));
+		}
+	}
+
+	/// Get the data of an entry by name.
+	pub fn get(&self, name: &str) -> Option<&[u8]> {
+		self.entries
+			.iter()
+			.find(|(entry, _)| entry == name)
+			.map(|(_, data)| data.as_slice())
+	}
+
+	/// Get an entry or return an error if it is missing.
+	pub fn require(&self, name: &str) -> Result<&[u8]> {
+		self.get(name)
+			.ok_or_else(|| anyhow::anyhow!("beetmodel missing entry: {name}"))
+	}
+
+	/// The names of all entries, in insertion order.
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.entries.iter().map(|(name, _)| name.as_str())
+	}
+
+	/// Serialize to the `.beetmodel` format.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		bytes.extend_from_slice(Self::MAGIC);
+		bytes.extend_from_slice(&Self::VERSION.to_le_bytes());
+		bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+		for (name, data) in &self.entries {
+			bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+			bytes.extend_from_slice(name.as_bytes());
+			bytes.extend_from_slice(&Sha256::digest(data));
+			bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+			bytes.extend_from_slice(data);
+		}
+		bytes
+	}
+
+	/// Parse an archive, returning an error if it is malformed or
+	/// the checksum of any entry does not match.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		let mut cursor = ByteCursor { bytes, pos: 0 };
+		if cursor.take(8)? != Self::MAGIC {
+			anyhow::bail!("not a beetmodel archive");
+		}
+		let version = cursor.u32()?;
+		if version != Self::VERSION {
+			anyhow::bail!("unsupported beetmodel version: {version}");
+		}
+		let num_entries = cursor.u32()?;
+		// the entry count is untrusted so dont preallocate
+		let mut entries = Vec::new();
+		for _ in 0..num_entries {
+			let name_len = cursor.u32()? as usize;
+			let name = String::from_utf8(cursor.take(name_len)?.to_vec())?;
+			let checksum = cursor.take(32)?;
+			let data_len = cursor.u64()? as usize;
+			let data = cursor.take(data_len)?;
+			if Sha256::digest(data).as_slice() != checksum {
+				anyhow::bail!("beetmodel checksum mismatch for entry: {name}");
+			}
+			entries.push((name, data.to_vec()));
+		}
+		Ok(Self { entries })
+	}
+
+	/// Bundle every file in a directory, subdirectories are ignored.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn from_dir(dir: impl AsRef<std::path::Path>) -> Result<Self> {
+		let mut paths = std::fs::read_dir(dir)?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<std::io::Result<Vec<_>>>()?;
+		paths.sort();
+		let mut archive = Self::new();
+		for path in paths.into_iter().filter(|path| path.is_file()) {
+			let name = path
+				.file_name()
+				.and_then(|name| name.to_str())
+				.ok_or_else(|| anyhow::anyhow!("invalid file name: {path:?}"))?
+				.to_string();
+			archive.insert(name, std::fs::read(&path)?);
+		}
+		Ok(archive)
+	}
+
+	/// Write the archive to a `.beetmodel` file.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+		std::fs::write(path, self.to_bytes())?;
+		Ok(())
+	}
+}
+
+/// The lowercase hex encoded sha256 of some bytes.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+	Sha256::digest(bytes)
+		.iter()
+		.map(|byte| format!("{byte:02x}"))
+		.collect()
+}
+
+struct ByteCursor<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> ByteCursor<'a> {
+	fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+		let end = self
+			.pos
+			.checked_add(len)
+			.filter(|end| *end <= self.bytes.len())
+			.ok_or_else(|| anyhow::anyhow!("unexpected end of beetmodel"))?;
+		let slice = &self.bytes[self.pos..end];
+		self.pos = end;
+		Ok(slice)
+	}
+	fn u32(&mut self) -> Result<u32> {
+		Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+	}
+	fn u64(&mut self) -> Result<u64> {
+		Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let archive = BeetModelArchive::new()
+			.with_entry("config.json", "{}")
+			.with_entry("model.safetensors", vec![1, 2, 3]);
+		let mut bytes = archive.to_bytes();
+		let parsed = BeetModelArchive::from_bytes(&bytes).unwrap();
+		expect(&parsed).to_be(&archive);
+		expect(parsed.get("model.safetensors"))
+			.to_be(Some([1, 2, 3].as_slice()));
+
+		// corrupt the last byte
+		*bytes.last_mut().unwrap() = 9;
+		expect(BeetModelArchive::from_bytes(&bytes).is_err()).to_be_true();
+		expect(BeetModelArchive::from_bytes(&bytes[..10]).is_err())
+			.to_be_true();
+	}
+
+	#[test]
+	fn huge_entry_count() {
+		let mut bytes = b"BEETMODL".to_vec();
+		bytes.extend_from_slice(&1u32.to_le_bytes());
+		bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+		expect(BeetModelArchive::from_bytes(&bytes).is_err()).to_be_true();
+	}
+
+	#[test]
+	fn checksum() {
+		expect(sha256_hex(b"abc")).to_be(
+			"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+				.to_string(),
+		);
+	}
+}
@@ -102,30 +102,40 @@ impl Bert {
 		// 	open_or_fetch(&tokenizer_url)
 		// );
 
-		let model_config = model_config
-			.map_err(|e| anyhow::anyhow!("config fetch error: {:?}", e))?
-			.to_vec();
-		let model_config: Config = serde_json::from_slice(&model_config)?;
-
-		let weights = weights
-			.map_err(|e| anyhow::anyhow!("weights fetch error: {:?}", e))?
-			.to_vec();
-		let device = &candle_core::Device::Cpu;
-		let vb = VarBuilder::from_buffered_safetensors(
-			weights,
-			candle_transformers::models::bert::DTYPE,
-			device,
-		)?;
-		// VarBuilder::from_buffered_safetensors(weights, DType::F64, device)?;
+		let files = BertFiles {
+			config: model_config
+				.map_err(|e| anyhow::anyhow!("config fetch error: {:?}", e))?
+				.to_vec(),
+			weights: weights
+				.map_err(|e| anyhow::anyhow!("weights fetch error: {:?}", e))?
+				.to_vec(),
+			tokenizer: tokenizer
+				.map_err(|e| anyhow::anyhow!("tokenizer fetch error: {:?}", e))?
+				.to_vec(),
+		};
+		Self::from_files(config, files)
+	}
 
+	/// Create from files already in memory, ie loaded from a
+	/// [`BertSource::Directory`] or [`BertSource::Archive`].
+	/// The files are verified against the [`BertConfig::checksums`].
+	pub fn from_files(config: BertConfig, files: BertFiles) -> Result<Self> {
+		use candle_transformers::models::bert::DTYPE;
+		use candle_transformers::models::bert::HiddenAct;
 
-		let tokenizer = tokenizer
-			.map_err(|e| anyhow::anyhow!("tokenizer fetch error: {:?}", e))?
-			.to_vec();
-		let tokenizer = Tokenizer::from_bytes(&tokenizer)
+		files.verify(&config.checksums)?;
+		let mut model_config: Config = serde_json::from_slice(&files.config)?;
+		if config.approximate_gelu {
+			model_config.hidden_act = HiddenAct::GeluApproximate;
+		}
+		let tokenizer = Tokenizer::from_bytes(&files.tokenizer)
 			.map_err(|m| anyhow::anyhow!(m.to_string()))?;
-
-
+		let device = candle_core::Device::Cpu;
+		let vb = VarBuilder::from_buffered_safetensors(
+			files.weights,
+			DTYPE,
+			&device,
+		)?;
 		let model = BertModel::load(vb, &model_config)?;
 
 		Ok(Self {
@@ -1,38 +1,54 @@
+use crate::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
 
 
+/// Missing fields use their default value, so a config for a local
+/// model only needs to specify the `source`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BertConfig {
+	/// Used when the source is [`BertSource::Hub`].
 	pub model: BertModelConfig,
+	pub source: BertSource,
+	/// Expected sha256 hex digests of local model files,
+	/// ie `"model.safetensors": "ba78..."`. Loading fails on a mismatch.
+	pub checksums: HashMap<String, String>,
 	pub normalize_embeddings: bool,
 	pub approximate_gelu: bool,
 }
 
 impl Default for BertConfig {
-	fn default() -> Self {
-		Self{
-			model: BertModelConfig {
-					base_url: "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/refs%2Fpr%2F21/".into(),
-					search_prefix: "".into(),
-					document_prefix: "".into(),
-					model_id: "sentence-transformers/all-MiniLM-L6-v2".into(),
-					revision: "refs/pr/21".into(),
-		},
-			normalize_embeddings: true,
-			approximate_gelu: false,
-		}
-	}
+	fn default() -> Self { Self::new(Default::default()) }
 }
 
 impl BertConfig {
 	pub fn new(model: BertModelConfig) -> Self {
 		Self {
 			model,
+			source: Default::default(),
+			checksums: Default::default(),
 			normalize_embeddings: true,
 			approximate_gelu: false,
 		}
 	}
+
+	/// Load the model files from a local directory or archive.
+	pub fn with_source(mut self, source: BertSource) -> Self {
+		self.source = source;
+		self
+	}
+
+	/// Expect a local model file to have this sha256 hex digest.
+	pub fn with_checksum(
+		mut self,
+		file: impl Into<String>,
+		sha256: impl Into<String>,
+	) -> Self {
+		self.checksums.insert(file.into(), sha256.into());
+		self
+	}
 }
 
 
@@ -48,6 +64,18 @@ pub struct BertModelConfig {
 }
 
 
+impl Default for BertModelConfig {
+	fn default() -> Self {
+		Self {
+			base_url: "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/refs%2Fpr%2F21/".into(),
+			search_prefix: "".into(),
+			document_prefix: "".into(),
+			model_id: "sentence-transformers/all-MiniLM-L6-v2".into(),
+			revision: "refs/pr/21".into(),
+		}
+	}
+}
+
 impl BertModelConfig {
 	pub fn model_url(&self) -> String {
 		self.base_url.clone() + "model.safetensors"
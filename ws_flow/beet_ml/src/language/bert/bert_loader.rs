@@ -3,10 +3,61 @@ use bevy::asset::AssetLoader;
 use bevy::asset::LoadContext;
 use bevy::asset::io::Reader;
 use bevy::tasks::ConditionalSendFuture;
+use std::path::PathBuf;
 
+/// Loads a [`Bert`] from either a `.bert.ron` [`BertConfig`] or a
+/// `.beetmodel` archive. An archive may include a `bert.ron` entry,
+/// otherwise the default config is used.
 #[derive(Default)]
 pub struct BertLoader;
 
+impl BertLoader {
+	/// The optional config entry of a `.beetmodel` archive.
+	pub const ARCHIVE_CONFIG: &'static str = "bert.ron";
+}
+
+/// Resolve a path relative to the directory of the asset being loaded.
+fn relative_path(load_context: &LoadContext<'_>, path: &str) -> PathBuf {
+	load_context
+		.path()
+		.parent()
+		.map(|parent| parent.join(path))
+		.unwrap_or_else(|| PathBuf::from(path))
+}
+
+async fn read_directory(
+	load_context: &mut LoadContext<'_>,
+	dir: PathBuf,
+) -> anyhow::Result<BertFiles> {
+	Ok(BertFiles {
+		config: load_context
+			.read_asset_bytes(dir.join(BertFiles::CONFIG))
+			.await?,
+		tokenizer: load_context
+			.read_asset_bytes(dir.join(BertFiles::TOKENIZER))
+			.await?,
+		weights: load_context
+			.read_asset_bytes(dir.join(BertFiles::WEIGHTS))
+			.await?,
+	})
+}
+
+fn from_archive(
+	mut config: Option<BertConfig>,
+	bytes: &[u8],
+) -> anyhow::Result<Bert> {
+	let archive = BeetModelArchive::from_bytes(bytes)?;
+	if config.is_none()
+		&& let Some(bytes) = archive.get(BertLoader::ARCHIVE_CONFIG)
+	{
+		config = Some(ron::de::from_bytes(bytes)?);
+	}
+	Bert::from_files(
+		config.unwrap_or_default(),
+		BertFiles::from_archive(&archive)?,
+	)
+}
+
 impl AssetLoader for BertLoader {
 	type Asset = Bert;
 	type Settings = ();
@@ -16,24 +67,42 @@ impl AssetLoader for BertLoader {
 		&self,
 		reader: &mut dyn Reader,
 		_settings: &Self::Settings,
-		_load_context: &mut LoadContext,
+		load_context: &mut LoadContext,
 	) -> impl ConditionalSendFuture<Output = Result<Self::Asset, Self::Error>>
 	{
 		Box::pin(async move {
 			let mut bytes = Vec::new();
 			reader.read_to_end(&mut bytes).await?;
+			if load_context.path().extension() == Some("beetmodel".as_ref()) {
+				return from_archive(None, &bytes);
+			}
 			let config = ron::de::from_bytes::<BertConfig>(&bytes)?;
-			let bert = Bert::new(config).await?;
+			let bert = match config.source.clone() {
+				BertSource::Hub => Bert::new(config).await?,
+				BertSource::Directory(dir) => {
+					let dir = relative_path(load_context, &dir);
+					let files = read_directory(load_context, dir).await?;
+					Bert::from_files(config, files)?
+				}
+				BertSource::Archive(path) => {
+					let path = relative_path(load_context, &path);
+					let bytes = load_context.read_asset_bytes(path).await?;
+					from_archive(Some(config), &bytes)?
+				}
+			};
 
 			Ok(bert)
 		})
 	}
+
+	fn extensions(&self) -> &[&str] { &["bert.ron", "beetmodel"] }
 }
 
 #[cfg(test)]
 mod test {
 	use crate::prelude::*;
 	use bevy::prelude::*;
+	use sweet::prelude::*;
 
 	#[test]
 	// possibly flaky tests here, getting occasional 403 on tokenizer.json
@@ -46,4 +115,64 @@ mod test {
 
 		block_on_asset_load::<Bert>(&mut app, "ml/default-bert.ron").unwrap();
 	}
+
+	#[test]
+	fn local() {
+		let dir = std::env::temp_dir()
+			.join(format!("beet_bert_loader_{}", std::process::id()));
+		let files = tiny_bert_files().unwrap();
+		std::fs::create_dir_all(dir.join("tiny")).unwrap();
+		std::fs::write(dir.join("tiny/config.json"), &files.config).unwrap();
+		std::fs::write(dir.join("tiny/tokenizer.json"), &files.tokenizer)
+			.unwrap();
+		std::fs::write(dir.join("tiny/model.safetensors"), &files.weights)
+			.unwrap();
+		let write_config = |name: &str, config: BertConfig| {
+			let text = ron::ser::to_string(&config).unwrap();
+			std::fs::write(dir.join(name), text).unwrap();
+		};
+		let mut directory_config = BertConfig::default()
+			.with_source(BertSource::Directory("tiny".into()));
+		directory_config.checksums = files.checksums();
+		write_config("directory.bert.ron", directory_config);
+		write_config(
+			"corrupt.bert.ron",
+			BertConfig::default()
+				.with_source(BertSource::Directory("tiny".into()))
+				.with_checksum(BertFiles::WEIGHTS, "0000"),
+		);
+		write_config(
+			"archive.bert.ron",
+			BertConfig::default()
+				.with_source(BertSource::Archive("tiny.beetmodel".into())),
+		);
+		files.clone().into_archive().save(dir.join("tiny.beetmodel")).unwrap();
+
+		let mut app = App::new();
+		app.add_plugins((TaskPoolPlugin::default(), AssetPlugin {
+			file_path: dir.to_string_lossy().into(),
+			..default()
+		}))
+		.init_asset::<Bert>()
+		.init_asset_loader::<BertLoader>();
+
+		let handle =
+			block_on_asset_load::<Bert>(&mut app, "directory.bert.ron")
+				.unwrap();
+		block_on_asset_load::<Bert>(&mut app, "archive.bert.ron").unwrap();
+		block_on_asset_load::<Bert>(&mut app, "tiny.beetmodel").unwrap();
+		expect(
+			block_on_asset_load::<Bert>(&mut app, "corrupt.bert.ron").is_err(),
+		)
+		.to_be_true();
+
+		let mut assets = app.world_mut().resource_mut::<Assets<Bert>>();
+		let embeddings = assets
+			.get_mut(&handle)
+			.unwrap()
+			.get_embeddings(vec!["the cat sits".into(), "a dog".into()])
+			.unwrap();
+		expect(embeddings.embeddings.dims().to_vec()).to_be(vec![2, 8]);
+		std::fs::remove_dir_all(&dir).ok();
+	}
 }
@@ -0,0 +1,122 @@
+use crate::prelude::*;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Where the [`BertLoader`] finds the model files.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BertSource {
+	/// Download with the hf-hub on native or the [`BertModelConfig`]
+	/// urls on wasm.
+	#[default]
+	Hub,
+	/// An asset directory containing a `config.json`, `tokenizer.json` and
+	/// `model.safetensors`, relative to the config asset.
+	Directory(String),
+	/// A `.beetmodel` archive containing the same files as a
+	/// [`BertSource::Directory`], relative to the config asset.
+	Archive(String),
+}
+
+/// The raw files required to create a [`Bert`].
+#[derive(Debug, Clone)]
+pub struct BertFiles {
+	/// The `config.json` of the model.
+	pub config: Vec<u8>,
+	/// The `tokenizer.json` of the model.
+	pub tokenizer: Vec<u8>,
+	/// The `model.safetensors` of the model.
+	pub weights: Vec<u8>,
+}
+
+impl BertFiles {
+	pub const CONFIG: &'static str = "config.json";
+	pub const TOKENIZER: &'static str = "tokenizer.json";
+	pub const WEIGHTS: &'static str = "model.safetensors";
+
+	/// Extract the files from an archive, the entry checksums
+	/// have already been verified by [`BeetModelArchive::from_bytes`].
+	pub fn from_archive(archive: &BeetModelArchive) -> Result<Self> {
+		Ok(Self {
+			config: archive.require(Self::CONFIG)?.to_vec(),
+			tokenizer: archive.require(Self::TOKENIZER)?.to_vec(),
+			weights: archive.require(Self::WEIGHTS)?.to_vec(),
+		})
+	}
+
+	/// Bundle the files into a `.beetmodel` archive.
+	pub fn into_archive(self) -> BeetModelArchive {
+		BeetModelArchive::new()
+			.with_entry(Self::CONFIG, self.config)
+			.with_entry(Self::TOKENIZER, self.tokenizer)
+			.with_entry(Self::WEIGHTS, self.weights)
+	}
+
+	fn get(&self, name: &str) -> Option<&[u8]> {
+		match name {
+			Self::CONFIG => Some(&self.config),
+			Self::TOKENIZER => Some(&self.tokenizer),
+			Self::WEIGHTS => Some(&self.weights),
+			_ => None,
+		}
+	}
+
+	/// The sha256 hex digest of each file, by file name.
+	pub fn checksums(&self) -> HashMap<String, String> {
+		[Self::CONFIG, Self::TOKENIZER, Self::WEIGHTS]
+			.into_iter()
+			.filter_map(|name| {
+				self.get(name)
+					.map(|bytes| (name.to_string(), sha256_hex(bytes)))
+			})
+			.collect()
+	}
+
+	/// Check each file against its expected sha256 hex digest,
+	/// files without a checksum are not verified.
+	pub fn verify(&self, checksums: &HashMap<String, String>) -> Result<()> {
+		for (name, expected) in checksums {
+			let Some(bytes) = self.get(name) else {
+				anyhow::bail!("checksum for unknown bert file: {name}");
+			};
+			let actual = sha256_hex(bytes);
+			if !actual.eq_ignore_ascii_case(expected) {
+				anyhow::bail!(
+					"checksum mismatch for {name}\nexpected: {expected}\nreceived: {actual}"
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn verify() {
+		let files = BertFiles {
+			config: b"{}".to_vec(),
+			tokenizer: b"{}".to_vec(),
+			weights: vec![1, 2, 3],
+		};
+		let checksums = files.checksums();
+		expect(checksums.len()).to_be(3);
+		expect(files.verify(&checksums).is_ok()).to_be_true();
+
+		let mut corrupt = files.clone();
+		corrupt.weights = vec![1, 2, 4];
+		expect(corrupt.verify(&checksums).is_err()).to_be_true();
+
+		let archive = BeetModelArchive::from_bytes(
+			&files.clone().into_archive().to_bytes(),
+		)
+		.unwrap();
+		expect(BertFiles::from_archive(&archive).unwrap().weights)
+			.to_be(files.weights);
+	}
+}
@@ -0,0 +1,141 @@
+use crate::prelude::*;
+use anyhow::Result;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::borrow::Cow;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// Sentence embeddings keyed by the hash of their text, so each
+/// distinct sentence is only passed through a [`Bert`] model once.
+///
+/// Entities using a text are tracked so that its embedding is dropped
+/// when the last [`Sentence`] with that text changes or is removed,
+/// see [`invalidate_sentence_embeddings`].
+#[derive(Debug, Default, Resource)]
+pub struct EmbeddingCache {
+	embeddings: HashMap<(AssetId<Bert>, u64), Vec<f32>>,
+	entities: HashMap<Entity, u64>,
+}
+
+impl EmbeddingCache {
+	pub fn text_hash(text: &str) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		text.hash(&mut hasher);
+		hasher.finish()
+	}
+
+	/// The number of cached embeddings.
+	pub fn len(&self) -> usize { self.embeddings.len() }
+	pub fn is_empty(&self) -> bool { self.embeddings.is_empty() }
+
+	pub fn get(&self, bert_id: AssetId<Bert>, text: &str) -> Option<&[f32]> {
+		self.embeddings
+			.get(&(bert_id, Self::text_hash(text)))
+			.map(|embedding| embedding.as_slice())
+	}
+
+	/// Get the embedding of a text, calculating it if not yet cached.
+	/// Each text is embedded on its own so the result does not depend
+	/// on the padding of other sentences in a batch.
+	pub fn embed(
+		&mut self,
+		bert_id: AssetId<Bert>,
+		bert: &mut Bert,
+		text: &Cow<'static, str>,
+	) -> Result<&[f32]> {
+		let key = (bert_id, Self::text_hash(text));
+		if !self.embeddings.contains_key(&key) {
+			let embedding = bert
+				.get_embeddings(vec![text.clone()])?
+				.embeddings
+				.get(0)?
+				.to_vec1::<f32>()?;
+			self.embeddings.insert(key, embedding);
+		}
+		Ok(&self.embeddings[&key])
+	}
+
+	/// Get the embedding of an entity's [`Sentence`], tracking the
+	/// entity so the embedding can be invalidated when it changes.
+	pub fn embed_entity(
+		&mut self,
+		bert_id: AssetId<Bert>,
+		bert: &mut Bert,
+		entity: Entity,
+		sentence: &Sentence,
+	) -> Result<&[f32]> {
+		let hash = Self::text_hash(&sentence.0);
+		if self.entities.get(&entity) != Some(&hash) {
+			self.invalidate(entity);
+			self.entities.insert(entity, hash);
+		}
+		self.embed(bert_id, bert, &sentence.0)
+	}
+
+	/// Stop tracking an entity, dropping the embeddings of its text
+	/// if no other tracked entity shares it.
+	pub fn invalidate(&mut self, entity: Entity) {
+		let Some(hash) = self.entities.remove(&entity) else {
+			return;
+		};
+		if !self.entities.values().any(|other| *other == hash) {
+			self.embeddings.retain(|(_, key), _| *key != hash);
+		}
+	}
+}
+
+/// Invalidate the cached embeddings and indexed vectors of any
+/// [`Sentence`] that has changed or been removed.
+pub fn invalidate_sentence_embeddings(
+	mut cache: ResMut<EmbeddingCache>,
+	mut index: ResMut<SentenceIndex>,
+	changed: Query<Entity, Changed<Sentence>>,
+	mut removed: RemovedComponents<Sentence>,
+) {
+	for entity in changed.iter().chain(removed.read()) {
+		cache.invalidate(entity);
+		index.remove(entity);
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut bert =
+			Bert::from_files(BertConfig::default(), tiny_bert_files().unwrap())
+				.unwrap();
+		let bert_id = AssetId::<Bert>::default();
+		let mut cache = EmbeddingCache::default();
+		let cat = Entity::from_raw(0);
+		let other_cat = Entity::from_raw(1);
+
+		let embedding = cache
+			.embed_entity(bert_id, &mut bert, cat, &Sentence::new("cat"))
+			.unwrap()
+			.to_vec();
+		expect(embedding.len()).to_be(8);
+		cache
+			.embed_entity(bert_id, &mut bert, other_cat, &Sentence::new("cat"))
+			.unwrap();
+		expect(cache.len()).to_be(1);
+		expect(cache.get(bert_id, "cat")).to_be(Some(embedding.as_slice()));
+
+		// still used by other_cat
+		cache.invalidate(cat);
+		expect(cache.len()).to_be(1);
+		// changing the sentence drops the last reference
+		cache
+			.embed_entity(bert_id, &mut bert, other_cat, &Sentence::new("dog"))
+			.unwrap();
+		expect(cache.get(bert_id, "cat")).to_be_none();
+		expect(cache.len()).to_be(1);
+	}
+}
@@ -1,9 +1,15 @@
+mod beet_model_archive;
+pub use self::beet_model_archive::*;
 mod bert;
 pub use self::bert::*;
 mod bert_config;
 pub use self::bert_config::*;
 mod bert_loader;
 pub use self::bert_loader::*;
+mod bert_source;
+pub use self::bert_source::*;
+mod embedding_cache;
+pub use self::embedding_cache::*;
 #[cfg(feature = "spatial")]
 pub mod sentence_steer_target;
 #[cfg(feature = "spatial")]
@@ -12,3 +18,5 @@ mod nearest_sentence;
 pub use self::nearest_sentence::*;
 mod sentence_embeddings;
 pub use self::sentence_embeddings::*;
+mod sentence_index;
+pub use self::sentence_index::*;
@@ -4,6 +4,8 @@ use bevy::prelude::*;
 
 /// Runs the child with the [`Sentence`] that is most similar to that of the agent.
 /// for use with [`ScoreFlow`]
+/// Sentences are embedded once by the [`SentenceIndex`], so each run
+/// only queries the index.
 #[action(nearest_sentence)]
 #[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
 #[reflect(Component)]
@@ -17,26 +19,24 @@ impl NearestSentence {
 fn nearest_sentence(
 	ev: Trigger<OnRun>,
 	mut commands: Commands,
-	mut berts: ResMut<Assets<Bert>>,
-	sentences: Query<&Sentence>,
-	// TODO double query, ie added running and added asset
-	query: Query<(
-		&NearestSentence,
-		&Sentence,
-		&HandleWrapper<Bert>,
-		&Children,
-	)>,
+	index: Res<SentenceIndex>,
+	parents: Query<&ChildOf>,
+	query: Query<(&NearestSentence, &HandleWrapper<Bert>, &Children)>,
 ) {
-	let (_scorer, target_sentence, handle, children) = query
+	let (_scorer, handle, children) = query
 		.get(ev.action)
 		.expect(&expect_action::to_have_action(&ev));
-	let bert = berts
-		.get_mut(handle)
-		.expect(&expect_action::to_have_asset(&ev));
-	match bert.closest_sentence_entity(
-		target_sentence.0.clone(),
-		children.iter().map(|e| e.clone()),
-		&sentences,
+	let filter = |entity: Entity| {
+		parents
+			.get(entity)
+			.map(|child_of| child_of.parent() == ev.action)
+			.unwrap_or(false)
+	};
+	match index.nearest_sentence(
+		handle.id(),
+		ev.action,
+		&filter,
+		children.iter(),
 	) {
 		Ok(entity) => {
 			ev.trigger_next(&mut commands, entity);
@@ -53,40 +53,45 @@ mod test {
 	use sweet::prelude::*;
 
 	#[test]
-	#[ignore = "we need to get sentence_flow back up and running, ie the req/res model like score"]
 	fn works() {
-		pretty_env_logger::try_init().ok();
-
 		let mut app = App::new();
 		app.add_plugins((
 			MinimalPlugins,
-			workspace_asset_plugin(),
+			AssetPlugin::default(),
 			LanguagePlugin::default(),
 			BeetFlowPlugin::default(),
 		))
 		.finish();
 		let on_run = observe_trigger_names::<OnRun>(app.world_mut());
 
-		let handle =
-			block_on_asset_load::<Bert>(&mut app, "ml/default-bert.ron")
+		let bert =
+			Bert::from_files(BertConfig::default(), tiny_bert_files().unwrap())
 				.unwrap();
+		let handle = app.world_mut().resource_mut::<Assets<Bert>>().add(bert);
 
-		app.world_mut()
+		let root = app
+			.world_mut()
 			.spawn((
 				Name::new("root"),
-				Sentence::new("destroy"),
+				Sentence::new("the cat sits"),
 				HandleWrapper(handle),
 				NearestSentence::default(),
 			))
 			.with_children(|parent| {
-				parent.spawn((Name::new("heal"), Sentence::new("heal")));
-				parent.spawn((Name::new("kill"), Sentence::new("kill")));
+				parent.spawn((Name::new("dog"), Sentence::new("a dog runs")));
+				parent.spawn((Name::new("cat"), Sentence::new("the cat sits")));
 			})
+			.id();
+		// index the sentences
+		app.update();
+		app.world_mut()
+			.entity_mut(root)
 			.flush_trigger(OnRun::local());
 
-
 		expect(&on_run).to_have_been_called_times(2);
 		expect(&on_run).to_have_returned_nth_with(0, &"root".to_string());
-		expect(&on_run).to_have_returned_nth_with(1, &"kill".to_string());
+		expect(&on_run).to_have_returned_nth_with(1, &"cat".to_string());
+		// every sentence is indexed, the target shares a cached embedding
+		expect(app.world().resource::<EmbeddingCache>().len()).to_be(2);
 	}
 }
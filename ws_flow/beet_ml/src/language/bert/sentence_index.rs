@@ -0,0 +1,238 @@
+use crate::prelude::*;
+use anyhow::Result;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// The number of neighbors requested before falling back to
+/// comparing every candidate.
+const NEAREST_K: usize = 16;
+
+/// A k-nearest index of [`Sentence`] entity embeddings, with a separate
+/// [`VectorIndex`] for each [`Bert`] model.
+/// Every [`Sentence`] is indexed once when it is added or changed, and
+/// removed when it changes or is removed, see [`index_sentence_embeddings`]
+/// and [`invalidate_sentence_embeddings`].
+///
+/// The default [`HnswIndex`] keeps queries `O(log n)` in the number
+/// of sentences. Queries are filtered to a set of candidates, ie the
+/// children of an action, and compared directly when none of the
+/// approximate results pass the filter.
+#[derive(Resource)]
+pub struct SentenceIndex {
+	create_index: fn() -> Box<dyn VectorIndex>,
+	indices: HashMap<AssetId<Bert>, Box<dyn VectorIndex>>,
+}
+
+impl Default for SentenceIndex {
+	fn default() -> Self { Self::hnsw() }
+}
+
+impl SentenceIndex {
+	/// Approximate queries, see [`HnswIndex`].
+	pub fn hnsw() -> Self { Self::new(|| Box::new(HnswIndex::default())) }
+	/// Exact queries, see [`BruteForceIndex`].
+	pub fn brute_force() -> Self {
+		Self::new(|| Box::new(BruteForceIndex::default()))
+	}
+
+	/// Create an index, calling `create_index` for each [`Bert`] model.
+	pub fn new(create_index: fn() -> Box<dyn VectorIndex>) -> Self {
+		Self {
+			create_index,
+			indices: default(),
+		}
+	}
+
+	/// The index for a model, if any sentences have been indexed with it.
+	pub fn get(&self, bert_id: AssetId<Bert>) -> Option<&dyn VectorIndex> {
+		self.indices.get(&bert_id).map(|index| index.as_ref())
+	}
+
+	/// Whether the entity has been indexed with the model.
+	pub fn contains(&self, bert_id: AssetId<Bert>, entity: Entity) -> bool {
+		self.get(bert_id)
+			.map(|index| index.get(entity).is_some())
+			.unwrap_or(false)
+	}
+
+	/// Insert or replace the embedding of an entity.
+	pub fn insert(
+		&mut self,
+		bert_id: AssetId<Bert>,
+		entity: Entity,
+		embedding: Vec<f32>,
+	) {
+		self.indices
+			.entry(bert_id)
+			.or_insert_with(self.create_index)
+			.insert(entity, embedding);
+	}
+
+	/// Embed the [`Sentence`] of an entity with the [`EmbeddingCache`]
+	/// and insert it into the index.
+	pub fn insert_sentence(
+		&mut self,
+		cache: &mut EmbeddingCache,
+		bert_id: AssetId<Bert>,
+		bert: &mut Bert,
+		entity: Entity,
+		sentence: &Sentence,
+	) -> Result<()> {
+		let embedding = cache.embed_entity(bert_id, bert, entity, sentence)?;
+		self.insert(bert_id, entity, embedding.to_vec());
+		Ok(())
+	}
+
+	/// Remove the entity from the index of every model.
+	pub fn remove(&mut self, entity: Entity) {
+		for index in self.indices.values_mut() {
+			index.remove(entity);
+		}
+	}
+
+	/// Returns the indexed entity passing the `filter` with the embedding
+	/// most similar to that of the target entity. The `candidates` are
+	/// only iterated if none of the nearest neighbors pass the filter.
+	/// # Errors
+	/// If the target is not indexed or there are no indexed candidates.
+	pub fn nearest_sentence(
+		&self,
+		bert_id: AssetId<Bert>,
+		target: Entity,
+		filter: &dyn Fn(Entity) -> bool,
+		candidates: impl IntoIterator<Item = Entity>,
+	) -> Result<Entity> {
+		let Some(index) = self.get(bert_id) else {
+			anyhow::bail!("no sentences indexed for this model");
+		};
+		let Some(query) = index.get(target) else {
+			anyhow::bail!("target sentence is not indexed");
+		};
+		if let Some((entity, _)) =
+			index.nearest(query, NEAREST_K, filter).first()
+		{
+			return Ok(*entity);
+		}
+		// the candidates may be a small subset of the index, in which
+		// case compare them directly
+		let similarity = |embedding: &[f32]| -> f32 {
+			embedding.iter().zip(query).map(|(a, b)| a * b).sum()
+		};
+		candidates
+			.into_iter()
+			.filter(|entity| filter(*entity))
+			.filter_map(|entity| {
+				let embedding = index.get(entity)?;
+				Some((entity, similarity(embedding)))
+			})
+			.max_by(|a, b| a.1.total_cmp(&b.1))
+			.map(|(entity, _)| entity)
+			.ok_or_else(|| anyhow::anyhow!("no candidates with a sentence"))
+	}
+}
+
+/// Index the [`Sentence`] of each added or changed entity with every
+/// loaded [`Bert`] model, indexing all sentences for newly loaded models.
+/// Runs after [`invalidate_sentence_embeddings`] in the [`PreTickSet`].
+pub fn index_sentence_embeddings(
+	mut berts: ResMut<Assets<Bert>>,
+	mut cache: ResMut<EmbeddingCache>,
+	mut index: ResMut<SentenceIndex>,
+	changed: Query<(Entity, &Sentence), Changed<Sentence>>,
+	sentences: Query<(Entity, &Sentence)>,
+) {
+	let bert_ids = berts.ids().collect::<Vec<_>>();
+	for bert_id in bert_ids {
+		let entities = if index.get(bert_id).is_none() {
+			sentences.iter().collect::<Vec<_>>()
+		} else {
+			changed.iter().collect()
+		};
+		if entities.is_empty() {
+			continue;
+		}
+		// get_mut marks the asset as modified, so only borrow when needed
+		let Some(bert) = berts.get_mut(bert_id) else {
+			continue;
+		};
+		for (entity, sentence) in entities {
+			if let Err(err) = index
+				.insert_sentence(&mut cache, bert_id, bert, entity, sentence)
+			{
+				log::error!("failed to index sentence: {err}");
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut bert =
+			Bert::from_files(BertConfig::default(), tiny_bert_files().unwrap())
+				.unwrap();
+		let bert_id = AssetId::<Bert>::default();
+		let mut cache = EmbeddingCache::default();
+		let mut index = SentenceIndex::default();
+
+		let dog = Entity::from_raw(0);
+		let cat = Entity::from_raw(1);
+		let target = Entity::from_raw(2);
+		for (entity, text) in [
+			(dog, "a dog runs"),
+			(cat, "the cat sits"),
+			(target, "the cat sits"),
+		] {
+			index
+				.insert_sentence(
+					&mut cache,
+					bert_id,
+					&mut bert,
+					entity,
+					&Sentence::new(text),
+				)
+				.unwrap();
+		}
+		expect(index.get(bert_id).unwrap().len()).to_be(3);
+		// the target shares its text with a candidate
+		expect(cache.len()).to_be(2);
+
+		let candidates = [dog, cat];
+		let filter = |entity: Entity| candidates.contains(&entity);
+		expect(
+			index
+				.nearest_sentence(bert_id, target, &filter, candidates)
+				.unwrap(),
+		)
+		.to_be(cat);
+
+		// only the first candidate is considered
+		expect(
+			index
+				.nearest_sentence(bert_id, target, &|e| e == dog, [dog])
+				.unwrap(),
+		)
+		.to_be(dog);
+
+		index.remove(cat);
+		expect(index.contains(bert_id, cat)).to_be_false();
+		expect(
+			index
+				.nearest_sentence(bert_id, target, &|e| e == cat, [cat])
+				.is_err(),
+		)
+		.to_be_true();
+		expect(
+			index
+				.nearest_sentence(bert_id, cat, &filter, candidates)
+				.is_err(),
+		)
+		.to_be_true();
+	}
+}
@@ -8,6 +8,8 @@ use bevy::prelude::*;
 /// Finds the [`Sentence`] with the highest similarity to the agent's,
 /// then set it as the agent's [`SteerTarget`].
 /// The generic parameter is used to [`With`] filter the entities to consider.
+/// Sentences are embedded once by the [`SentenceIndex`], so each run
+/// only queries the index.
 #[action(sentence_steer_target::<F>)]
 #[derive(Debug, Clone, PartialEq, Component, Reflect)]
 #[reflect(Default, Component)]
@@ -45,11 +47,10 @@ fn sentence_steer_target<F: Component>(
 	ev: Trigger<OnRun>,
 	mut commands: Commands,
 	query: Query<(&HandleWrapper<Bert>, &SentenceSteerTarget<F>)>,
-	sentences: Query<&Sentence>,
 	// TODO this should be query of Sentence, but we need
 	// it to be similar to sentence_scorer
 	items: Query<Entity, (With<Sentence>, With<F>)>,
-	mut berts: ResMut<Assets<Bert>>,
+	index: Res<SentenceIndex>,
 ) {
 	let (handle, sentence_steer_target) = query
 		.get(ev.action())
@@ -57,21 +58,14 @@ fn sentence_steer_target<F: Component>(
 
 	let target_entity = sentence_steer_target.target_entity.get_target(&*ev);
 
-	let target_sentence = sentences
-		.get(target_entity)
-		.expect(&expect_action::to_have_other(&ev));
-
-	let bert = berts
-		.get_mut(handle)
-		.expect(&expect_action::to_have_asset(&ev));
-
-	match bert.closest_sentence_entity(
-		target_sentence.0.clone(),
-		items
-			.into_iter()
-			.filter(|e| *e != target_entity)
-			.collect::<Vec<_>>(),
-		&sentences,
+	let filter =
+		|entity: Entity| entity != target_entity && items.contains(entity);
+
+	match index.nearest_sentence(
+		handle.id(),
+		target_entity,
+		&filter,
+		items.iter(),
 	) {
 		Ok(entity) => {
 			commands
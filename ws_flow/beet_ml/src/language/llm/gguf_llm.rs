@@ -0,0 +1,176 @@
+use crate::prelude::*;
+use anyhow::Error as E;
+use anyhow::Result;
+use candle_core::Device;
+use candle_core::Tensor;
+use candle_core::quantized::gguf_file;
+use candle_transformers::generation::LogitsProcessor;
+use candle_transformers::models::quantized_llama::ModelWeights;
+use std::io::Read;
+use std::io::Seek;
+use tokenizers::Tokenizer;
+
+/// A quantized llama-architecture model loaded from a `.gguf` file,
+/// running on the cpu. Sampling is greedy so the same prompt always
+/// gives the same completion.
+pub struct GgufLlm {
+	model: ModelWeights,
+	tokenizer: Tokenizer,
+	device: Device,
+	logits_processor: LogitsProcessor,
+	eos_token: Option<u32>,
+}
+
+impl GgufLlm {
+	/// End of sequence tokens checked in order when none is specified.
+	const EOS_TOKENS: &'static [&'static str] = &[
+		"<|eot_id|>",
+		"<|end_of_text|>",
+		"<|im_end|>",
+		"<|endoftext|>",
+		"</s>",
+	];
+
+	/// Load from the `.gguf` weights and `tokenizer.json` of the model.
+	pub fn from_reader<R: Read + Seek>(
+		gguf: &mut R,
+		tokenizer: &[u8],
+	) -> Result<Self> {
+		let device = Device::Cpu;
+		let content = gguf_file::Content::read(gguf)?;
+		let model = ModelWeights::from_gguf(content, gguf, &device)?;
+		let tokenizer = Tokenizer::from_bytes(tokenizer).map_err(E::msg)?;
+		let eos_token = Self::eos_token(&tokenizer);
+		Ok(Self {
+			model,
+			tokenizer,
+			device,
+			logits_processor: LogitsProcessor::new(0, None, None),
+			eos_token,
+		})
+	}
+
+	pub fn from_bytes(gguf: &[u8], tokenizer: &[u8]) -> Result<Self> {
+		Self::from_reader(&mut std::io::Cursor::new(gguf), tokenizer)
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn from_files(
+		gguf: impl AsRef<std::path::Path>,
+		tokenizer: impl AsRef<std::path::Path>,
+	) -> Result<Self> {
+		let mut file = std::fs::File::open(gguf)?;
+		Self::from_reader(&mut file, &std::fs::read(tokenizer)?)
+	}
+
+	/// The first of [`Self::EOS_TOKENS`] in the vocabulary.
+	fn eos_token(tokenizer: &Tokenizer) -> Option<u32> {
+		Self::EOS_TOKENS
+			.iter()
+			.find_map(|token| tokenizer.token_to_id(token))
+	}
+
+	/// Whether the completion has finished its first non-empty line.
+	fn is_complete(text: &str) -> bool { text.trim_start().contains('\n') }
+
+	/// Stop generating at the given token instead of the detected one.
+	pub fn with_eos_token(mut self, token: &str) -> Result<Self> {
+		self.eos_token =
+			Some(self.tokenizer.token_to_id(token).ok_or_else(|| {
+				anyhow::anyhow!("token not in vocabulary: {token}")
+			})?);
+		Ok(self)
+	}
+}
+
+impl LlmBackend for GgufLlm {
+	/// Generation also stops at the end of the first non-empty line,
+	/// as the selector only needs a short answer.
+	fn complete(&mut self, prompt: &str, max_tokens: usize) -> Result<String> {
+		let mut input = self
+			.tokenizer
+			.encode(prompt, true)
+			.map_err(E::msg)?
+			.get_ids()
+			.to_vec();
+		let mut generated = Vec::new();
+		// an index_pos of zero resets the kv cache
+		let mut index_pos = 0;
+		for _ in 0..max_tokens {
+			let tokens = Tensor::new(input.as_slice(), &self.device)?;
+			let logits = self
+				.model
+				.forward(&tokens.unsqueeze(0)?, index_pos)?
+				.squeeze(0)?;
+			index_pos += input.len();
+			let next = self.logits_processor.sample(&logits)?;
+			if Some(next) == self.eos_token {
+				break;
+			}
+			generated.push(next);
+			input = vec![next];
+			let text =
+				self.tokenizer.decode(&generated, true).map_err(E::msg)?;
+			if Self::is_complete(&text) {
+				break;
+			}
+		}
+		self.tokenizer.decode(&generated, true).map_err(E::msg)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+	use tokenizers::Tokenizer;
+
+	const TOKENIZER: &str = r#"{
+	"version": "1.0",
+	"truncation": null,
+	"padding": null,
+	"added_tokens": [],
+	"normalizer": null,
+	"pre_tokenizer": { "type": "Whitespace" },
+	"post_processor": null,
+	"decoder": null,
+	"model": {
+		"type": "WordLevel",
+		"vocab": {
+			"[UNK]": 0,
+			"eat": 1,
+			"sleep": 2,
+			"</s>": 3,
+			"<|im_end|>": 4
+		},
+		"unk_token": "[UNK]"
+	}
+}"#;
+
+	#[test]
+	fn eos_token() {
+		let tokenizer = Tokenizer::from_bytes(TOKENIZER).unwrap();
+		// earlier tokens in the list take precedence
+		expect(GgufLlm::eos_token(&tokenizer)).to_be(Some(4));
+	}
+
+	#[test]
+	fn is_complete() {
+		expect(GgufLlm::is_complete(" eat")).to_be_false();
+		expect(GgufLlm::is_complete("\neat")).to_be_false();
+		expect(GgufLlm::is_complete("eat\n")).to_be_true();
+	}
+
+	#[test]
+	fn parses_completion() {
+		let options = vec!["eat".to_string(), "sleep".to_string()];
+		let prompt = LlmSelector::default()
+			.prompt(&[], None, &[("eat".into(), None), ("sleep".into(), None)]);
+		expect(prompt.as_str()).to_contain("Actions:\n1. eat\n2. sleep\n");
+		// the model continues the prompt with its answer
+		expect(prompt.as_str()).to_end_with("\nAction:");
+		// completions stop at the first newline, so the answer is one line
+		expect(LlmSelector::parse_choice(" sleep\n", &options)).to_be(Some(1));
+		expect(LlmSelector::parse_choice(" 1\n", &options)).to_be(Some(0));
+	}
+}
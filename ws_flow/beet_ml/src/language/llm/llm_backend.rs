@@ -0,0 +1,81 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// A text completion model used by the
+/// [`LlmSelector`](crate::prelude::LlmSelector).
+/// Implement this to swap in a remote backend, ie an http api.
+pub trait LlmBackend: 'static + Send + Sync {
+	/// Continue the prompt, generating at most `max_tokens` tokens.
+	fn complete(&mut self, prompt: &str, max_tokens: usize) -> Result<String>;
+}
+
+/// The [`LlmBackend`] used by language model actions,
+/// this must be inserted by the user.
+/// The backend is shared so that completions can run in a task.
+#[derive(Clone, Resource)]
+pub struct Llm(Arc<Mutex<Box<dyn LlmBackend>>>);
+
+impl Llm {
+	pub fn new(backend: impl LlmBackend) -> Self {
+		Self(Arc::new(Mutex::new(Box::new(backend))))
+	}
+
+	/// Continue the prompt, blocking until the backend is available and
+	/// has finished, so this should usually be called from a task.
+	pub fn complete(&self, prompt: &str, max_tokens: usize) -> Result<String> {
+		self.0
+			.lock()
+			.map_err(|_| anyhow::anyhow!("Llm: backend mutex poisoned"))?
+			.complete(prompt, max_tokens)
+	}
+}
+
+/// A deterministic [`LlmBackend`] for tests, answering with the
+/// response of the first rule whose pattern appears in the prompt.
+#[derive(Debug, Default, Clone)]
+pub struct MockLlm {
+	rules: Vec<(String, String)>,
+	default_response: String,
+	/// Every prompt received, shared between clones so they can be
+	/// inspected after the backend is moved into an [`Llm`].
+	pub prompts: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockLlm {
+	/// Create a mock that always gives the same response.
+	pub fn new(default_response: impl Into<String>) -> Self {
+		Self {
+			default_response: default_response.into(),
+			..default()
+		}
+	}
+
+	/// Respond with `response` when the prompt contains `pattern`.
+	pub fn with_rule(
+		mut self,
+		pattern: impl Into<String>,
+		response: impl Into<String>,
+	) -> Self {
+		self.rules.push((pattern.into(), response.into()));
+		self
+	}
+
+	pub fn prompts(&self) -> Vec<String> {
+		self.prompts.lock().unwrap().clone()
+	}
+}
+
+impl LlmBackend for MockLlm {
+	fn complete(&mut self, prompt: &str, _max_tokens: usize) -> Result<String> {
+		self.prompts.lock().unwrap().push(prompt.to_string());
+		let response = self
+			.rules
+			.iter()
+			.find(|(pattern, _)| prompt.contains(pattern.as_str()))
+			.map(|(_, response)| response)
+			.unwrap_or(&self.default_response);
+		Ok(response.clone())
+	}
+}
@@ -0,0 +1,357 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+
+/// Registers the [`LlmSelector`], added by the [`LanguagePlugin`].
+/// Pending completions are polled as an [`AsyncActionTask`]
+/// by the [`BeetFlowPlugin`].
+#[derive(Default)]
+pub struct LlmPlugin;
+
+impl Plugin for LlmPlugin {
+	fn build(&self, app: &mut App) { app.register_type::<LlmSelector>(); }
+}
+
+/// Prompts the [`Llm`] with the agent's [`Blackboard`] context and the
+/// available children, then runs the child it chooses.
+///
+/// Each child is described by its [`Name`] and optional [`Sentence`],
+/// and the [`Sentence`] of this action, ie set by [`RunWithUserSentence`],
+/// is included as the request. Values in the [`origin`](OnRun::origin)
+/// blackboard override those of the tree blackboard.
+///
+/// The model runs in an [`AsyncActionTask`] on the [`AsyncComputeTaskPool`],
+/// which requires the [`TaskPoolPlugin`], and the action keeps [`Running`]
+/// until it answers and the chosen child returns a result.
+/// The completion is cancelled if the action is interrupted.
+/// Returns [`RunResult::Failure`] if the model fails or its answer
+/// does not name a child.
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// ## Example
+/// ```
+/// # use beet_flow::prelude::*;
+/// # use beet_ml::prelude::*;
+/// # use bevy::prelude::*;
+/// let mut app = App::new();
+/// app.add_plugins((
+/// 	TaskPoolPlugin::default(),
+/// 	BeetFlowPlugin::default(),
+/// 	LlmPlugin,
+/// ))
+/// .insert_resource(Llm::new(MockLlm::new("eat")));
+/// app.world_mut()
+/// 	.spawn((
+/// 		LlmSelector::default(),
+/// 		Blackboard::default().with("hunger", 0.9),
+/// 	))
+/// 	.with_children(|parent| {
+/// 		parent.spawn((Name::new("eat"), Sentence::new("eat some food")));
+/// 		parent.spawn((Name::new("sleep"), Sentence::new("go to bed")));
+/// 	})
+/// 	.trigger(OnRun::local());
+/// app.update();
+/// ```
+#[action(llm_selector)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(ContinueRun, BubbleResult)]
+pub struct LlmSelector {
+	/// Placed at the start of the prompt, describing the agent's role.
+	pub instructions: String,
+	/// The maximum number of tokens the model may generate.
+	pub max_tokens: usize,
+}
+
+impl Default for LlmSelector {
+	fn default() -> Self {
+		Self {
+			instructions: "You are choosing the next action for an agent."
+				.into(),
+			max_tokens: 16,
+		}
+	}
+}
+
+impl LlmSelector {
+	pub fn new(instructions: impl Into<String>) -> Self {
+		Self {
+			instructions: instructions.into(),
+			..default()
+		}
+	}
+
+	/// Build the prompt from the context key-value pairs, the optional
+	/// request and the name and optional description of each option.
+	pub fn prompt(
+		&self,
+		context: &[(String, String)],
+		request: Option<&str>,
+		options: &[(String, Option<String>)],
+	) -> String {
+		let mut prompt = format!("{}\n\n", self.instructions);
+		if !context.is_empty() {
+			prompt.push_str("Context:\n");
+			for (key, value) in context {
+				prompt.push_str(&format!("- {key}: {value}\n"));
+			}
+			prompt.push('\n');
+		}
+		if let Some(request) = request {
+			prompt.push_str(&format!("Request: {request}\n\n"));
+		}
+		prompt.push_str("Actions:\n");
+		for (index, (name, description)) in options.iter().enumerate() {
+			let number = index + 1;
+			match description {
+				Some(description) => prompt
+					.push_str(&format!("{number}. {name}: {description}\n")),
+				None => prompt.push_str(&format!("{number}. {name}\n")),
+			}
+		}
+		prompt.push_str("\nReply with the name of one action.\nAction:");
+		prompt
+	}
+
+	/// Find the option chosen by the model, accepting an exact name,
+	/// an option number, or the earliest name mentioned in the answer.
+	pub fn parse_choice(answer: &str, options: &[String]) -> Option<usize> {
+		let answer = answer.trim().to_lowercase();
+		let first_line = answer.lines().next().unwrap_or_default();
+		let first_line =
+			first_line.trim_matches(|c: char| !c.is_alphanumeric());
+		let names = options
+			.iter()
+			.map(|name| name.to_lowercase())
+			.collect::<Vec<_>>();
+
+		if let Some(index) = names.iter().position(|name| name == first_line) {
+			return Some(index);
+		}
+		if let Ok(number) = first_line.parse::<usize>()
+			&& (1..=options.len()).contains(&number)
+		{
+			return Some(number - 1);
+		}
+		names
+			.iter()
+			.enumerate()
+			.filter(|(_, name)| !name.is_empty())
+			.filter_map(|(index, name)| {
+				answer.find(name.as_str()).map(|pos| (pos, index))
+			})
+			// prefer the earliest, then longest match
+			.min_by_key(|(pos, index)| (*pos, usize::MAX - names[*index].len()))
+			.map(|(_, index)| index)
+	}
+}
+
+/// Format a [`BlackboardValue`] for a prompt.
+fn format_value(value: &BlackboardValue) -> String {
+	match value {
+		BlackboardValue::Bool(value) => value.to_string(),
+		BlackboardValue::Int(value) => value.to_string(),
+		BlackboardValue::Float(value) => value.to_string(),
+		BlackboardValue::String(value) => format!("\"{value}\""),
+		BlackboardValue::Vec3(value) => {
+			format!("({}, {}, {})", value.x, value.y, value.z)
+		}
+		BlackboardValue::Entity(value) => value.to_string(),
+	}
+}
+
+fn llm_selector(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	llm: Res<Llm>,
+	query: Query<(&LlmSelector, Option<&Sentence>, &Children)>,
+	children: Query<(Option<&Name>, Option<&Sentence>)>,
+	blackboard_query: BlackboardQuery,
+	blackboards: Query<&Blackboard>,
+) {
+	let (selector, request, child_entities) = query
+		.get(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+
+	let mut context = bevy::platform::collections::HashMap::new();
+	let tree_blackboard = blackboard_query.tree_blackboard(ev.action);
+	// the origin is applied last so it overrides the tree
+	for entity in tree_blackboard.into_iter().chain([ev.origin]) {
+		if let Ok(blackboard) = blackboards.get(entity) {
+			for (key, value) in blackboard.iter() {
+				context.insert(key.clone(), format_value(value));
+			}
+		}
+	}
+	let mut context = context.into_iter().collect::<Vec<_>>();
+	context.sort();
+
+	let options = child_entities
+		.iter()
+		.map(|child| {
+			let (name, sentence) = children.get(child).unwrap_or_default();
+			let name = name
+				.map(|name| name.to_string())
+				.unwrap_or_else(|| child.to_string());
+			(name, sentence.map(|sentence| sentence.0.to_string()))
+		})
+		.collect::<Vec<_>>();
+
+	let prompt = selector.prompt(
+		&context,
+		request.map(|sentence| sentence.0.as_ref()),
+		&options,
+	);
+	let names = options
+		.into_iter()
+		.map(|(name, _)| name)
+		.collect::<Vec<_>>();
+
+	let llm = llm.clone();
+	let max_tokens = selector.max_tokens;
+	let children = child_entities.iter().collect::<Vec<_>>();
+	let task = AsyncActionTask::new(AsyncComputeTaskPool::get(), async move {
+		let answer = llm.complete(&prompt, max_tokens);
+		AsyncActionOutput::new(move |commands, action, running| match answer {
+			Ok(answer) => match LlmSelector::parse_choice(&answer, &names) {
+				Some(index) => {
+					commands.trigger(OnRunAction::new(
+						children[index],
+						running.origin,
+						(),
+					));
+				}
+				None => {
+					log::warn!(
+						"LlmSelector: no action matches answer: {answer}"
+					);
+					running.trigger_result(
+						commands,
+						action,
+						RunResult::Failure,
+					);
+				}
+			},
+			Err(err) => {
+				log::error!("LlmSelector: {err}");
+				running.trigger_result(commands, action, RunResult::Failure);
+			}
+		})
+	});
+	// any previous task is dropped and cancelled
+	commands.entity(ev.action).insert(task);
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn options() -> Vec<String> {
+		vec!["eat".into(), "sleep".into(), "eat cake".into()]
+	}
+
+	/// the model runs on another thread so give it a few frames
+	fn update_until_answered(app: &mut App, action: Entity) {
+		for _ in 0..100 {
+			app.update();
+			if app.world().get::<AsyncActionTask>(action).is_none() {
+				return;
+			}
+			std::thread::sleep(std::time::Duration::from_millis(1));
+		}
+	}
+
+	#[test]
+	fn prompt() {
+		let prompt = LlmSelector::new("Choose.").prompt(
+			&[("hunger".into(), "0.9".into())],
+			Some("what next?"),
+			&[
+				("eat".into(), Some("eat some food".into())),
+				("sleep".into(), None),
+			],
+		);
+		expect(prompt.as_str()).to_be_str(concat!(
+			"Choose.\n\n",
+			"Context:\n- hunger: 0.9\n\n",
+			"Request: what next?\n\n",
+			"Actions:\n1. eat: eat some food\n2. sleep\n\n",
+			"Reply with the name of one action.\nAction:",
+		));
+	}
+
+	#[test]
+	fn parse_choice() {
+		expect(LlmSelector::parse_choice(" Sleep.\n", &options()))
+			.to_be(Some(1));
+		expect(LlmSelector::parse_choice("3", &options())).to_be(Some(2));
+		expect(LlmSelector::parse_choice("I would eat cake", &options()))
+			.to_be(Some(2));
+		expect(LlmSelector::parse_choice("9", &options())).to_be_none();
+		expect(LlmSelector::parse_choice("dance", &options())).to_be_none();
+	}
+
+	#[test]
+	fn works() {
+		let mock = MockLlm::new("dance").with_rule("hunger: 0.9", "eat");
+		let mut app = App::new();
+		app.add_plugins((
+			TaskPoolPlugin::default(),
+			BeetFlowPlugin::default(),
+			LlmPlugin,
+		))
+		.insert_resource(Llm::new(mock.clone()));
+		let world = app.world_mut();
+		let on_run = observe_trigger_names::<OnRun>(world);
+
+		let agent = world.spawn(Blackboard::default().with("hunger", 0.9)).id();
+		let root = world
+			.spawn((
+				Name::new("root"),
+				LlmSelector::default(),
+				Sentence::new("what next?"),
+				Blackboard::default()
+					.with("hunger", 0.1)
+					.with("location", "kitchen"),
+			))
+			.with_children(|parent| {
+				parent.spawn((
+					Name::new("sleep"),
+					Sentence::new("go to bed"),
+					ReturnWith(RunResult::Success),
+				));
+				parent.spawn((
+					Name::new("eat"),
+					Sentence::new("eat some food"),
+					ReturnWith(RunResult::Success),
+				));
+			})
+			.id();
+		world.flush_trigger(OnRunAction::new(root, agent, ()));
+		// the child runs once the model has answered
+		expect(&on_run).to_have_been_called_times(1);
+		expect(app.world().get::<Running>(root)).to_be_some();
+		update_until_answered(&mut app, root);
+
+		expect(&on_run).to_have_been_called_times(2);
+		expect(&on_run).to_have_returned_nth_with(1, &"eat".to_string());
+		let prompt = mock.prompts()[0].clone();
+		expect(prompt.as_str()).to_contain("- hunger: 0.9");
+		expect(prompt.as_str()).to_contain("- location: \"kitchen\"");
+		expect(prompt.as_str()).to_contain("Request: what next?");
+		expect(prompt.as_str()).to_contain("2. eat: eat some food");
+
+		// the tree blackboard alone does not match, so no child runs
+		app.world_mut()
+			.entity_mut(root)
+			.flush_trigger(OnRun::local());
+		update_until_answered(&mut app, root);
+		expect(&on_run).to_have_been_called_times(3);
+		expect(app.world().get::<Running>(root)).to_be_none();
+		expect(mock.prompts()[1].as_str()).to_contain("- hunger: 0.1");
+	}
+}
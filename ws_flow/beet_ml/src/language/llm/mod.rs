@@ -0,0 +1,8 @@
+#[cfg(feature = "candle")]
+mod gguf_llm;
+#[cfg(feature = "candle")]
+pub use self::gguf_llm::*;
+mod llm_backend;
+pub use self::llm_backend::*;
+mod llm_selector;
+pub use self::llm_selector::*;
@@ -2,8 +2,12 @@
 mod bert;
 #[cfg(feature = "candle")]
 pub use self::bert::*;
+mod llm;
+pub use self::llm::*;
 mod run_with_user_sentence;
 pub use self::run_with_user_sentence::*;
+mod vector_index;
+pub use self::vector_index::*;
 
 use beet_flow::prelude::*;
 use bevy::prelude::*;
@@ -14,11 +18,22 @@ pub struct LanguagePlugin;
 
 impl Plugin for LanguagePlugin {
 	fn build(&self, app: &mut App) {
-		app.register_type::<Sentence>()
+		app.add_plugins(LlmPlugin)
+			.register_type::<Sentence>()
 			.add_observer(run_with_user_sentence::<()>);
 
 		#[cfg(feature = "candle")]
-		app.init_asset::<Bert>().init_asset_loader::<BertLoader>();
+		app.init_asset::<Bert>()
+			.init_asset_loader::<BertLoader>()
+			.init_resource::<EmbeddingCache>()
+			.init_resource::<SentenceIndex>();
+		#[cfg(feature = "candle")]
+		app.add_systems(
+			TickSchedule::get(app),
+			(invalidate_sentence_embeddings, index_sentence_embeddings)
+				.chain()
+				.in_set(PreTickSet),
+		);
 
 		let world = app.world_mut();
 		world.register_component::<Sentence>();
@@ -0,0 +1,430 @@
+use bevy::platform::collections::HashMap;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A k-nearest neighbor index of entity embeddings, ranked by
+/// cosine similarity. Vectors are normalized on insertion.
+pub trait VectorIndex: 'static + Send + Sync {
+	/// Insert or replace the vector for an entity.
+	fn insert(&mut self, entity: Entity, vector: Vec<f32>);
+	fn remove(&mut self, entity: Entity);
+	/// The normalized vector for an entity.
+	fn get(&self, entity: Entity) -> Option<&[f32]>;
+	fn len(&self) -> usize;
+	fn is_empty(&self) -> bool { self.len() == 0 }
+	/// Up to `k` entities passing the `filter`, sorted by descending
+	/// similarity to the `query`. Approximate indices may return
+	/// fewer than `k` results when the filter is very selective.
+	fn nearest(
+		&self,
+		query: &[f32],
+		k: usize,
+		filter: &dyn Fn(Entity) -> bool,
+	) -> Vec<(Entity, f32)>;
+}
+
+/// Normalize a vector to unit length, zero vectors are unchanged.
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+	let len = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+	if len > 0. {
+		vector.iter_mut().for_each(|v| *v /= len);
+	}
+	vector
+}
+
+/// The dot product, equal to the cosine similarity of normalized vectors.
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+	a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Compare every vector, `O(n)` per query but exact.
+#[derive(Debug, Default, Clone)]
+pub struct BruteForceIndex {
+	vectors: HashMap<Entity, Vec<f32>>,
+}
+
+impl VectorIndex for BruteForceIndex {
+	fn insert(&mut self, entity: Entity, vector: Vec<f32>) {
+		self.vectors.insert(entity, normalize(vector));
+	}
+	fn remove(&mut self, entity: Entity) { self.vectors.remove(&entity); }
+	fn get(&self, entity: Entity) -> Option<&[f32]> {
+		self.vectors.get(&entity).map(|vector| vector.as_slice())
+	}
+	fn len(&self) -> usize { self.vectors.len() }
+
+	fn nearest(
+		&self,
+		query: &[f32],
+		k: usize,
+		filter: &dyn Fn(Entity) -> bool,
+	) -> Vec<(Entity, f32)> {
+		let query = normalize(query.to_vec());
+		let mut results = self
+			.vectors
+			.iter()
+			.filter(|(entity, _)| filter(**entity))
+			.map(|(entity, vector)| (*entity, dot(&query, vector)))
+			.collect::<Vec<_>>();
+		results.sort_by(|a, b| b.1.total_cmp(&a.1));
+		results.truncate(k);
+		results
+	}
+}
+
+/// A node id paired with its similarity to the query,
+/// ordered by similarity.
+#[derive(Debug, Clone, Copy)]
+struct Scored(f32, usize);
+
+impl PartialEq for Scored {
+	fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl Eq for Scored {}
+impl PartialOrd for Scored {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+impl Ord for Scored {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+	}
+}
+
+#[derive(Debug, Clone)]
+struct HnswNode {
+	entity: Entity,
+	vector: Vec<f32>,
+	/// The neighbors of this node for each layer it is present in.
+	layers: Vec<Vec<usize>>,
+	removed: bool,
+}
+
+/// A Hierarchical Navigable Small World graph, `O(log n)` per query
+/// with high but approximate recall.
+/// Removed entities are skipped until more than half the nodes are
+/// removed, at which point the graph is rebuilt.
+/// https://arxiv.org/abs/1603.09320
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+	/// The number of neighbors per node, doubled for the bottom layer.
+	pub max_neighbors: usize,
+	/// The size of the candidate list when inserting.
+	pub ef_construction: usize,
+	/// The size of the candidate list when querying.
+	pub ef_search: usize,
+	nodes: Vec<HnswNode>,
+	entities: HashMap<Entity, usize>,
+	entry: Option<usize>,
+	/// Splitmix64 state for choosing node levels deterministically.
+	seed: u64,
+}
+
+impl Default for HnswIndex {
+	fn default() -> Self { Self::new(16, 100, 64) }
+}
+
+impl HnswIndex {
+	pub fn new(
+		max_neighbors: usize,
+		ef_construction: usize,
+		ef_search: usize,
+	) -> Self {
+		Self {
+			max_neighbors: max_neighbors.max(2),
+			ef_construction,
+			ef_search,
+			nodes: Vec::new(),
+			entities: HashMap::default(),
+			entry: None,
+			seed: 0,
+		}
+	}
+
+	fn random_level(&mut self) -> usize {
+		let level_mult = 1. / (self.max_neighbors as f32).ln();
+		self.seed = self.seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.seed;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^= z >> 31;
+		let uniform = (z >> 40) as f32 / (1u64 << 24) as f32;
+		let uniform = uniform.max(f32::EPSILON);
+		(-uniform.ln() * level_mult) as usize
+	}
+
+	fn max_neighbors(&self, layer: usize) -> usize {
+		if layer == 0 {
+			self.max_neighbors * 2
+		} else {
+			self.max_neighbors
+		}
+	}
+
+	fn similarity(&self, query: &[f32], node: usize) -> f32 {
+		dot(query, &self.nodes[node].vector)
+	}
+
+	/// Search a single layer, returning up to `ef` nodes sorted
+	/// by descending similarity.
+	fn search_layer(
+		&self,
+		query: &[f32],
+		entry_points: &[usize],
+		ef: usize,
+		layer: usize,
+	) -> Vec<Scored> {
+		let mut visited = HashSet::<usize>::default();
+		// best first
+		let mut candidates = BinaryHeap::new();
+		// worst first
+		let mut results = BinaryHeap::new();
+		for &node in entry_points {
+			if visited.insert(node) {
+				let scored = Scored(self.similarity(query, node), node);
+				candidates.push(scored);
+				results.push(Reverse(scored));
+			}
+		}
+		while let Some(candidate) = candidates.pop() {
+			let worst = results.peek().map(|r: &Reverse<Scored>| r.0.0);
+			if let Some(worst) = worst
+				&& candidate.0 < worst
+				&& results.len() >= ef
+			{
+				break;
+			}
+			for &neighbor in &self.nodes[candidate.1].layers[layer] {
+				if !visited.insert(neighbor) {
+					continue;
+				}
+				let similarity = self.similarity(query, neighbor);
+				let worst = results.peek().map(|r| r.0.0).unwrap_or(f32::MIN);
+				if results.len() < ef || similarity > worst {
+					let scored = Scored(similarity, neighbor);
+					candidates.push(scored);
+					results.push(Reverse(scored));
+					if results.len() > ef {
+						results.pop();
+					}
+				}
+			}
+		}
+		let mut results = results.into_iter().map(|r| r.0).collect::<Vec<_>>();
+		results.sort_by(|a, b| b.cmp(a));
+		results
+	}
+
+	/// Descend from the entry point to the given layer,
+	/// greedily moving to the nearest node.
+	fn descend(&self, query: &[f32], to_layer: usize) -> Option<usize> {
+		let mut entry = self.entry?;
+		let top = self.nodes[entry].layers.len() - 1;
+		for layer in (to_layer + 1..=top).rev() {
+			entry = self.search_layer(query, &[entry], 1, layer)[0].1;
+		}
+		Some(entry)
+	}
+
+	fn insert_node(&mut self, entity: Entity, vector: Vec<f32>) {
+		let level = self.random_level();
+		let id = self.nodes.len();
+		self.nodes.push(HnswNode {
+			entity,
+			vector,
+			layers: vec![Vec::new(); level + 1],
+			removed: false,
+		});
+		self.entities.insert(entity, id);
+		let Some(entry) = self.entry else {
+			self.entry = Some(id);
+			return;
+		};
+		let top = self.nodes[entry].layers.len() - 1;
+		let query = self.nodes[id].vector.clone();
+		let mut entry_points =
+			vec![self.descend(&query, level).unwrap_or(entry)];
+
+		for layer in (0..=level.min(top)).rev() {
+			let candidates = self.search_layer(
+				&query,
+				&entry_points,
+				self.ef_construction,
+				layer,
+			);
+			let max = self.max_neighbors(layer);
+			let neighbors = candidates
+				.iter()
+				.take(max)
+				.map(|scored| scored.1)
+				.collect::<Vec<_>>();
+			for &neighbor in &neighbors {
+				self.nodes[neighbor].layers[layer].push(id);
+				if self.nodes[neighbor].layers[layer].len() > max {
+					self.prune(neighbor, layer, max);
+				}
+			}
+			self.nodes[id].layers[layer] = neighbors;
+			entry_points = candidates.iter().map(|scored| scored.1).collect();
+		}
+		if level > top {
+			self.entry = Some(id);
+		}
+	}
+
+	/// Keep only the `max` most similar neighbors of a node.
+	fn prune(&mut self, node: usize, layer: usize, max: usize) {
+		let vector = &self.nodes[node].vector;
+		let mut neighbors = self.nodes[node].layers[layer]
+			.iter()
+			.map(|&neighbor| {
+				Scored(dot(vector, &self.nodes[neighbor].vector), neighbor)
+			})
+			.collect::<Vec<_>>();
+		neighbors.sort_by(|a, b| b.cmp(a));
+		self.nodes[node].layers[layer] = neighbors
+			.into_iter()
+			.take(max)
+			.map(|scored| scored.1)
+			.collect();
+	}
+
+	/// Recreate the graph without removed nodes.
+	fn rebuild(&mut self) {
+		let nodes = std::mem::take(&mut self.nodes);
+		self.entities.clear();
+		self.entry = None;
+		for node in nodes.into_iter().filter(|node| !node.removed) {
+			self.insert_node(node.entity, node.vector);
+		}
+	}
+}
+
+impl VectorIndex for HnswIndex {
+	fn insert(&mut self, entity: Entity, vector: Vec<f32>) {
+		self.remove(entity);
+		self.insert_node(entity, normalize(vector));
+	}
+
+	fn remove(&mut self, entity: Entity) {
+		let Some(id) = self.entities.remove(&entity) else {
+			return;
+		};
+		self.nodes[id].removed = true;
+		if self.entities.len() * 2 < self.nodes.len() {
+			self.rebuild();
+		}
+	}
+
+	fn get(&self, entity: Entity) -> Option<&[f32]> {
+		self.entities
+			.get(&entity)
+			.map(|id| self.nodes[*id].vector.as_slice())
+	}
+
+	fn len(&self) -> usize { self.entities.len() }
+
+	fn nearest(
+		&self,
+		query: &[f32],
+		k: usize,
+		filter: &dyn Fn(Entity) -> bool,
+	) -> Vec<(Entity, f32)> {
+		let query = normalize(query.to_vec());
+		let Some(entry) = self.descend(&query, 0) else {
+			return Vec::new();
+		};
+		self.search_layer(&query, &[entry], self.ef_search.max(k), 0)
+			.into_iter()
+			.filter(|scored| {
+				let node = &self.nodes[scored.1];
+				!node.removed && filter(node.entity)
+			})
+			.take(k)
+			.map(|scored| (self.nodes[scored.1].entity, scored.0))
+			.collect()
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn random_vectors(count: usize, seed: u64) -> Vec<Vec<f32>> {
+		let mut rng = RandomSource::from_seed(seed);
+		(0..count)
+			.map(|_| (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect())
+			.collect()
+	}
+
+	fn populate(index: &mut impl VectorIndex) {
+		for (i, vector) in random_vectors(500, 0).into_iter().enumerate() {
+			index.insert(Entity::from_raw(i as u32), vector);
+		}
+	}
+
+	#[test]
+	fn brute_force() {
+		let mut index = BruteForceIndex::default();
+		index.insert(Entity::from_raw(0), vec![1., 0.]);
+		index.insert(Entity::from_raw(1), vec![0., 2.]);
+		index.insert(Entity::from_raw(2), vec![-1., 0.]);
+		let nearest = index.nearest(&[0.1, 1.], 2, &|_| true);
+		expect(nearest[0].0).to_be(Entity::from_raw(1));
+		expect(nearest[1].0).to_be(Entity::from_raw(0));
+		let filtered =
+			index.nearest(&[0.1, 1.], 1, &|e| e != Entity::from_raw(1));
+		expect(filtered[0].0).to_be(Entity::from_raw(0));
+		index.remove(Entity::from_raw(1));
+		expect(index.len()).to_be(2);
+	}
+
+	#[test]
+	fn hnsw_recall() {
+		let mut exact = BruteForceIndex::default();
+		let mut hnsw = HnswIndex::default();
+		populate(&mut exact);
+		populate(&mut hnsw);
+
+		let queries = random_vectors(50, 1);
+		let matches = queries
+			.iter()
+			.filter(|query| {
+				exact.nearest(query, 1, &|_| true)[0].0
+					== hnsw.nearest(query, 1, &|_| true)[0].0
+			})
+			.count();
+		expect(matches).to_be_greater_than(45);
+	}
+
+	#[test]
+	fn hnsw_remove() {
+		let mut hnsw = HnswIndex::default();
+		populate(&mut hnsw);
+		let query = hnsw.get(Entity::from_raw(7)).unwrap().to_vec();
+		expect(hnsw.nearest(&query, 1, &|_| true)[0].0)
+			.to_be(Entity::from_raw(7));
+
+		hnsw.remove(Entity::from_raw(7));
+		expect(hnsw.get(Entity::from_raw(7))).to_be_none();
+		expect(hnsw.nearest(&query, 1, &|_| true)[0].0)
+			.not()
+			.to_be(Entity::from_raw(7));
+
+		// trigger a rebuild
+		for i in 0..300 {
+			hnsw.remove(Entity::from_raw(i));
+		}
+		expect(hnsw.len()).to_be(200);
+		let query = hnsw.get(Entity::from_raw(400)).unwrap().to_vec();
+		expect(hnsw.nearest(&query, 1, &|_| true)[0].0)
+			.to_be(Entity::from_raw(400));
+	}
+}
@@ -10,6 +10,8 @@ mod utils;
 
 #[cfg(feature = "bevy_default")]
 pub mod frozen_lake;
+#[cfg(all(feature = "bevy_default", not(target_arch = "wasm32")))]
+pub mod gym;
 pub mod language;
 #[cfg(feature = "bevy_default")]
 pub mod rl;
@@ -23,6 +25,8 @@ pub mod wasm;
 pub mod prelude {
 	#[cfg(feature = "bevy_default")]
 	pub use crate::frozen_lake::*;
+	#[cfg(all(feature = "bevy_default", not(target_arch = "wasm32")))]
+	pub use crate::gym::*;
 	pub use crate::language::*;
 	#[cfg(feature = "bevy_default")]
 	pub use crate::rl::*;
@@ -0,0 +1,229 @@
+//! Implementation of the OpenAI Gym Cart Pole environment.
+//! https://github.com/openai/gym/blob/master/gym/envs/classic_control/cartpole.py
+//!
+//! A pole is attached by an unactuated joint to a cart, which moves along a
+//! frictionless track. The goal is to balance the pole by pushing the cart
+//! left or right.
+//!
+//! ### Rewards
+//! - `1` for every step, including the last.
+//!
+//! ### Termination
+//! - The pole angle is more than 12 degrees.
+//! - The cart position is more than 2.4 from the center.
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::hash::Hash;
+use std::hash::Hasher;
+use strum::VariantArray;
+use sweet::prelude::*;
+
+/// The position and velocity of the cart and pole.
+#[derive(Debug, Default, Copy, Clone, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct CartPoleState {
+	/// Position of the cart along the track.
+	pub x: f32,
+	/// Velocity of the cart.
+	pub x_dot: f32,
+	/// Angle of the pole in radians, `0.` is upright.
+	pub theta: f32,
+	/// Angular velocity of the pole.
+	pub theta_dot: f32,
+}
+
+impl CartPoleState {
+	/// A state where each value is uniformly random in `-0.05..0.05`,
+	/// matching the gym reset.
+	pub fn random(rng: &mut impl Rng) -> Self {
+		let mut value = || rng.gen_range(-0.05..0.05);
+		Self {
+			x: value(),
+			x_dot: value(),
+			theta: value(),
+			theta_dot: value(),
+		}
+	}
+	fn bits(&self) -> [u32; 4] {
+		[
+			self.x.to_bits(),
+			self.x_dot.to_bits(),
+			self.theta.to_bits(),
+			self.theta_dot.to_bits(),
+		]
+	}
+}
+
+impl PartialEq for CartPoleState {
+	fn eq(&self, other: &Self) -> bool { self.bits() == other.bits() }
+}
+impl Eq for CartPoleState {}
+impl Hash for CartPoleState {
+	fn hash<H: Hasher>(&self, state: &mut H) { self.bits().hash(state); }
+}
+
+impl StateVector for CartPoleState {
+	const LEN: usize = 4;
+	fn to_vec(&self) -> Vec<f32> {
+		vec![
+			self.x / CartPoleEnv::X_THRESHOLD,
+			self.x_dot,
+			self.theta / CartPoleEnv::THETA_THRESHOLD,
+			self.theta_dot,
+		]
+	}
+}
+
+/// Push the cart left or right.
+#[derive(
+	Debug,
+	Default,
+	Copy,
+	Clone,
+	PartialEq,
+	Eq,
+	Hash,
+	Component,
+	Reflect,
+	VariantArray,
+)]
+#[reflect(Default, Component)]
+pub enum CartPoleAction {
+	#[default]
+	Left,
+	Right,
+}
+
+impl ActionSpace for CartPoleAction {
+	fn sample(rng: &mut impl Rng) -> Self {
+		if rng.gen_bool(0.5) {
+			Self::Left
+		} else {
+			Self::Right
+		}
+	}
+}
+
+/// The physical constants of the cart pole, integrated with euler steps.
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct CartPoleEnv {
+	/// Acceleration due to gravity.
+	pub gravity: f32,
+	/// Mass of the cart.
+	pub cart_mass: f32,
+	/// Mass of the pole.
+	pub pole_mass: f32,
+	/// Half the length of the pole.
+	pub pole_half_length: f32,
+	/// The magnitude of the force applied by each action.
+	pub force: f32,
+	/// Seconds between each step.
+	pub tau: f32,
+}
+
+impl Default for CartPoleEnv {
+	fn default() -> Self {
+		Self {
+			gravity: 9.8,
+			cart_mass: 1.0,
+			pole_mass: 0.1,
+			pole_half_length: 0.5,
+			force: 10.0,
+			tau: 0.02,
+		}
+	}
+}
+
+impl CartPoleEnv {
+	/// The episode ends when the cart leaves this distance from the center.
+	pub const X_THRESHOLD: f32 = 2.4;
+	/// The episode ends when the pole falls past this angle, 12 degrees.
+	pub const THETA_THRESHOLD: f32 = 12. * std::f32::consts::PI / 180.;
+
+	/// Whether the cart or pole is out of bounds.
+	pub fn is_done(state: &CartPoleState) -> bool {
+		state.x.abs() > Self::X_THRESHOLD
+			|| state.theta.abs() > Self::THETA_THRESHOLD
+	}
+}
+
+impl Environment for CartPoleEnv {
+	type State = CartPoleState;
+	type Action = CartPoleAction;
+
+	/// A [`CartPoleState::random`] state, ignoring the initial state.
+	fn reset(
+		&mut self,
+		_initial_state: &Self::State,
+		rng: &mut impl Rng,
+	) -> Self::State {
+		CartPoleState::random(rng)
+	}
+
+	fn step(
+		&mut self,
+		state: &Self::State,
+		action: &Self::Action,
+	) -> StepOutcome<Self::State> {
+		let force = match action {
+			CartPoleAction::Left => -self.force,
+			CartPoleAction::Right => self.force,
+		};
+		let total_mass = self.cart_mass + self.pole_mass;
+		let pole_mass_length = self.pole_mass * self.pole_half_length;
+		let (sin, cos) = state.theta.sin_cos();
+
+		let temp = (force + pole_mass_length * state.theta_dot.powi(2) * sin)
+			/ total_mass;
+		let theta_acc = (self.gravity * sin - cos * temp)
+			/ (self.pole_half_length
+				* (4. / 3. - self.pole_mass * cos.powi(2) / total_mass));
+		let x_acc = temp - pole_mass_length * theta_acc * cos / total_mass;
+
+		let next = CartPoleState {
+			x: state.x + self.tau * state.x_dot,
+			x_dot: state.x_dot + self.tau * x_acc,
+			theta: state.theta + self.tau * state.theta_dot,
+			theta_dot: state.theta_dot + self.tau * theta_acc,
+		};
+		StepOutcome {
+			done: Self::is_done(&next),
+			state: next,
+			reward: 1.,
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn falls_over() {
+		let mut env = CartPoleEnv::default();
+		let mut state = CartPoleState::default();
+		let mut steps = 0;
+		loop {
+			steps += 1;
+			let outcome = env.step(&state, &CartPoleAction::Right);
+			expect(outcome.reward).to_be(1.);
+			if outcome.done {
+				// pushing right tips the pole left
+				expect(outcome.state.theta).to_be_less_than(0.);
+				break;
+			}
+			state = outcome.state;
+		}
+		expect(steps).to_be_less_than(50);
+	}
+
+	#[test]
+	fn actions() {
+		expect(CartPoleAction::num_actions()).to_be(2);
+		expect(CartPoleAction::Right.to_index()).to_be(1);
+		expect(CartPoleAction::from_index(0)).to_be(CartPoleAction::Left);
+	}
+}
@@ -0,0 +1,88 @@
+use crate::prelude::*;
+use std::fmt::Debug;
+use sweet::prelude::*;
+
+/// A state or action represented as a fixed length vector of floats,
+/// for use with policy gradient methods like the `ReinforceTrainer`.
+/// Actions are expected to be in the range `-1..=1`.
+pub trait ContinuousSpace: 'static + Send + Sync + Debug + Clone {
+	/// The length of [`Self::to_vec`].
+	const LEN: usize;
+	/// The values of this state or action, ideally normalized.
+	fn to_vec(&self) -> Vec<f32>;
+	/// Create from a slice of length [`Self::LEN`].
+	fn from_vec(values: &[f32]) -> Self;
+}
+
+/// An environment with continuous states and actions, unlike an
+/// [`Environment`] which is limited to a [`DiscreteSpace`].
+pub trait ContinuousEnvironment: 'static + Send + Sync + Clone {
+	type State: ContinuousSpace;
+	type Action: ContinuousSpace;
+
+	/// Create the initial state for an episode.
+	fn reset(&mut self, rng: &mut impl Rng) -> Self::State;
+
+	fn step(
+		&mut self,
+		state: &Self::State,
+		action: &Self::Action,
+	) -> StepOutcome<Self::State>;
+}
+
+impl ContinuousSpace for f32 {
+	const LEN: usize = 1;
+	fn to_vec(&self) -> Vec<f32> { vec![*self] }
+	fn from_vec(values: &[f32]) -> Self { values[0] }
+}
+
+impl<const N: usize> ContinuousSpace for [f32; N] {
+	const LEN: usize = N;
+	fn to_vec(&self) -> Vec<f32> { self.as_slice().to_vec() }
+	fn from_vec(values: &[f32]) -> Self {
+		std::array::from_fn(|index| values[index])
+	}
+}
+
+#[cfg(feature = "spatial")]
+mod spatial {
+	use super::*;
+	use beet_spatial::prelude::*;
+	use bevy::prelude::*;
+
+	/// The signed speed of the left and right motor.
+	impl ContinuousSpace for DualMotorValue {
+		const LEN: usize = 2;
+		fn to_vec(&self) -> Vec<f32> {
+			vec![self.left.to_signed_normal(), self.right.to_signed_normal()]
+		}
+		fn from_vec(values: &[f32]) -> Self {
+			Self::new(
+				MotorValue::from_signed_normal(values[0].clamp(-1., 1.)),
+				MotorValue::from_signed_normal(values[1].clamp(-1., 1.)),
+			)
+		}
+	}
+
+	/// The unscaled impulse, usually multiplied by the [`MaxForce`]
+	/// before being applied.
+	impl ContinuousSpace for Impulse {
+		const LEN: usize = 3;
+		fn to_vec(&self) -> Vec<f32> { self.to_array().to_vec() }
+		fn from_vec(values: &[f32]) -> Self { Self(Vec3::from_slice(values)) }
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		expect(<[f32; 3]>::from_vec(&[1., 2., 3.])).to_be([1., 2., 3.]);
+		expect(ContinuousSpace::to_vec(&2_f32)).to_be(vec![2.]);
+		expect(f32::LEN).to_be(1);
+	}
+}
@@ -0,0 +1,467 @@
+use super::*;
+use candle_core::DType;
+use candle_core::Device;
+use candle_core::Result;
+use candle_core::Tensor;
+use candle_nn::AdamW;
+use candle_nn::Linear;
+use candle_nn::Module;
+use candle_nn::Optimizer;
+use candle_nn::ParamsAdamW;
+use candle_nn::VarBuilder;
+use candle_nn::VarMap;
+use sweet::prelude::*;
+
+/// Parameters specific to a [`DqnTrainer`], the episode count,
+/// discount and epsilon schedule are taken from the [`QLearnParams`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DqnParams {
+	/// The size of each of the two hidden layers.
+	pub hidden_size: usize,
+	/// The number of transitions sampled for each optimizer step.
+	pub batch_size: usize,
+	/// The maximum number of transitions kept in the [`ReplayBuffer`].
+	pub replay_capacity: usize,
+	/// Optimizer steps are skipped until the buffer has this many transitions.
+	pub min_replay_size: usize,
+	/// The number of steps between copying the weights to the target network.
+	pub target_update_interval: usize,
+	/// The learning rate of the optimizer, this is separate from
+	/// [`QLearnParams::learning_rate`] which is only used by [`QPolicy::step`].
+	pub learning_rate: f64,
+	/// Seeds the initial weights and the episode starts used by
+	/// [`QTrainer::evaluate`], so training with the same `rng` is
+	/// reproducible.
+	pub seed: u64,
+}
+
+impl Default for DqnParams {
+	fn default() -> Self {
+		Self {
+			hidden_size: 64,
+			batch_size: 32,
+			replay_capacity: 10_000,
+			min_replay_size: 64,
+			target_update_interval: 100,
+			learning_rate: 1e-3,
+			seed: 0,
+		}
+	}
+}
+
+/// A multilayer perceptron mapping a [`StateVector`] to a [`QValue`]
+/// for each action, initialized with [`init_vars_seeded`].
+pub struct QNetwork {
+	varmap: VarMap,
+	layers: Vec<Linear>,
+}
+
+impl QNetwork {
+	pub fn new(
+		num_inputs: usize,
+		hidden_size: usize,
+		num_outputs: usize,
+		device: &Device,
+		rng: &mut impl Rng,
+	) -> Result<Self> {
+		let varmap = VarMap::new();
+		let vb = VarBuilder::from_varmap(&varmap, DType::F32, device);
+		let layers = vec![
+			candle_nn::linear(num_inputs, hidden_size, vb.pp("layer0"))?,
+			candle_nn::linear(hidden_size, hidden_size, vb.pp("layer1"))?,
+			candle_nn::linear(hidden_size, num_outputs, vb.pp("layer2"))?,
+		];
+		init_vars_seeded(&varmap, rng)?;
+		Ok(Self { varmap, layers })
+	}
+
+	/// Copy the weights of another network with the same shape.
+	pub fn copy_from(&self, other: &QNetwork) -> Result<()> {
+		let source = other.varmap.data().lock().unwrap();
+		let target = self.varmap.data().lock().unwrap();
+		for (name, var) in source.iter() {
+			if let Some(target) = target.get(name) {
+				target.set(var.as_tensor())?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Module for QNetwork {
+	fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+		let last = self.layers.len() - 1;
+		let mut xs = xs.clone();
+		for (index, layer) in self.layers.iter().enumerate() {
+			xs = layer.forward(&xs)?;
+			if index != last {
+				xs = xs.relu()?;
+			}
+		}
+		Ok(xs)
+	}
+}
+
+/// Reinitialize each variable uniformly in `±1/sqrt(n)`, where `n` is the
+/// size of its last dimension, ie the inputs of a weight matrix.
+/// Variables are visited in order of name so the result depends only
+/// on the `rng`, unlike the default initialization which is unseeded.
+pub fn init_vars_seeded(varmap: &VarMap, rng: &mut impl Rng) -> Result<()> {
+	let vars = varmap.data().lock().unwrap();
+	let mut names = vars.keys().collect::<Vec<_>>();
+	names.sort();
+	for name in names {
+		let var = &vars[name];
+		let dims = var.dims();
+		let fan_in = dims.last().copied().unwrap_or(1).max(1);
+		let bound = 1. / (fan_in as f32).sqrt();
+		let values = (0..var.elem_count())
+			.map(|_| rng.gen_range(-bound..=bound))
+			.collect::<Vec<f32>>();
+		var.set(&Tensor::from_vec(values, dims, var.device())?)?;
+	}
+	Ok(())
+}
+
+/// Used for training a deep Q network to completion with a provided
+/// [`Environment`], for continuous states that cant fit in a [`QTable`].
+/// This uses an experience [`ReplayBuffer`] and a target network that is
+/// periodically synced for stability. Training runs on the CPU.
+pub struct DqnTrainer<Env: Environment>
+where
+	Env::State: StateVector,
+	Env::Action: DiscreteActionSpace,
+{
+	pub env: Readonly<Env>,
+	pub params: Readonly<QLearnParams>,
+	pub dqn_params: Readonly<DqnParams>,
+	pub replay_buffer: ReplayBuffer<Env::State, Env::Action>,
+	initial_state: Env::State,
+	device: Device,
+	network: QNetwork,
+	target_network: QNetwork,
+	optimizer: AdamW,
+	/// Steps taken since the target network was last synced.
+	steps_since_sync: usize,
+}
+
+impl<Env: Environment> DqnTrainer<Env>
+where
+	Env::State: StateVector,
+	Env::Action: DiscreteActionSpace,
+{
+	/// Create a trainer, the `initial_state` is passed to
+	/// [`Environment::reset`] at the start of each episode.
+	pub fn new(
+		env: Env,
+		params: QLearnParams,
+		dqn_params: DqnParams,
+		initial_state: Env::State,
+	) -> Result<Self> {
+		let device = Device::Cpu;
+		let mut rng = RandomSource::from_seed(dqn_params.seed);
+		let network = QNetwork::new(
+			Env::State::LEN,
+			dqn_params.hidden_size,
+			Env::Action::num_actions(),
+			&device,
+			&mut rng.0,
+		)?;
+		let target_network = QNetwork::new(
+			Env::State::LEN,
+			dqn_params.hidden_size,
+			Env::Action::num_actions(),
+			&device,
+			&mut rng.0,
+		)?;
+		target_network.copy_from(&network)?;
+		let optimizer = AdamW::new(network.varmap.all_vars(), ParamsAdamW {
+			lr: dqn_params.learning_rate,
+			..Default::default()
+		})?;
+		Ok(Self {
+			env: Readonly::new(env),
+			params: Readonly::new(params),
+			replay_buffer: ReplayBuffer::new(dqn_params.replay_capacity),
+			dqn_params: Readonly::new(dqn_params),
+			initial_state,
+			device,
+			network,
+			target_network,
+			optimizer,
+			steps_since_sync: 0,
+		})
+	}
+
+	fn states_to_tensor<'a>(
+		&self,
+		states: impl IntoIterator<Item = &'a Env::State>,
+	) -> Result<Tensor> {
+		let data = states
+			.into_iter()
+			.flat_map(|state| state.to_vec())
+			.collect::<Vec<_>>();
+		let len = data.len() / Env::State::LEN;
+		Tensor::from_vec(data, (len, Env::State::LEN), &self.device)
+	}
+
+	/// The [`QValue`] of each action for the given state.
+	pub fn q_values(&self, state: &Env::State) -> Result<Vec<QValue>> {
+		self.network
+			.forward(&self.states_to_tensor([state])?)?
+			.squeeze(0)?
+			.to_vec1()
+	}
+
+	/// Perform a single optimizer step moving the [`QValue`] of each
+	/// state-action pair toward its target.
+	fn optimize(
+		&mut self,
+		states: &[&Env::State],
+		actions: &[&Env::Action],
+		targets: Vec<f32>,
+	) -> Result<()> {
+		let actions = actions
+			.iter()
+			.map(|action| action.to_index() as u32)
+			.collect::<Vec<_>>();
+		let batch_size = actions.len();
+		let actions = Tensor::from_vec(actions, (batch_size, 1), &self.device)?;
+		let targets = Tensor::from_vec(targets, batch_size, &self.device)?;
+		let q = self
+			.network
+			.forward(&self.states_to_tensor(states.iter().copied())?)?
+			.gather(&actions, 1)?
+			.squeeze(1)?;
+		let loss = candle_nn::loss::mse(&q, &targets)?;
+		self.optimizer.backward_step(&loss)
+	}
+
+	/// Sample a batch from the replay buffer and move the network toward
+	/// the Bellman target, using the target network for the next state.
+	fn train_batch(&mut self, rng: &mut impl Rng) -> Result<()> {
+		let batch = self
+			.replay_buffer
+			.sample(rng, self.dqn_params.batch_size)
+			.into_iter()
+			.cloned()
+			.collect::<Vec<_>>();
+		let next_q = self
+			.target_network
+			.forward(&self.states_to_tensor(
+				batch.iter().map(|transition| &transition.next_state),
+			)?)?
+			.max(1)?
+			.detach()
+			.to_vec1::<f32>()?;
+		// Q(s,a) = R(s,a) + gamma * max Q'(s',a')
+		let targets = batch
+			.iter()
+			.zip(next_q)
+			.map(|(transition, next_q)| {
+				if transition.done {
+					transition.reward
+				} else {
+					transition.reward + self.params.gamma * next_q
+				}
+			})
+			.collect();
+		let states = batch
+			.iter()
+			.map(|transition| &transition.state)
+			.collect::<Vec<_>>();
+		let actions = batch
+			.iter()
+			.map(|transition| &transition.action)
+			.collect::<Vec<_>>();
+		self.optimize(&states, &actions, targets)
+	}
+
+	/// Fallible version of [`QTrainer::train`].
+	pub fn try_train(&mut self, rng: &mut impl Rng) -> Result<()> {
+		for episode in 0..self.params.n_training_episodes {
+			let epsilon = self.params.epsilon(episode);
+			let mut env = self.env.clone();
+			let mut state = env.reset(&self.initial_state, rng);
+
+			for _step in 0..self.params.max_steps {
+				// 1. select action
+				let (action, _) =
+					self.epsilon_greedy_policy(&state, epsilon, rng);
+				// 2. step environment
+				let outcome = env.step(&state, &action);
+				// 3. remember and learn from a random batch
+				self.replay_buffer.push(Transition {
+					state: state.clone(),
+					action,
+					reward: outcome.reward,
+					next_state: outcome.state.clone(),
+					done: outcome.done,
+				});
+				if self.replay_buffer.len() >= self.dqn_params.min_replay_size {
+					self.train_batch(rng)?;
+				}
+				self.steps_since_sync += 1;
+				let sync_interval = self.dqn_params.target_update_interval;
+				if self.steps_since_sync >= sync_interval {
+					self.target_network.copy_from(&self.network)?;
+					self.steps_since_sync = 0;
+				}
+				// 4. update state and break if done
+				if outcome.done {
+					break;
+				}
+				state = outcome.state;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<Env: Environment> QPolicy for DqnTrainer<Env>
+where
+	Env::State: StateVector,
+	Env::Action: DiscreteActionSpace,
+{
+	type Action = Env::Action;
+	type State = Env::State;
+
+	fn greedy_policy(&self, state: &Self::State) -> (Self::Action, QValue) {
+		self.action_values(state)
+			.into_iter()
+			.max_by(|a, b| a.1.total_cmp(&b.1))
+			.unwrap_or_default()
+	}
+
+	fn action_values(
+		&self,
+		state: &Self::State,
+	) -> Vec<(Self::Action, QValue)> {
+		self.q_values(state)
+			.expect("DqnTrainer: failed to evaluate network")
+			.into_iter()
+			.enumerate()
+			.map(|(index, value)| (Self::Action::from_index(index), value))
+			.collect()
+	}
+
+	fn get_q(&self, state: &Self::State, action: &Self::Action) -> QValue {
+		self.q_values(state)
+			.expect("DqnTrainer: failed to evaluate network")[action.to_index()]
+	}
+
+	/// Performs a single optimizer step toward the value,
+	/// allowing the network to be trained in realtime with [`QPolicy::step`].
+	fn set_q(
+		&mut self,
+		state: &Self::State,
+		action: &Self::Action,
+		value: QValue,
+	) {
+		self.optimize(&[state], &[action], vec![value])
+			.expect("DqnTrainer: failed to optimize network");
+	}
+}
+
+impl<Env: Environment> QTrainer for DqnTrainer<Env>
+where
+	Env::State: StateVector,
+	Env::Action: DiscreteActionSpace,
+{
+	fn train(&mut self, rng: &mut impl Rng) {
+		self.try_train(rng)
+			.expect("DqnTrainer: failed to train network");
+	}
+
+	/// Evaluate the greedy policy, episodes are reset with a
+	/// [`DqnParams::seed`] so evaluations are comparable.
+	fn evaluate(&self) -> Evaluation {
+		let mut rng = RandomSource::from_seed(self.dqn_params.seed);
+		let mut rewards = Vec::new();
+		let mut total_steps = 0;
+		for _episode in 0..self.params.n_eval_episodes {
+			let mut env = self.env.clone();
+			let mut state = env.reset(&self.initial_state, &mut rng.0);
+			let mut total_reward = 0.0;
+
+			for _step in 0..self.params.max_steps {
+				total_steps += 1;
+				let (action, _) = self.greedy_policy(&state);
+				let outcome = env.step(&state, &action);
+				total_reward += outcome.reward;
+				state = outcome.state;
+
+				if outcome.done {
+					break;
+				}
+			}
+			rewards.push(total_reward);
+		}
+		Evaluation::new(rewards, total_steps)
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	fn trainer() -> DqnTrainer<CartPoleEnv> {
+		let params = QLearnParams {
+			n_training_episodes: 10,
+			n_eval_episodes: 2,
+			max_steps: 50,
+			gamma: 0.99,
+			decay_rate: 0.03,
+			..Default::default()
+		};
+		let dqn_params = DqnParams {
+			hidden_size: 16,
+			batch_size: 8,
+			min_replay_size: 16,
+			target_update_interval: 20,
+			..Default::default()
+		};
+		DqnTrainer::new(
+			CartPoleEnv::default(),
+			params,
+			dqn_params,
+			CartPoleState::default(),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn cart_pole() {
+		let state = CartPoleState::default();
+		let mut trainer = trainer();
+		let untrained = trainer.q_values(&state).unwrap();
+		expect(untrained.len()).to_be(2);
+		// the weights are seeded
+		expect(trainer().q_values(&state).unwrap()).to_be(untrained.clone());
+
+		trainer.train(&mut RandomSource::from_seed(0).0);
+		expect(trainer.replay_buffer.len()).to_be_greater_than(16);
+		let trained = trainer.q_values(&state).unwrap();
+		expect(&trained).not().to_be(&untrained);
+		expect(trainer.evaluate().mean).to_be_greater_than(0.);
+
+		// training is reproducible with the same rng
+		let mut other = trainer();
+		other.train(&mut RandomSource::from_seed(0).0);
+		expect(other.q_values(&state).unwrap()).to_be(trained);
+	}
+
+	#[test]
+	fn action_values() {
+		let state = CartPoleState::default();
+		let trainer = trainer();
+		let values = trainer.action_values(&state);
+		expect(values.len()).to_be(2);
+		expect(values[1].0).to_be(CartPoleAction::Right);
+		expect(values[1].1).to_be(trainer.q_values(&state).unwrap()[1]);
+		// values are not stored
+		expect(trainer.get_actions(&state).count()).to_be(0);
+	}
+}
@@ -2,6 +2,7 @@ use bevy::ecs::component::Mutable;
 use bevy::prelude::*;
 use std::fmt::Debug;
 use std::hash::Hash;
+use strum::VariantArray;
 use sweet::prelude::*;
 
 #[derive(Deref)]
@@ -20,6 +21,16 @@ pub trait Environment: 'static + Send + Sync + Clone {
 		state: &Self::State,
 		action: &Self::Action,
 	) -> StepOutcome<Self::State>;
+
+	/// Create the state for the start of an episode, defaults to the
+	/// trainer's `initial_state`. Override for randomized starts.
+	fn reset(
+		&mut self,
+		initial_state: &Self::State,
+		_rng: &mut impl Rng,
+	) -> Self::State {
+		initial_state.clone()
+	}
 	// fn state_space(&self) -> State;
 	// fn action_space(&self) -> Action;
 }
@@ -78,3 +89,31 @@ pub trait ActionSpace: DiscreteSpace + Default {
 	fn sample(rng: &mut impl Rng) -> Self;
 }
 // impl<T: DiscreteSpace + TryFrom<usize>> ActionSpace for T {}
+
+/// An [`ActionSpace`] with a fixed number of variants,
+/// so each action can be mapped to the output of a network.
+pub trait DiscreteActionSpace: ActionSpace + VariantArray {
+	/// The number of possible actions.
+	fn num_actions() -> usize { Self::VARIANTS.len() }
+	/// The position of this action in [`VariantArray::VARIANTS`].
+	fn to_index(&self) -> usize {
+		Self::VARIANTS
+			.iter()
+			.position(|variant| variant == self)
+			.expect("action is a variant")
+	}
+	/// The action at the given position in [`VariantArray::VARIANTS`].
+	fn from_index(index: usize) -> Self { Self::VARIANTS[index].clone() }
+}
+impl<T: ActionSpace + VariantArray> DiscreteActionSpace for T {}
+
+/// A [`StateSpace`] that can be represented as a fixed length vector,
+/// for use with function approximators like a deep Q network.
+/// Continuous states should implement [`Hash`] and [`Eq`] by bits,
+/// ie [`f32::to_bits`].
+pub trait StateVector: StateSpace {
+	/// The length of [`Self::to_vec`].
+	const LEN: usize;
+	/// The values of this state, ideally normalized.
+	fn to_vec(&self) -> Vec<f32>;
+}
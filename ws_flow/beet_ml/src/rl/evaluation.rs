@@ -3,6 +3,9 @@ pub struct Evaluation {
 	pub total_steps: u128,
 	pub mean: f32,
 	pub std: f32,
+	/// The total reward of each episode in order,
+	/// useful for comparing learning curves.
+	pub rewards: Vec<f32>,
 }
 
 impl Evaluation {
@@ -13,8 +16,18 @@ impl Evaluation {
 			mean,
 			std,
 			total_steps,
+			rewards,
 		}
 	}
+
+	/// The mean reward of each consecutive `window` of episodes,
+	/// a trailing partial window is ignored.
+	pub fn window_means(&self, window: usize) -> Vec<f32> {
+		self.rewards
+			.chunks_exact(window.max(1))
+			.filter_map(mean)
+			.collect()
+	}
 }
 
 fn mean(data: &[f32]) -> Option<f32> {
@@ -37,3 +50,17 @@ fn variance(data: &[f32]) -> Option<f32> {
 		/ (len - 1) as f32;
 	Some(var)
 }
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn window_means() {
+		let eval = Evaluation::new(vec![0., 1., 1., 1., 2.], 5);
+		expect(eval.window_means(2)).to_be(vec![0.5, 1.]);
+		expect(eval.rewards.len()).to_be(5);
+	}
+}
@@ -0,0 +1,235 @@
+use super::*;
+use bevy::asset::AssetLoader;
+use bevy::asset::LoadContext;
+use bevy::asset::io::Reader;
+use bevy::prelude::*;
+use bevy::tasks::ConditionalSendFuture;
+use candle_core::DType;
+use candle_core::Device;
+use candle_core::Result;
+use candle_core::Tensor;
+use candle_core::Var;
+use candle_nn::Init;
+use candle_nn::Module;
+use candle_nn::VarBuilder;
+use candle_nn::VarMap;
+use std::path::Path;
+use sweet::prelude::*;
+
+/// `ln(2π)`, used by the gaussian log probability.
+const LN_2PI: f64 = 1.837_877_066_409_345_3;
+
+/// A stochastic policy for a [`ContinuousSpace`], a [`Mlp`] outputs the
+/// mean of each action in the range `-1..=1` and a learned
+/// standard deviation, independent of the state, adds exploration noise.
+///
+/// Checkpoints are saved in the safetensors format and can be loaded as
+/// an asset with the `.policy.safetensors` extension, the network shape
+/// is inferred from the file.
+#[derive(Asset, TypePath)]
+pub struct GaussianPolicy {
+	varmap: VarMap,
+	mean: Mlp,
+	log_std: Tensor,
+	device: Device,
+}
+
+impl GaussianPolicy {
+	/// The initial standard deviation is `e^-0.5`, about `0.6`.
+	pub const INITIAL_LOG_STD: f64 = -0.5;
+
+	/// Create a new policy with two hidden layers of `hidden_size`.
+	pub fn new(
+		state_len: usize,
+		action_len: usize,
+		hidden_size: usize,
+	) -> Result<Self> {
+		let device = Device::Cpu;
+		let varmap = VarMap::new();
+		let vb = VarBuilder::from_varmap(&varmap, DType::F32, &device);
+		let mean = Mlp::new(vb.pp("mean"), &[
+			state_len,
+			hidden_size,
+			hidden_size,
+			action_len,
+		])?;
+		let log_std = vb.get_with_hints(
+			action_len,
+			"log_std",
+			Init::Const(Self::INITIAL_LOG_STD),
+		)?;
+		Ok(Self {
+			varmap,
+			mean,
+			log_std,
+			device,
+		})
+	}
+
+	/// Reinitialize the mean network with [`init_vars_seeded`] and reset
+	/// the standard deviation to [`Self::INITIAL_LOG_STD`].
+	pub fn init_seeded(&self, rng: &mut impl Rng) -> Result<()> {
+		init_vars_seeded(&self.varmap, rng)?;
+		let log_std = Tensor::full(
+			Self::INITIAL_LOG_STD as f32,
+			self.log_std.dims(),
+			&self.device,
+		)?;
+		if let Some(var) = self.varmap.data().lock().unwrap().get("log_std") {
+			var.set(&log_std)?;
+		}
+		Ok(())
+	}
+
+	/// Load a checkpoint saved with [`Self::save`].
+	pub fn from_safetensors(bytes: &[u8]) -> Result<Self> {
+		let tensors =
+			candle_core::safetensors::load_buffer(bytes, &Device::Cpu)?;
+		let dims = |name: &str| {
+			tensors
+				.get(name)
+				.ok_or_else(|| {
+					candle_core::Error::Msg(format!("missing tensor: {name}"))
+				})?
+				.dims2()
+		};
+		let (hidden_size, state_len) = dims("mean.layer0.weight")?;
+		let (action_len, _) = dims("mean.layer2.weight")?;
+		let policy = Self::new(state_len, action_len, hidden_size)?;
+		for (name, var) in policy.varmap.data().lock().unwrap().iter() {
+			if let Some(tensor) = tensors.get(name) {
+				var.set(tensor)?;
+			}
+		}
+		Ok(policy)
+	}
+
+	/// Save a checkpoint in the safetensors format.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+		self.varmap.save(path)
+	}
+
+	/// The trainable variables of this policy.
+	pub fn all_vars(&self) -> Vec<Var> { self.varmap.all_vars() }
+
+	/// The device the networks run on, currently always the CPU.
+	pub fn device(&self) -> &Device { &self.device }
+
+	/// The mean action for a batch of states with shape `(batch, state_len)`.
+	pub fn forward_mean(&self, states: &Tensor) -> Result<Tensor> {
+		self.mean.forward(states)?.tanh()
+	}
+
+	/// The deterministic action for a state, used once training is complete.
+	pub fn mean_action(&self, state: &[f32]) -> Result<Vec<f32>> {
+		let states = Tensor::from_slice(state, (1, state.len()), &self.device)?;
+		self.forward_mean(&states)?.squeeze(0)?.to_vec1()
+	}
+
+	/// Sample an action for a state, adding gaussian noise to the mean.
+	pub fn sample_action(
+		&self,
+		state: &[f32],
+		rng: &mut impl Rng,
+	) -> Result<Vec<f32>> {
+		let std = self.log_std.exp()?.to_vec1::<f32>()?;
+		Ok(self
+			.mean_action(state)?
+			.into_iter()
+			.zip(std)
+			.map(|(mean, std)| mean + std * standard_normal(rng))
+			.collect())
+	}
+
+	/// The log probability of each action given its state,
+	/// returning a tensor with shape `(batch)`.
+	pub fn log_prob(
+		&self,
+		states: &Tensor,
+		actions: &Tensor,
+	) -> Result<Tensor> {
+		let mean = self.forward_mean(states)?;
+		let std = self.log_std.exp()?;
+		actions
+			.sub(&mean)?
+			.broadcast_div(&std)?
+			.sqr()?
+			.affine(-0.5, -0.5 * LN_2PI)?
+			.broadcast_sub(&self.log_std)?
+			.sum(1)
+	}
+}
+
+/// Sample from a normal distribution with the Box-Muller transform.
+fn standard_normal(rng: &mut impl Rng) -> f32 {
+	let u1 = rng.r#gen::<f32>().max(f32::EPSILON);
+	let u2 = rng.r#gen::<f32>();
+	(-2. * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+/// Loads a [`GaussianPolicy`] checkpoint.
+#[derive(Default)]
+pub struct GaussianPolicyLoader;
+
+impl AssetLoader for GaussianPolicyLoader {
+	type Asset = GaussianPolicy;
+	type Settings = ();
+	type Error = anyhow::Error;
+
+	fn load(
+		&self,
+		reader: &mut dyn Reader,
+		_settings: &Self::Settings,
+		_load_context: &mut LoadContext,
+	) -> impl ConditionalSendFuture<Output = anyhow::Result<Self::Asset>> {
+		Box::pin(async move {
+			let mut bytes = Vec::new();
+			reader.read_to_end(&mut bytes).await?;
+			Ok(GaussianPolicy::from_safetensors(&bytes)?)
+		})
+	}
+
+	fn extensions(&self) -> &[&str] { &["policy.safetensors"] }
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn save_load() {
+		let policy = GaussianPolicy::new(3, 2, 8).unwrap();
+		let action = policy.mean_action(&[0.1, 0.2, 0.3]).unwrap();
+		expect(action.len()).to_be(2);
+		expect(action.iter().all(|value| value.abs() <= 1.)).to_be_true();
+
+		let path = std::env::temp_dir().join(format!(
+			"beet_test_{}.policy.safetensors",
+			std::process::id()
+		));
+		policy.save(&path).unwrap();
+		let bytes = std::fs::read(&path).unwrap();
+		std::fs::remove_file(&path).ok();
+		let loaded = GaussianPolicy::from_safetensors(&bytes).unwrap();
+		expect(loaded.mean_action(&[0.1, 0.2, 0.3]).unwrap()).to_be(action);
+	}
+
+	#[test]
+	fn log_prob() {
+		let policy = GaussianPolicy::new(1, 1, 8).unwrap();
+		let states =
+			candle_core::Tensor::new(&[[0.5_f32]], policy.device()).unwrap();
+		let mean = policy.forward_mean(&states).unwrap();
+		let log_prob = policy
+			.log_prob(&states, &mean)
+			.unwrap()
+			.to_vec1::<f32>()
+			.unwrap();
+		// the density peaks at the mean
+		let expected = -0.5 * (std::f32::consts::TAU).ln()
+			- GaussianPolicy::INITIAL_LOG_STD as f32;
+		expect(log_prob[0]).to_be_close_to(expected);
+	}
+}
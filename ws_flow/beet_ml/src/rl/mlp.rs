@@ -0,0 +1,55 @@
+use candle_core::Result;
+use candle_core::Tensor;
+use candle_nn::Linear;
+use candle_nn::Module;
+use candle_nn::VarBuilder;
+use candle_nn::VarMap;
+
+/// A multilayer perceptron with a relu activation between each layer.
+/// Layers are named `layer0`, `layer1` etc in the [`VarBuilder`].
+#[derive(Debug, Clone)]
+pub struct Mlp {
+	layers: Vec<Linear>,
+}
+
+impl Mlp {
+	/// Create a new network, `sizes` includes the input and output sizes,
+	/// ie `[4, 64, 64, 2]` has two hidden layers.
+	pub fn new(vb: VarBuilder, sizes: &[usize]) -> Result<Self> {
+		let layers = sizes
+			.windows(2)
+			.enumerate()
+			.map(|(index, pair)| {
+				let vb = vb.pp(format!("layer{index}"));
+				candle_nn::linear(pair[0], pair[1], vb)
+			})
+			.collect::<Result<Vec<_>>>()?;
+		Ok(Self { layers })
+	}
+}
+
+impl Module for Mlp {
+	fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+		let mut xs = xs.clone();
+		for (index, layer) in self.layers.iter().enumerate() {
+			xs = layer.forward(&xs)?;
+			if index != self.layers.len() - 1 {
+				xs = xs.relu()?;
+			}
+		}
+		Ok(xs)
+	}
+}
+
+/// Copy each variable in the `source` to the variable with the same name
+/// in the `target`, the shapes must match.
+pub fn copy_vars(source: &VarMap, target: &VarMap) -> Result<()> {
+	let source = source.data().lock().unwrap();
+	let target = target.data().lock().unwrap();
+	for (name, var) in source.iter() {
+		if let Some(target) = target.get(name) {
+			target.set(var.as_tensor())?;
+		}
+	}
+	Ok(())
+}
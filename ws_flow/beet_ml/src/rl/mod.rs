@@ -1,8 +1,26 @@
+mod cart_pole;
+pub use self::cart_pole::*;
+mod continuous_space;
+pub use self::continuous_space::*;
+#[cfg(feature = "candle")]
+mod dqn_trainer;
+#[cfg(feature = "candle")]
+pub use self::dqn_trainer::*;
 mod environment;
 pub use self::environment::*;
 mod evaluation;
 pub use self::evaluation::*;
+#[cfg(feature = "candle")]
+mod gaussian_policy;
+#[cfg(feature = "candle")]
+pub use self::gaussian_policy::*;
 mod hash_q_table;
+#[cfg(feature = "candle")]
+mod mlp;
+#[cfg(feature = "candle")]
+pub use self::mlp::*;
+mod q_learn_algorithm;
+pub use self::q_learn_algorithm::*;
 mod q_learn_params;
 pub use self::q_learn_params::*;
 mod q_policy;
@@ -19,3 +37,9 @@ mod q_table_trainer;
 pub use self::q_table_trainer::*;
 mod q_trainer;
 pub use self::q_trainer::*;
+#[cfg(feature = "candle")]
+mod reinforce_trainer;
+#[cfg(feature = "candle")]
+pub use self::reinforce_trainer::*;
+mod replay_buffer;
+pub use self::replay_buffer::*;
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+/// The temporal difference update used by a
+/// [`QTableTrainer`](crate::prelude::QTableTrainer).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect)]
+pub enum QLearnAlgorithm {
+	/// Off-policy, bootstraps from the best action of the next state.
+	#[default]
+	QLearning,
+	/// On-policy, bootstraps from the action actually taken next.
+	Sarsa,
+	/// Bootstraps from the expected value of the next state under
+	/// the epsilon greedy policy, reducing the variance of [`Self::Sarsa`].
+	ExpectedSarsa,
+	/// Alternately updates two tables, each using the other to evaluate
+	/// its best action, avoiding the maximization bias of
+	/// [`Self::QLearning`]. The tables are averaged when training completes.
+	DoubleQLearning,
+	/// SARSA(λ), rewards are propagated back to recently visited
+	/// state-action pairs with replacing eligibility traces which decay
+	/// by `gamma * lambda` each step.
+	TdLambda { lambda: f32 },
+}
@@ -61,10 +61,26 @@ pub trait QPolicy: 'static + Send + Sync {
 		}
 	}
 
+	/// The stored value of each action for the given state.
+	/// Policies that do not store their values, ie a deep Q network,
+	/// return nothing here and implement [`Self::action_values`] instead.
 	fn get_actions(
+		&self,
+		_state: &Self::State,
+	) -> impl Iterator<Item = (&Self::Action, &QValue)> {
+		std::iter::empty()
+	}
+
+	/// The value of each action for the given state, defaults to
+	/// the [`Self::get_actions`].
+	fn action_values(
 		&self,
 		state: &Self::State,
-	) -> impl Iterator<Item = (&Self::Action, &QValue)>;
+	) -> Vec<(Self::Action, QValue)> {
+		self.get_actions(state)
+			.map(|(action, value)| (action.clone(), *value))
+			.collect()
+	}
 
 	fn get_q(&self, state: &Self::State, action: &Self::Action) -> QValue;
 
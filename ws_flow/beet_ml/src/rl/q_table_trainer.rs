@@ -1,14 +1,20 @@
 use super::*;
 use crate::prelude::RlSessionTypes;
+use bevy::platform::collections::HashMap;
+use bevy::platform::collections::HashSet;
 use sweet::prelude::*;
 
 
 /// Used for training a QTable to completion with a provided [`Environment`].
+/// The update rule is selected with [`Self::with_algorithm`].
 pub struct QTableTrainer<S: RlSessionTypes> {
 	pub table: S::QLearnPolicy,
 	pub env: Readonly<S::Env>,
 	pub params: Readonly<QLearnParams>,
 	initial_state: S::State,
+	algorithm: QLearnAlgorithm,
+	training_rewards: Vec<f32>,
+	training_steps: u128,
 }
 
 impl<S: RlSessionTypes> QTableTrainer<S> {
@@ -23,8 +29,26 @@ impl<S: RlSessionTypes> QTableTrainer<S> {
 			env: Readonly::new(env),
 			params: Readonly::new(params),
 			initial_state,
+			algorithm: QLearnAlgorithm::default(),
+			training_rewards: Vec::new(),
+			training_steps: 0,
 		}
 	}
+
+	/// The update rule used by [`QTrainer::train`].
+	pub fn algorithm(&self) -> QLearnAlgorithm { self.algorithm }
+
+	/// The reward of each episode during the last call to [`QTrainer::train`],
+	/// including exploration.
+	pub fn training_evaluation(&self) -> Evaluation {
+		Evaluation::new(self.training_rewards.clone(), self.training_steps)
+	}
+
+	/// Select the update rule, defaults to [`QLearnAlgorithm::QLearning`].
+	pub fn with_algorithm(mut self, algorithm: QLearnAlgorithm) -> Self {
+		self.algorithm = algorithm;
+		self
+	}
 }
 
 
@@ -60,34 +84,63 @@ impl<S: RlSessionTypes> QPolicy for QTableTrainer<S> {
 impl<S: RlSessionTypes> QTrainer for QTableTrainer<S>
 where
 	S::State: Clone,
+	S::Action: DiscreteActionSpace,
+	S::QLearnPolicy: Clone,
 {
 	fn train(&mut self, rng: &mut impl Rng) {
-		let params = &self.params;
+		self.training_rewards.clear();
+		self.training_steps = 0;
+		// only used by double q learning
+		let mut double = (self.algorithm == QLearnAlgorithm::DoubleQLearning)
+			.then(|| {
+				(
+					self.table.clone(),
+					HashSet::<(S::State, S::Action)>::default(),
+				)
+			});
 
-		for episode in 0..params.n_training_episodes {
-			let epsilon = params.epsilon(episode);
-			let mut env = self.env.clone();
-			let mut state = self.initial_state.clone();
-
-			'step: for _step in 0..params.max_steps {
-				// 1. select action
-				let (action, _) =
-					self.table.epsilon_greedy_policy(&state, epsilon, rng);
-				// 2. step environent
-				let outcome = env.step(&state, &action);
-				// 3. update reward
-				self.table.set_discounted_reward(
-					params,
-					&action,
-					outcome.reward,
-					&state,
-					&outcome.state,
-				);
-				// 4. update state and break if done
-				if outcome.done {
-					break 'step;
+		for episode in 0..self.params.n_training_episodes {
+			let mut run = Episode::<S::Env>::new(
+				&self.params,
+				&self.env,
+				&self.initial_state,
+				episode,
+				rng,
+			);
+			match self.algorithm {
+				QLearnAlgorithm::QLearning => {
+					run.q_learning(&mut self.table, rng)
+				}
+				QLearnAlgorithm::Sarsa => {
+					run.sarsa(&mut self.table, false, rng)
+				}
+				QLearnAlgorithm::ExpectedSarsa => {
+					run.sarsa(&mut self.table, true, rng)
+				}
+				QLearnAlgorithm::DoubleQLearning => {
+					let (double_table, visited) =
+						double.as_mut().expect("double table is created");
+					run.double_q_learning(
+						&mut self.table,
+						double_table,
+						visited,
+						rng,
+					)
+				}
+				QLearnAlgorithm::TdLambda { lambda } => {
+					run.td_lambda(&mut self.table, lambda, rng)
 				}
-				state = outcome.state;
+			}
+			self.training_rewards.push(run.total_reward);
+			self.training_steps += run.steps;
+		}
+
+		if let Some((double_table, visited)) = double {
+			for (state, action) in visited {
+				let value = (self.table.get_q(&state, &action)
+					+ double_table.get_q(&state, &action))
+					/ 2.;
+				self.table.set_q(&state, &action, value);
 			}
 		}
 	}
@@ -119,7 +172,237 @@ where
 }
 
 
+/// The state of a single training episode.
+struct Episode<'a, Env: Environment> {
+	params: &'a QLearnParams,
+	env: Env,
+	state: Env::State,
+	epsilon: f32,
+	total_reward: f32,
+	steps: u128,
+}
 
+impl<'a, Env: Environment> Episode<'a, Env> {
+	/// Start an episode with [`Environment::reset`].
+	fn new(
+		params: &'a QLearnParams,
+		env: &Env,
+		initial_state: &Env::State,
+		episode: u32,
+		rng: &mut impl Rng,
+	) -> Self {
+		let mut env = env.clone();
+		let state = env.reset(initial_state, rng);
+		Self {
+			params,
+			env,
+			state,
+			epsilon: params.epsilon(episode),
+			total_reward: 0.,
+			steps: 0,
+		}
+	}
+
+	fn step(&mut self, action: &Env::Action) -> StepOutcome<Env::State> {
+		let outcome = self.env.step(&self.state, action);
+		self.total_reward += outcome.reward;
+		self.steps += 1;
+		outcome
+	}
+
+	fn q_learning<P: QPolicy<State = Env::State, Action = Env::Action>>(
+		&mut self,
+		table: &mut P,
+		rng: &mut impl Rng,
+	) {
+		for _step in 0..self.params.max_steps {
+			// 1. select action
+			let (action, _) =
+				table.epsilon_greedy_policy(&self.state, self.epsilon, rng);
+			// 2. step environent
+			let outcome = self.step(&action);
+			// 3. update reward
+			table.set_discounted_reward(
+				self.params,
+				&action,
+				outcome.reward,
+				&self.state,
+				&outcome.state,
+			);
+			// 4. update state and break if done
+			if outcome.done {
+				break;
+			}
+			self.state = outcome.state;
+		}
+	}
+}
+
+impl<Env: Environment> Episode<'_, Env>
+where
+	Env::Action: DiscreteActionSpace,
+{
+	/// Sarsa if not `expected`, otherwise Expected Sarsa.
+	fn sarsa<P: QPolicy<State = Env::State, Action = Env::Action>>(
+		&mut self,
+		table: &mut P,
+		expected: bool,
+		rng: &mut impl Rng,
+	) {
+		let (mut action, _) =
+			table.epsilon_greedy_policy(&self.state, self.epsilon, rng);
+		for _step in 0..self.params.max_steps {
+			let outcome = self.step(&action);
+			let (next_action, _) =
+				table.epsilon_greedy_policy(&outcome.state, self.epsilon, rng);
+			let next_q = if outcome.done {
+				0.
+			} else if expected {
+				expected_q(table, &outcome.state, self.epsilon)
+			} else {
+				table.get_q(&outcome.state, &next_action)
+			};
+			let target = outcome.reward + self.params.gamma * next_q;
+			td_update(table, self.params, &self.state, &action, target);
+			if outcome.done {
+				break;
+			}
+			self.state = outcome.state;
+			action = next_action;
+		}
+	}
+
+	fn double_q_learning<
+		P: QPolicy<State = Env::State, Action = Env::Action>,
+	>(
+		&mut self,
+		table_a: &mut P,
+		table_b: &mut P,
+		visited: &mut HashSet<(Env::State, Env::Action)>,
+		rng: &mut impl Rng,
+	) {
+		for _step in 0..self.params.max_steps {
+			// act on the sum of both tables
+			let action = if rng.r#gen::<f32>() > self.epsilon {
+				best_action(|action| {
+					table_a.get_q(&self.state, action)
+						+ table_b.get_q(&self.state, action)
+				})
+				.0
+			} else {
+				Env::Action::sample(rng)
+			};
+			let outcome = self.step(&action);
+			let (table, other) = if rng.gen_bool(0.5) {
+				(&mut *table_a, &*table_b)
+			} else {
+				(&mut *table_b, &*table_a)
+			};
+			let next_q = if outcome.done {
+				0.
+			} else {
+				let (best, _) = max_q(table, &outcome.state);
+				other.get_q(&outcome.state, &best)
+			};
+			let target = outcome.reward + self.params.gamma * next_q;
+			td_update(table, self.params, &self.state, &action, target);
+			visited.insert((self.state.clone(), action));
+			if outcome.done {
+				break;
+			}
+			self.state = outcome.state;
+		}
+	}
+
+	fn td_lambda<P: QPolicy<State = Env::State, Action = Env::Action>>(
+		&mut self,
+		table: &mut P,
+		lambda: f32,
+		rng: &mut impl Rng,
+	) {
+		let mut traces = HashMap::<(Env::State, Env::Action), f32>::default();
+		let (mut action, _) =
+			table.epsilon_greedy_policy(&self.state, self.epsilon, rng);
+		for _step in 0..self.params.max_steps {
+			let outcome = self.step(&action);
+			let (next_action, _) =
+				table.epsilon_greedy_policy(&outcome.state, self.epsilon, rng);
+			let next_q = if outcome.done {
+				0.
+			} else {
+				table.get_q(&outcome.state, &next_action)
+			};
+			let delta = outcome.reward + self.params.gamma * next_q
+				- table.get_q(&self.state, &action);
+			traces.insert((self.state.clone(), action), 1.);
+			for ((state, action), trace) in traces.iter_mut() {
+				let prev_q = table.get_q(state, action);
+				let value = prev_q + self.params.learning_rate * delta * *trace;
+				table.set_q(state, action, value);
+				*trace *= self.params.gamma * lambda;
+			}
+			traces.retain(|_, trace| *trace > MIN_TRACE);
+			if outcome.done {
+				break;
+			}
+			self.state = outcome.state;
+			action = next_action;
+		}
+	}
+}
+
+/// Eligibility traces below this value are dropped.
+const MIN_TRACE: f32 = 0.001;
+
+/// Move `Q(s,a)` toward the `target` by the learning rate.
+fn td_update<P: QPolicy>(
+	table: &mut P,
+	params: &QLearnParams,
+	state: &P::State,
+	action: &P::Action,
+	target: QValue,
+) {
+	let prev_q = table.get_q(state, action);
+	let value = prev_q + params.learning_rate * (target - prev_q);
+	table.set_q(state, action, value);
+}
+
+/// The action with the highest value, the first is chosen on a tie.
+fn best_action<A: DiscreteActionSpace>(
+	value: impl Fn(&A) -> QValue,
+) -> (A, QValue) {
+	let mut best = (A::from_index(0), value(&A::VARIANTS[0]));
+	for action in A::VARIANTS.iter().skip(1) {
+		let q = value(action);
+		if q > best.1 {
+			best = (action.clone(), q);
+		}
+	}
+	best
+}
+
+/// Unlike [`QPolicy::greedy_policy`] this considers unvisited actions,
+/// which have a value of zero.
+fn max_q<P: QPolicy>(table: &P, state: &P::State) -> (P::Action, QValue)
+where
+	P::Action: DiscreteActionSpace,
+{
+	best_action(|action| table.get_q(state, action))
+}
+
+/// The expected value of a state when following the epsilon greedy policy.
+fn expected_q<P: QPolicy>(table: &P, state: &P::State, epsilon: f32) -> QValue
+where
+	P::Action: DiscreteActionSpace,
+{
+	let (_, max) = max_q(table, state);
+	let mean = P::Action::VARIANTS
+		.iter()
+		.map(|action| table.get_q(state, action))
+		.sum::<QValue>()
+		/ P::Action::num_actions() as f32;
+	(1. - epsilon) * max + epsilon * mean
+}
 
 
 #[cfg(test)]
@@ -161,4 +444,47 @@ mod test {
 		expect(eval.std).to_be(0.);
 		expect(eval.total_steps).to_be(600);
 	}
+
+	#[test]
+	fn algorithms() {
+		let map = FrozenLakeMap::default_four_by_four();
+		let initial_state = map.agent_position();
+		let env = QTableEnv::new(map.transition_outcomes());
+		let params = QLearnParams {
+			n_training_episodes: 2000,
+			learning_rate: 0.5,
+			min_epsilon: 0.01,
+			decay_rate: 0.003,
+			..Default::default()
+		};
+
+		for algorithm in [
+			QLearnAlgorithm::QLearning,
+			QLearnAlgorithm::Sarsa,
+			QLearnAlgorithm::ExpectedSarsa,
+			QLearnAlgorithm::DoubleQLearning,
+			QLearnAlgorithm::TdLambda { lambda: 0.8 },
+		] {
+			let mut trainer = QTableTrainer::<FrozenLakeQTableSession>::new(
+				env.clone(),
+				QTable::default(),
+				params.clone(),
+				initial_state,
+			)
+			.with_algorithm(algorithm);
+			trainer.train(&mut RandomSource::from_seed(0).0);
+
+			let training = trainer.training_evaluation();
+			expect(training.rewards.len()).to_be(2000);
+			let curve = training.window_means(500);
+			expect(curve.len()).to_be(4);
+			// mostly random exploration
+			expect(curve[0]).to_be_less_than(0.6);
+			// mostly exploitation
+			expect(curve[3]).to_be_greater_than(0.9);
+
+			let eval = trainer.evaluate();
+			expect(eval.rewards.len()).to_be(100);
+		}
+	}
 }
@@ -0,0 +1,342 @@
+use super::*;
+use candle_core::DType;
+use candle_core::Result;
+use candle_core::Tensor;
+use candle_nn::AdamW;
+use candle_nn::Module;
+use candle_nn::Optimizer;
+use candle_nn::ParamsAdamW;
+use candle_nn::VarBuilder;
+use candle_nn::VarMap;
+use sweet::prelude::*;
+
+/// Parameters for a [`ReinforceTrainer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReinforceParams {
+	/// The number of episodes to train for, the policy is updated
+	/// at the end of each one.
+	pub n_training_episodes: u32,
+	/// The number of episodes averaged by [`ReinforceTrainer::evaluate`].
+	pub n_eval_episodes: u32,
+	/// The maximum number of steps in an episode.
+	pub max_steps: u32,
+	/// The discount applied to future rewards.
+	pub gamma: f32,
+	/// The learning rate of both the policy and value optimizers.
+	pub learning_rate: f64,
+	/// The size of each of the two hidden layers.
+	pub hidden_size: usize,
+	/// Seeds the initial weights of the policy and value networks.
+	pub seed: u64,
+}
+
+impl Default for ReinforceParams {
+	fn default() -> Self {
+		Self {
+			n_training_episodes: 1000,
+			n_eval_episodes: 10,
+			max_steps: 200,
+			gamma: 0.99,
+			learning_rate: 3e-3,
+			hidden_size: 32,
+			seed: 0,
+		}
+	}
+}
+
+/// The states, sampled actions and rewards of a single episode.
+struct Rollout {
+	states: Vec<f32>,
+	actions: Vec<f32>,
+	rewards: Vec<f32>,
+}
+
+/// Used for training a [`GaussianPolicy`] to completion with a provided
+/// [`ContinuousEnvironment`], using the REINFORCE policy gradient
+/// with a learned value function as the baseline.
+/// Training runs on the CPU.
+pub struct ReinforceTrainer<Env: ContinuousEnvironment> {
+	pub env: Readonly<Env>,
+	pub params: Readonly<ReinforceParams>,
+	pub policy: GaussianPolicy,
+	value_varmap: VarMap,
+	value: Mlp,
+	policy_optimizer: AdamW,
+	value_optimizer: AdamW,
+}
+
+impl<Env: ContinuousEnvironment> ReinforceTrainer<Env> {
+	/// Create a trainer with a new policy and value function,
+	/// seeded by [`ReinforceParams::seed`].
+	pub fn new(env: Env, params: ReinforceParams) -> Result<Self> {
+		let policy = GaussianPolicy::new(
+			Env::State::LEN,
+			Env::Action::LEN,
+			params.hidden_size,
+		)?;
+		let value_varmap = VarMap::new();
+		let vb =
+			VarBuilder::from_varmap(&value_varmap, DType::F32, policy.device());
+		let value = Mlp::new(vb, &[
+			Env::State::LEN,
+			params.hidden_size,
+			params.hidden_size,
+			1,
+		])?;
+		let mut rng = RandomSource::from_seed(params.seed);
+		policy.init_seeded(&mut rng.0)?;
+		init_vars_seeded(&value_varmap, &mut rng.0)?;
+		let optimizer_params = ParamsAdamW {
+			lr: params.learning_rate,
+			weight_decay: 0.,
+			..Default::default()
+		};
+		let policy_optimizer =
+			AdamW::new(policy.all_vars(), optimizer_params.clone())?;
+		let value_optimizer =
+			AdamW::new(value_varmap.all_vars(), optimizer_params)?;
+		Ok(Self {
+			env: Readonly::new(env),
+			params: Readonly::new(params),
+			policy,
+			value_varmap,
+			value,
+			policy_optimizer,
+			value_optimizer,
+		})
+	}
+
+	/// Run a single episode, sampling from the policy if `explore`
+	/// or using the mean action otherwise.
+	fn rollout(&self, rng: &mut impl Rng, explore: bool) -> Result<Rollout> {
+		let mut env = self.env.clone();
+		let mut state = env.reset(rng);
+		let mut rollout = Rollout {
+			states: Vec::new(),
+			actions: Vec::new(),
+			rewards: Vec::new(),
+		};
+		for _step in 0..self.params.max_steps {
+			let state_vec = state.to_vec();
+			let action = if explore {
+				self.policy.sample_action(&state_vec, rng)?
+			} else {
+				self.policy.mean_action(&state_vec)?
+			};
+			let clamped = action
+				.iter()
+				.map(|value| value.clamp(-1., 1.))
+				.collect::<Vec<_>>();
+			let outcome = env.step(&state, &Env::Action::from_vec(&clamped));
+			rollout.states.extend(state_vec);
+			// the unclamped action is used for the log probability
+			rollout.actions.extend(action);
+			rollout.rewards.push(outcome.reward);
+			if outcome.done {
+				break;
+			}
+			state = outcome.state;
+		}
+		Ok(rollout)
+	}
+
+	/// Update the value function toward the discounted returns and the
+	/// policy toward actions that did better than expected.
+	fn learn(&mut self, rollout: Rollout) -> Result<()> {
+		let num_steps = rollout.rewards.len();
+		let device = self.policy.device().clone();
+		let mut returns = vec![0.; num_steps];
+		let mut next_return = 0.;
+		for (index, reward) in rollout.rewards.iter().enumerate().rev() {
+			next_return = reward + self.params.gamma * next_return;
+			returns[index] = next_return;
+		}
+
+		let states = Tensor::from_vec(
+			rollout.states,
+			(num_steps, Env::State::LEN),
+			&device,
+		)?;
+		let actions = Tensor::from_vec(
+			rollout.actions,
+			(num_steps, Env::Action::LEN),
+			&device,
+		)?;
+
+		// 1. value function
+		let values = self.value.forward(&states)?.squeeze(1)?;
+		let returns_tensor = Tensor::from_slice(&returns, num_steps, &device)?;
+		let value_loss = candle_nn::loss::mse(&values, &returns_tensor)?;
+		self.value_optimizer.backward_step(&value_loss)?;
+
+		// 2. normalized advantages
+		let values = values.detach().to_vec1::<f32>()?;
+		let mut advantages = returns
+			.iter()
+			.zip(values)
+			.map(|(ret, value)| ret - value)
+			.collect::<Vec<_>>();
+		if num_steps > 1 {
+			let mean = advantages.iter().sum::<f32>() / num_steps as f32;
+			let std = (advantages
+				.iter()
+				.map(|adv| (adv - mean).powi(2))
+				.sum::<f32>()
+				/ num_steps as f32)
+				.sqrt();
+			for adv in advantages.iter_mut() {
+				*adv = (*adv - mean) / (std + 1e-8);
+			}
+		}
+		let advantages = Tensor::from_vec(advantages, num_steps, &device)?;
+
+		// 3. policy gradient
+		let policy_loss = self
+			.policy
+			.log_prob(&states, &actions)?
+			.mul(&advantages)?
+			.mean_all()?
+			.neg()?;
+		self.policy_optimizer.backward_step(&policy_loss)
+	}
+
+	/// Fallible version of [`Self::train`].
+	pub fn try_train(&mut self, rng: &mut impl Rng) -> Result<()> {
+		for _episode in 0..self.params.n_training_episodes {
+			let rollout = self.rollout(rng, true)?;
+			self.learn(rollout)?;
+		}
+		Ok(())
+	}
+
+	/// Immediately train the policy.
+	pub fn train(&mut self, rng: &mut impl Rng) {
+		self.try_train(rng)
+			.expect("ReinforceTrainer: failed to train policy");
+	}
+
+	/// Evaluate the mean action of the policy for
+	/// [`ReinforceParams::n_eval_episodes`] episodes.
+	pub fn evaluate(&self, rng: &mut impl Rng) -> Evaluation {
+		let mut rewards = Vec::new();
+		let mut total_steps = 0;
+		for _episode in 0..self.params.n_eval_episodes {
+			let rollout = self
+				.rollout(rng, false)
+				.expect("ReinforceTrainer: failed to evaluate policy");
+			total_steps += rollout.rewards.len() as u128;
+			rewards.push(rollout.rewards.iter().sum());
+		}
+		Evaluation::new(rewards, total_steps)
+	}
+
+	/// The variables of the value function, usually only required
+	/// to resume training.
+	pub fn value_varmap(&self) -> &VarMap { &self.value_varmap }
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	#[cfg(feature = "spatial")]
+	use beet_spatial::prelude::*;
+	use sweet::prelude::*;
+
+	/// Move a point along a line toward the origin.
+	#[derive(Clone)]
+	struct ReachOrigin;
+
+	impl ContinuousEnvironment for ReachOrigin {
+		type State = f32;
+		type Action = f32;
+
+		fn reset(&mut self, rng: &mut impl Rng) -> f32 {
+			rng.gen_range(-1.0..1.0)
+		}
+
+		fn step(&mut self, state: &f32, action: &f32) -> StepOutcome<f32> {
+			let state = (state + action * 0.2).clamp(-2., 2.);
+			StepOutcome {
+				state,
+				reward: -state.abs(),
+				done: false,
+			}
+		}
+	}
+
+	#[test]
+	fn works() {
+		let params = ReinforceParams {
+			n_training_episodes: 300,
+			n_eval_episodes: 10,
+			max_steps: 20,
+			learning_rate: 1e-2,
+			..Default::default()
+		};
+		let train = || {
+			let mut trainer =
+				ReinforceTrainer::new(ReachOrigin, params.clone()).unwrap();
+			let untrained = trainer.evaluate(&mut RandomSource::from_seed(1).0);
+			trainer.train(&mut RandomSource::from_seed(0).0);
+			let trained = trainer.evaluate(&mut RandomSource::from_seed(1).0);
+			(untrained, trained)
+		};
+		let (untrained, trained) = train();
+
+		expect(trained.total_steps).to_be(200);
+		expect(trained.mean).to_be_greater_than(untrained.mean);
+		// the networks and rollouts are seeded
+		expect(train().1.mean).to_be(trained.mean);
+	}
+
+	/// Turn a differential drive to face a heading of zero.
+	#[cfg(feature = "spatial")]
+	#[derive(Clone, Default)]
+	struct FaceHeading {
+		drive: DifferentialDrive,
+	}
+
+	#[cfg(feature = "spatial")]
+	impl ContinuousEnvironment for FaceHeading {
+		type State = f32;
+		type Action = DualMotorValue;
+
+		fn reset(&mut self, rng: &mut impl Rng) -> f32 {
+			rng.gen_range(-1.0..1.0)
+		}
+
+		fn step(
+			&mut self,
+			heading: &f32,
+			motors: &DualMotorValue,
+		) -> StepOutcome<f32> {
+			let (_linear, angular) = self.drive.velocity(motors);
+			let heading = wrap_angle(heading + angular * 0.1);
+			StepOutcome {
+				state: heading,
+				reward: -heading.abs(),
+				done: false,
+			}
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "spatial")]
+	fn dual_motor() {
+		let params = ReinforceParams {
+			n_training_episodes: 300,
+			n_eval_episodes: 10,
+			max_steps: 20,
+			learning_rate: 1e-2,
+			..Default::default()
+		};
+		let mut trainer =
+			ReinforceTrainer::new(FaceHeading::default(), params).unwrap();
+		let untrained = trainer.evaluate(&mut RandomSource::from_seed(1).0);
+		trainer.train(&mut RandomSource::from_seed(0).0);
+		let trained = trainer.evaluate(&mut RandomSource::from_seed(1).0);
+
+		expect(trained.mean).to_be_greater_than(untrained.mean);
+	}
+}
@@ -0,0 +1,109 @@
+use sweet::prelude::*;
+
+/// A single step of experience, stored in a [`ReplayBuffer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition<State, Action> {
+	/// The state the action was taken in.
+	pub state: State,
+	/// The action taken.
+	pub action: Action,
+	/// The reward received for the action.
+	pub reward: f32,
+	/// The state after the action was taken.
+	pub next_state: State,
+	/// Whether the episode ended after this step.
+	pub done: bool,
+}
+
+/// A fixed size buffer of [`Transition`], once full the oldest
+/// transitions are overwritten. Sampling random batches breaks the
+/// correlation between consecutive steps when training a network.
+#[derive(Debug, Clone)]
+pub struct ReplayBuffer<State, Action> {
+	capacity: usize,
+	transitions: Vec<Transition<State, Action>>,
+	/// The index to write the next transition to, once full.
+	next: usize,
+}
+
+impl<State, Action> ReplayBuffer<State, Action> {
+	/// Create an empty buffer holding at most `capacity` transitions,
+	/// at least one.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity: capacity.max(1),
+			transitions: Vec::with_capacity(capacity),
+			next: 0,
+		}
+	}
+
+	/// The number of transitions currently stored.
+	pub fn len(&self) -> usize { self.transitions.len() }
+	/// Whether no transitions have been pushed.
+	pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+	/// The maximum number of transitions stored.
+	pub fn capacity(&self) -> usize { self.capacity }
+
+	/// Add a transition, overwriting the oldest if the buffer is full.
+	pub fn push(&mut self, transition: Transition<State, Action>) {
+		if self.transitions.len() < self.capacity {
+			self.transitions.push(transition);
+		} else {
+			self.transitions[self.next] = transition;
+		}
+		self.next = (self.next + 1) % self.capacity;
+	}
+
+	/// Sample `batch_size` transitions with replacement,
+	/// empty if the buffer is empty.
+	pub fn sample(
+		&self,
+		rng: &mut impl Rng,
+		batch_size: usize,
+	) -> Vec<&Transition<State, Action>> {
+		if self.transitions.is_empty() {
+			return Vec::new();
+		}
+		(0..batch_size)
+			.map(|_| {
+				&self.transitions[rng.gen_range(0..self.transitions.len())]
+			})
+			.collect()
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	fn transition(reward: f32) -> Transition<u32, u32> {
+		Transition {
+			state: 0,
+			action: 0,
+			reward,
+			next_state: 0,
+			done: false,
+		}
+	}
+
+	#[test]
+	fn works() {
+		let mut buffer = ReplayBuffer::new(3);
+		let mut rng = RandomSource::from_seed(0);
+		expect(buffer.sample(&mut rng.0, 2).is_empty()).to_be_true();
+		for i in 0..5 {
+			buffer.push(transition(i as f32));
+		}
+		expect(buffer.len()).to_be(3);
+		// the oldest are overwritten
+		let rewards = buffer
+			.sample(&mut rng.0, 20)
+			.into_iter()
+			.map(|transition| transition.reward)
+			.collect::<Vec<_>>();
+		expect(rewards.len()).to_be(20);
+		expect(rewards.iter().all(|reward| *reward >= 2.)).to_be_true();
+	}
+}
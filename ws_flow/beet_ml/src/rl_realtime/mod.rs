@@ -16,6 +16,11 @@ pub use self::rl_session::*;
 pub mod rl_session_types;
 #[allow(unused_imports)]
 pub use self::rl_session_types::*;
+#[cfg(feature = "candle")]
+pub mod run_policy;
+#[cfg(feature = "candle")]
+#[allow(unused_imports)]
+pub use self::run_policy::*;
 pub mod step_environment;
 #[allow(unused_imports)]
 pub use self::step_environment::*;
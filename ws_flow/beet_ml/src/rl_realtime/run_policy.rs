@@ -0,0 +1,148 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+
+/// Each tick, read the agent's `S` state and update its `A` action using
+/// the mean action of a trained [`GaussianPolicy`].
+/// The policy is specified with a [`HandleWrapper`] on the action entity,
+/// this action does nothing until the asset is loaded.
+/// Requires the [`RunPolicyPlugin`] for each `S` and `A` pair.
+/// With the `spatial` feature `DualMotorValue` and `Impulse` may be
+/// used as the action.
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// - [MutateOrigin](ActionTag::MutateOrigin)
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(ContinueRun)]
+pub struct RunPolicy<S: Component + TypePath, A: Component + TypePath> {
+	#[reflect(ignore)]
+	phantom: PhantomData<(S, A)>,
+}
+
+impl<S: Component + TypePath, A: Component + TypePath> Default
+	for RunPolicy<S, A>
+{
+	fn default() -> Self {
+		Self {
+			phantom: PhantomData,
+		}
+	}
+}
+
+/// Adds the system for a [`RunPolicy`] with the given state and action,
+/// and registers the [`GaussianPolicy`] asset if needed.
+pub struct RunPolicyPlugin<S, A> {
+	phantom: PhantomData<(S, A)>,
+}
+
+impl<S, A> Default for RunPolicyPlugin<S, A> {
+	fn default() -> Self {
+		Self {
+			phantom: PhantomData,
+		}
+	}
+}
+
+impl<
+	S: ContinuousSpace + Component + TypePath,
+	A: ContinuousSpace + Component<Mutability = Mutable> + TypePath,
+> Plugin for RunPolicyPlugin<S, A>
+{
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			TickSchedule::get(app),
+			run_policy::<S, A>.in_set(TickSet),
+		)
+		.register_type::<RunPolicy<S, A>>();
+		if !app.world().contains_resource::<Assets<GaussianPolicy>>() {
+			app.init_asset::<GaussianPolicy>()
+				.init_asset_loader::<GaussianPolicyLoader>();
+		}
+	}
+}
+
+fn run_policy<
+	S: ContinuousSpace + Component + TypePath,
+	A: ContinuousSpace + Component<Mutability = Mutable> + TypePath,
+>(
+	assets: Res<Assets<GaussianPolicy>>,
+	mut agents: Query<(&S, &mut A)>,
+	query: Query<
+		(&Running, &HandleWrapper<GaussianPolicy>),
+		With<RunPolicy<S, A>>,
+	>,
+) {
+	for (running, handle) in query.iter() {
+		let Some(policy) = assets.get(&**handle) else {
+			continue;
+		};
+		let (state, mut action) = agents
+			.get_mut(running.origin)
+			.expect(&expect_action::to_have_origin(&running));
+		match policy.mean_action(&state.to_vec()) {
+			Ok(values) => *action = A::from_vec(&values),
+			Err(err) => log::warn!("RunPolicy: {err}"),
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[derive(Debug, Clone, Component, Reflect)]
+	struct Observation(f32);
+	impl ContinuousSpace for Observation {
+		const LEN: usize = 1;
+		fn to_vec(&self) -> Vec<f32> { vec![self.0] }
+		fn from_vec(values: &[f32]) -> Self { Self(values[0]) }
+	}
+
+	#[derive(Debug, Clone, Component, Reflect)]
+	struct Thrust(f32);
+	impl ContinuousSpace for Thrust {
+		const LEN: usize = 1;
+		fn to_vec(&self) -> Vec<f32> { vec![self.0] }
+		fn from_vec(values: &[f32]) -> Self { Self(values[0]) }
+	}
+
+	#[test]
+	fn works() {
+		let mut app = App::new();
+		app.add_plugins((
+			MinimalPlugins,
+			AssetPlugin::default(),
+			BeetFlowPlugin::default(),
+			RunPolicyPlugin::<Observation, Thrust>::default(),
+		));
+		let policy = GaussianPolicy::new(1, 1, 8).unwrap();
+		let expected = policy.mean_action(&[0.5]).unwrap()[0];
+		let handle = app
+			.world_mut()
+			.resource_mut::<Assets<GaussianPolicy>>()
+			.add(policy);
+
+		let agent = app
+			.world_mut()
+			.spawn((
+				Observation(0.5),
+				Thrust(0.),
+				RunPolicy::<Observation, Thrust>::default(),
+				HandleWrapper(handle),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+		app.update();
+
+		expect(app.world().get::<Thrust>(agent).unwrap().0)
+			.to_be_close_to(expected);
+	}
+}
@@ -41,3 +41,92 @@ pub fn block_on_asset_load<'a, A: Asset>(
 		}
 	}
 }
+
+/// The `config.json` of a tiny bert model with a hidden size of 8.
+#[cfg(feature = "candle")]
+const TINY_BERT_CONFIG: &str = r#"{
+	"vocab_size": 16,
+	"hidden_size": 8,
+	"num_hidden_layers": 1,
+	"num_attention_heads": 2,
+	"intermediate_size": 16,
+	"hidden_act": "gelu",
+	"hidden_dropout_prob": 0.0,
+	"max_position_embeddings": 32,
+	"type_vocab_size": 2,
+	"initializer_range": 0.02,
+	"layer_norm_eps": 1e-12,
+	"pad_token_id": 0,
+	"position_embedding_type": "absolute",
+	"use_cache": true,
+	"classifier_dropout": null,
+	"model_type": "bert"
+}"#;
+
+/// A word level `tokenizer.json` matching [`TINY_BERT_CONFIG`].
+#[cfg(feature = "candle")]
+const TINY_BERT_TOKENIZER: &str = r#"{
+	"version": "1.0",
+	"truncation": null,
+	"padding": null,
+	"added_tokens": [],
+	"normalizer": null,
+	"pre_tokenizer": { "type": "Whitespace" },
+	"post_processor": null,
+	"decoder": null,
+	"model": {
+		"type": "WordLevel",
+		"vocab": {
+			"[PAD]": 0,
+			"[UNK]": 1,
+			"the": 2,
+			"a": 3,
+			"cat": 4,
+			"dog": 5,
+			"sits": 6,
+			"runs": 7,
+			"outside": 8,
+			"inside": 9
+		},
+		"unk_token": "[UNK]"
+	}
+}"#;
+
+/// The files of a tiny randomly initialized bert model,
+/// for testing without network access.
+#[cfg(feature = "candle")]
+pub fn tiny_bert_files() -> Result<crate::prelude::BertFiles> {
+	use candle_core::DType;
+	use candle_core::Device;
+	use candle_core::Tensor;
+	use candle_nn::VarBuilder;
+	use candle_nn::VarMap;
+	use candle_transformers::models::bert::BertModel;
+	use candle_transformers::models::bert::Config;
+	use std::sync::atomic::AtomicUsize;
+	use std::sync::atomic::Ordering;
+	static FIXTURE_ID: AtomicUsize = AtomicUsize::new(0);
+
+	let varmap = VarMap::new();
+	let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+	let config = serde_json::from_str::<Config>(TINY_BERT_CONFIG)?;
+	BertModel::load(vb, &config)?;
+	for var in varmap.all_vars() {
+		var.set(&Tensor::randn(0_f32, 0.5, var.dims(), &Device::Cpu)?)?;
+	}
+	// the varmap can only be saved to a file
+	let path = std::env::temp_dir().join(format!(
+		"beet_tiny_bert_{}_{}.safetensors",
+		std::process::id(),
+		FIXTURE_ID.fetch_add(1, Ordering::SeqCst)
+	));
+	varmap.save(&path)?;
+	let weights = std::fs::read(&path)?;
+	std::fs::remove_file(&path).ok();
+
+	Ok(crate::prelude::BertFiles {
+		config: TINY_BERT_CONFIG.as_bytes().to_vec(),
+		tokenizer: TINY_BERT_TOKENIZER.as_bytes().to_vec(),
+		weights,
+	})
+}
@@ -15,6 +15,7 @@ fn main() {
 			emoji_hexcode: "1F9D8".to_string(), //🧘
 			global_range: StatValue::range(0.0..1.),
 			default_value: StatValue(1.),
+			formula: None,
 		})
 		.with_stat(StatDescriptor {
 			name: STRESS.to_string(),
@@ -22,6 +23,7 @@ fn main() {
 			emoji_hexcode: "1F92F".to_string(), //🤯
 			global_range: StatValue::range(0.0..1.),
 			default_value: StatValue(0.),
+			formula: None,
 		});
 	App::new()
 		.add_plugins((BeetFlowPlugin::default(), BeetSimPlugin))
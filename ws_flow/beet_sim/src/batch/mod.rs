@@ -0,0 +1,6 @@
+pub mod sim_metrics;
+#[allow(unused_imports)]
+pub use self::sim_metrics::*;
+pub mod sim_runner;
+#[allow(unused_imports)]
+pub use self::sim_runner::*;
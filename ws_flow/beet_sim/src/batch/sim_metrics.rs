@@ -0,0 +1,346 @@
+use crate::prelude::*;
+use anyhow::Result;
+use bevy::prelude::*;
+use std::fmt::Write;
+
+/// The value of an agent's stat at a step of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatSample {
+	pub step: usize,
+	/// Elapsed simulation time in seconds.
+	pub time: f32,
+	pub agent: String,
+	pub stat: String,
+	pub value: f32,
+}
+
+/// How well an agent's desire was satisfied at a step of a run,
+/// see [`DesiredVaule::satisfaction`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesireSample {
+	pub step: usize,
+	/// Elapsed simulation time in seconds.
+	pub time: f32,
+	pub agent: String,
+	pub stat: String,
+	pub weight: f32,
+	pub satisfaction: f32,
+}
+
+/// The time series collected from a single run of a [`SimRunner`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RunMetrics {
+	pub seed: u64,
+	pub stats: Vec<StatSample>,
+	pub desires: Vec<DesireSample>,
+}
+
+impl RunMetrics {
+	pub fn new(seed: u64) -> Self { Self { seed, ..default() } }
+
+	/// Sample every stat and desire in the world.
+	///
+	/// Stats are entities with a [`StatId`] and [`StatValue`], their agent
+	/// is their parent. Desires are either a [`Desire<StatValue>`] or a
+	/// [`StatValueGoal`] with a [`StatId`], ie a [`StatScoreProvider`],
+	/// and belong to the nearest ancestor, including itself, with a
+	/// child stat of the same id.
+	pub fn record(&mut self, world: &mut World, step: usize) {
+		let time = world.resource::<Time>().elapsed_secs();
+		let stat_map = world.resource::<StatMap>().clone();
+
+		let mut stats = Vec::new();
+		for (id, value, child_of) in
+			world.query::<(&StatId, &StatValue, &ChildOf)>().iter(world)
+		{
+			let Some(descriptor) = stat_map.get(id) else {
+				continue;
+			};
+			stats.push(StatSample {
+				step,
+				time,
+				agent: agent_name(world, child_of.parent()),
+				stat: descriptor.name.clone(),
+				value: value.0,
+			});
+		}
+		stats.sort_by(|a, b| (&a.agent, &a.stat).cmp(&(&b.agent, &b.stat)));
+		self.stats.extend(stats);
+
+		let mut desired = world
+			.query::<(Entity, &Desire<StatValue>)>()
+			.iter(world)
+			.map(|(entity, desire)| {
+				(entity, desire.stat_id, desire.value.clone(), desire.weight)
+			})
+			.collect::<Vec<_>>();
+		desired.extend(
+			world
+				.query::<(Entity, &StatId, &StatValueGoal)>()
+				.iter(world)
+				.map(|(entity, id, goal)| (entity, *id, (*goal).into(), 1.)),
+		);
+
+		let mut desires = Vec::new();
+		for (entity, stat_id, value, weight) in desired {
+			let Some(descriptor) = stat_map.get(&stat_id) else {
+				continue;
+			};
+			let Some((agent, stat_value)) =
+				find_agent_stat(world, entity, stat_id)
+			else {
+				continue;
+			};
+			desires.push(DesireSample {
+				step,
+				time,
+				agent: agent_name(world, agent),
+				stat: descriptor.name.clone(),
+				weight,
+				satisfaction: value
+					.satisfaction(stat_value, descriptor.global_range.clone()),
+			});
+		}
+		desires.sort_by(|a, b| (&a.agent, &a.stat).cmp(&(&b.agent, &b.stat)));
+		self.desires.extend(desires);
+	}
+
+	/// The weighted mean satisfaction of all desire samples,
+	/// or zero if there are none.
+	pub fn mean_satisfaction(&self) -> f32 {
+		let total_weight = self.desires.iter().map(|d| d.weight).sum::<f32>();
+		if total_weight == 0. {
+			return 0.;
+		}
+		self.desires
+			.iter()
+			.map(|d| d.satisfaction * d.weight)
+			.sum::<f32>()
+			/ total_weight
+	}
+
+	/// The values of a stat for an agent, in order of step.
+	pub fn series(&self, agent: &str, stat: &str) -> Vec<f32> {
+		self.stats
+			.iter()
+			.filter(|sample| sample.agent == agent && sample.stat == stat)
+			.map(|sample| sample.value)
+			.collect()
+	}
+}
+
+/// The [`Name`] of the agent, or its entity id.
+fn agent_name(world: &World, agent: Entity) -> String {
+	world
+		.get::<Name>(agent)
+		.map(|name| name.to_string())
+		.unwrap_or_else(|| agent.to_string())
+}
+
+/// Walk up from the entity to find the first with a child stat
+/// matching the id.
+fn find_agent_stat(
+	world: &World,
+	entity: Entity,
+	stat_id: StatId,
+) -> Option<(Entity, StatValue)> {
+	let mut current = Some(entity);
+	while let Some(agent) = current {
+		let value = world.get::<Children>(agent).and_then(|children| {
+			children.iter().find_map(|child| {
+				(world.get::<StatId>(child) == Some(&stat_id))
+					.then(|| world.get::<StatValue>(child).copied())
+					.flatten()
+			})
+		});
+		if let Some(value) = value {
+			return Some((agent, value));
+		}
+		current = world
+			.get::<ChildOf>(agent)
+			.map(|child_of| child_of.parent());
+	}
+	None
+}
+
+/// The statistics of a single time series in a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesSummary {
+	pub seed: u64,
+	pub agent: String,
+	pub stat: String,
+	/// Either `stat` for a stat value or `desire` for its satisfaction.
+	pub kind: &'static str,
+	pub mean: f32,
+	pub min: f32,
+	pub max: f32,
+	pub last: f32,
+}
+
+impl SeriesSummary {
+	fn new(
+		seed: u64,
+		agent: &str,
+		stat: &str,
+		kind: &'static str,
+		values: &[f32],
+	) -> Self {
+		Self {
+			seed,
+			agent: agent.to_string(),
+			stat: stat.to_string(),
+			kind,
+			mean: values.iter().sum::<f32>() / values.len().max(1) as f32,
+			min: values.iter().copied().fold(f32::INFINITY, f32::min),
+			max: values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+			last: values.last().copied().unwrap_or_default(),
+		}
+	}
+}
+
+/// The metrics of every run in a batch, exported as csv in long format,
+/// one row per sample, for loading into dataframe tools or converting
+/// to parquet.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BatchReport {
+	pub runs: Vec<RunMetrics>,
+}
+
+impl BatchReport {
+	/// The mean of [`RunMetrics::mean_satisfaction`] across all runs.
+	pub fn mean_satisfaction(&self) -> f32 {
+		self.runs
+			.iter()
+			.map(|run| run.mean_satisfaction())
+			.sum::<f32>()
+			/ self.runs.len().max(1) as f32
+	}
+
+	/// Summarize each stat and desire time series of each run.
+	pub fn summary(&self) -> Vec<SeriesSummary> {
+		let mut summaries = Vec::new();
+		for run in &self.runs {
+			let mut keys = run
+				.stats
+				.iter()
+				.map(|sample| (&sample.agent, &sample.stat))
+				.collect::<Vec<_>>();
+			keys.sort();
+			keys.dedup();
+			for (agent, stat) in keys {
+				let values = run.series(agent, stat);
+				summaries.push(SeriesSummary::new(
+					run.seed, agent, stat, "stat", &values,
+				));
+			}
+			let mut keys = run
+				.desires
+				.iter()
+				.map(|sample| (&sample.agent, &sample.stat))
+				.collect::<Vec<_>>();
+			keys.sort();
+			keys.dedup();
+			for (agent, stat) in keys {
+				let values = run
+					.desires
+					.iter()
+					.filter(|d| &d.agent == agent && &d.stat == stat)
+					.map(|d| d.satisfaction)
+					.collect::<Vec<_>>();
+				summaries.push(SeriesSummary::new(
+					run.seed, agent, stat, "desire", &values,
+				));
+			}
+		}
+		summaries
+	}
+
+	/// Every stat sample with the columns
+	/// `seed,step,time,agent,stat,value`.
+	pub fn stats_csv(&self) -> String {
+		let mut csv = String::from("seed,step,time,agent,stat,value\n");
+		for run in &self.runs {
+			for sample in &run.stats {
+				writeln!(
+					csv,
+					"{},{},{},{},{},{}",
+					run.seed,
+					sample.step,
+					sample.time,
+					csv_field(&sample.agent),
+					csv_field(&sample.stat),
+					sample.value
+				)
+				.unwrap();
+			}
+		}
+		csv
+	}
+
+	/// Every desire sample with the columns
+	/// `seed,step,time,agent,stat,weight,satisfaction`.
+	pub fn desires_csv(&self) -> String {
+		let mut csv =
+			String::from("seed,step,time,agent,stat,weight,satisfaction\n");
+		for run in &self.runs {
+			for sample in &run.desires {
+				writeln!(
+					csv,
+					"{},{},{},{},{},{},{}",
+					run.seed,
+					sample.step,
+					sample.time,
+					csv_field(&sample.agent),
+					csv_field(&sample.stat),
+					sample.weight,
+					sample.satisfaction
+				)
+				.unwrap();
+			}
+		}
+		csv
+	}
+
+	/// The [`Self::summary`] with the columns
+	/// `seed,agent,stat,kind,mean,min,max,last`.
+	pub fn summary_csv(&self) -> String {
+		let mut csv = String::from("seed,agent,stat,kind,mean,min,max,last\n");
+		for summary in self.summary() {
+			writeln!(
+				csv,
+				"{},{},{},{},{},{},{},{}",
+				summary.seed,
+				csv_field(&summary.agent),
+				csv_field(&summary.stat),
+				summary.kind,
+				summary.mean,
+				summary.min,
+				summary.max,
+				summary.last
+			)
+			.unwrap();
+		}
+		csv
+	}
+
+	/// Write `stats.csv`, `desires.csv` and `summary.csv` to the
+	/// directory, creating it if it does not exist.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn write_csv(&self, dir: impl AsRef<std::path::Path>) -> Result<()> {
+		let dir = dir.as_ref();
+		std::fs::create_dir_all(dir)?;
+		std::fs::write(dir.join("stats.csv"), self.stats_csv())?;
+		std::fs::write(dir.join("desires.csv"), self.desires_csv())?;
+		std::fs::write(dir.join("summary.csv"), self.summary_csv())?;
+		Ok(())
+	}
+}
+
+/// Quote a field if it contains a comma, quote or newline.
+fn csv_field(field: &str) -> String {
+	if field.contains([',', '"', '\n']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
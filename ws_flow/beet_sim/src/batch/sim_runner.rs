@@ -0,0 +1,224 @@
+use crate::prelude::*;
+use anyhow::Result;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::time::Duration;
+
+/// Spawns the agents and behaviors of a run, called with the seed of
+/// the run after the plugins and [`StatMap`] have been added.
+pub type SimSetup = Box<dyn Fn(&mut App, u64)>;
+
+/// Headlessly steps a [`SimDescriptor`] scenario once for each seed,
+/// collecting the stats and desire satisfaction of every agent into a
+/// [`BatchReport`]. Useful for tuning [`Desire`] weights and
+/// [`StatScoreProvider`] curves offline or in regression tests.
+///
+/// Each run is a new [`App`] seeded by [`BeetFlowPlugin::deterministic`]
+/// and [`Time`] advancing by exactly `step_duration` per step.
+/// ## Example
+/// ```no_run
+/// # use beet_sim::prelude::*;
+/// # use bevy::prelude::*;
+/// let report = SimRunner::new(SimDescriptor::default(), |app, _seed| {
+/// 	app.world_mut().spawn(Name::new("Agent"));
+/// })
+/// .with_runs(10)
+/// .with_steps(1000)
+/// .run()
+/// .unwrap();
+/// report.write_csv("target/sim").unwrap();
+/// ```
+pub struct SimRunner {
+	pub descriptor: SimDescriptor,
+	pub seeds: Vec<u64>,
+	/// The number of updates in each run.
+	pub steps: usize,
+	/// The time advanced by each update.
+	pub step_duration: Duration,
+	/// Record metrics every n steps.
+	pub sample_interval: usize,
+	setup: SimSetup,
+}
+
+impl SimRunner {
+	/// Create a runner with a single run of 100 steps of 100ms.
+	pub fn new(
+		descriptor: SimDescriptor,
+		setup: impl 'static + Fn(&mut App, u64),
+	) -> Self {
+		Self {
+			descriptor,
+			seeds: vec![0],
+			steps: 100,
+			step_duration: Duration::from_millis(100),
+			sample_interval: 1,
+			setup: Box::new(setup),
+		}
+	}
+
+	/// Run once for each of the given seeds.
+	pub fn with_seeds(mut self, seeds: impl IntoIterator<Item = u64>) -> Self {
+		self.seeds = seeds.into_iter().collect();
+		self
+	}
+	/// Use the seeds `0..runs`.
+	pub fn with_runs(self, runs: u64) -> Self { self.with_seeds(0..runs) }
+	/// Set the number of updates in each run.
+	pub fn with_steps(mut self, steps: usize) -> Self {
+		self.steps = steps;
+		self
+	}
+	/// Set the time advanced by each update.
+	pub fn with_step_duration(mut self, step_duration: Duration) -> Self {
+		self.step_duration = step_duration;
+		self
+	}
+	/// Record metrics every n steps, at least one.
+	pub fn with_sample_interval(mut self, sample_interval: usize) -> Self {
+		self.sample_interval = sample_interval.max(1);
+		self
+	}
+
+	/// Run the scenario once for each seed.
+	/// # Errors
+	/// If the descriptor is invalid.
+	pub fn run(&self) -> Result<BatchReport> {
+		self.descriptor.validate()?;
+		let runs = self.seeds.iter().map(|seed| self.run_once(*seed)).collect();
+		Ok(BatchReport { runs })
+	}
+
+	/// Run the scenario with the given seed, without validating the
+	/// descriptor, see [`SimDescriptor::validate`].
+	pub fn run_once(&self, seed: u64) -> RunMetrics {
+		let mut app = self.build_app(seed);
+		let mut metrics = RunMetrics::new(seed);
+		for step in 0..self.steps {
+			app.update();
+			if step % self.sample_interval == 0 {
+				metrics.record(app.world_mut(), step);
+			}
+		}
+		metrics
+	}
+
+	fn build_app(&self, seed: u64) -> App {
+		let mut app = App::new();
+		app.add_plugins((
+			MinimalPlugins,
+			BeetFlowPlugin::deterministic(seed),
+			BeetSimPlugin,
+		))
+		.insert_resource(StatMap::from_sim_descriptor(&self.descriptor))
+		.insert_resource(Time::<Fixed>::from_duration(self.step_duration))
+		.insert_resource(TimeUpdateStrategy::ManualDuration(
+			self.step_duration,
+		));
+		// long steps would otherwise be clamped
+		let mut time = app.world_mut().resource_mut::<Time<Virtual>>();
+		let max_delta = time.max_delta().max(self.step_duration);
+		time.set_max_delta(max_delta);
+		(self.setup)(&mut app, seed);
+		app
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	const SCENARIO: &str = r#"(
+	stats: [
+		(
+			name: "Stress",
+			global_range: (start: 0.0, end: 1.0),
+			default_value: 0.0,
+		),
+		(
+			name: "Calm",
+			global_range: (start: 0.0, end: 1.0),
+			default_value: 1.0,
+			formula: Some(Sub(Const(1.0), Stat("Stress"))),
+		),
+	],
+)"#;
+
+	fn stress(
+		time: Res<Time>,
+		stat_map: Res<StatMap>,
+		mut rng: ResMut<RandomSource>,
+		mut stats: Query<(&StatId, &mut StatValue)>,
+	) {
+		let stress = stat_map.get_id_by_name("Stress").unwrap();
+		for (id, mut value) in stats.iter_mut() {
+			if *id == stress {
+				let delta = rng.random_range(0.0..1.0) * time.delta_secs();
+				value.0 = (value.0 + delta).min(1.);
+			}
+		}
+	}
+
+	fn runner() -> SimRunner {
+		let descriptor = SimDescriptor::from_ron(SCENARIO).unwrap();
+		SimRunner::new(descriptor, |app, _seed| {
+			app.add_systems(Update, stress.before(update_derived_stats));
+			let stat_map = app.world().resource::<StatMap>().clone();
+			let stress = stat_map.get_id_by_name("Stress").unwrap();
+			let calm = stat_map.get_id_by_name("Calm").unwrap();
+			app.world_mut()
+				.spawn((
+					Name::new("Agent"),
+					Desire::<StatValue>::new(calm, DesiredVaule::Max)
+						.with_weight(2.),
+				))
+				.with_children(|parent| {
+					parent.spawn((stress, StatValue(0.)));
+					parent.spawn((calm, StatValue(1.)));
+				});
+		})
+		.with_steps(10)
+		.with_sample_interval(2)
+	}
+
+	#[test]
+	fn runs() {
+		let report = runner().with_seeds([0, 0, 1]).run().unwrap();
+		expect(report.runs.len()).to_be(3);
+		let run = &report.runs[0];
+		expect(run.stats.len()).to_be(10);
+		expect(run.desires.len()).to_be(5);
+		expect(run.desires[0].weight).to_be(2.);
+
+		let stress = run.series("Agent", "Stress");
+		let calm = run.series("Agent", "Calm");
+		expect(*stress.last().unwrap()).to_be_greater_than(0.);
+		for (stress, calm) in stress.iter().zip(calm.iter()) {
+			expect(*calm).to_be_close_to(1. - stress);
+		}
+		expect(run.mean_satisfaction())
+			.to_be_close_to(calm.iter().sum::<f32>() / calm.len() as f32);
+
+		// deterministic by seed
+		expect(&report.runs[1].stats).to_be(&run.stats);
+		expect(report.runs[2].stats != run.stats).to_be_true();
+	}
+
+	#[test]
+	fn csv() {
+		let report = runner().with_runs(2).run().unwrap();
+		let stats = report.stats_csv();
+		expect(&stats).to_contain("seed,step,time,agent,stat,value\n");
+		expect(stats.lines().count()).to_be(1 + 2 * 10);
+		let desires = report.desires_csv();
+		expect(&desires)
+			.to_contain("seed,step,time,agent,stat,weight,satisfaction\n");
+		expect(desires.lines().count()).to_be(1 + 2 * 5);
+		let summary = report.summary_csv();
+		expect(&summary).to_contain("0,Agent,Calm,desire,");
+		// stress and calm stats plus the calm desire, for each seed
+		expect(summary.lines().count()).to_be(1 + 2 * 3);
+	}
+}
@@ -1,17 +1,85 @@
 use crate::prelude::*;
 use bevy::prelude::*;
+use std::ops::Range;
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Desire<T> {
 	pub value: DesiredVaule<T>,
 	pub stat_id: StatId,
+	/// The relative importance of this desire when
+	/// combining the satisfaction of several desires.
+	pub weight: f32,
 }
 
-#[derive(Debug, Default, Reflect)]
+impl<T> Desire<T> {
+	/// Create a desire for the stat with a weight of `1`.
+	pub fn new(stat_id: StatId, value: DesiredVaule<T>) -> Self {
+		Self {
+			value,
+			stat_id,
+			weight: 1.,
+		}
+	}
+
+	/// Set the relative importance of this desire.
+	pub fn with_weight(mut self, weight: f32) -> Self {
+		self.weight = weight;
+		self
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Reflect)]
 pub enum DesiredVaule<T> {
 	Min,
 	#[default]
 	Max,
 	Exact(T),
 }
+
+impl DesiredVaule<StatValue> {
+	/// How close the value is to the desired value, in a range of `0..1`
+	/// where `1` is completely satisfied.
+	pub fn satisfaction(
+		&self,
+		value: StatValue,
+		range: Range<StatValue>,
+	) -> f32 {
+		let normalized = value.normalize(range.clone()).clamp(0., 1.);
+		match self {
+			Self::Min => 1. - normalized,
+			Self::Max => normalized,
+			Self::Exact(target) => {
+				let target = target.normalize(range).clamp(0., 1.);
+				1. - (normalized - target).abs()
+			}
+		}
+	}
+}
+
+impl From<StatValueGoal> for DesiredVaule<StatValue> {
+	fn from(goal: StatValueGoal) -> Self {
+		match goal {
+			StatValueGoal::High => Self::Max,
+			StatValueGoal::Low => Self::Min,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn satisfaction() {
+		let range = StatValue::range(-1.0..3.0);
+		let value = StatValue(0.);
+		let max = DesiredVaule::<StatValue>::Max;
+		expect(max.satisfaction(value, range.clone())).to_be(0.25);
+		let min = DesiredVaule::<StatValue>::Min;
+		expect(min.satisfaction(value, range.clone())).to_be(0.75);
+		expect(DesiredVaule::Exact(StatValue(1.)).satisfaction(value, range))
+			.to_be(0.75);
+	}
+}
@@ -3,22 +3,26 @@
 #![feature(let_chains)]
 // temp until replace emoji stuff
 #![allow(unused)]
+pub mod batch;
 pub mod behavior;
 pub mod plugins;
 pub mod render;
 pub mod sim;
 pub mod stat_modifiers;
 pub mod stats;
+pub mod utility;
 
 
 
 
 
 pub mod prelude {
+	pub use crate::batch::*;
 	pub use crate::behavior::*;
 	pub use crate::plugins::*;
 	pub use crate::render::*;
 	pub use crate::sim::*;
 	pub use crate::stat_modifiers::*;
 	pub use crate::stats::*;
+	pub use crate::utility::*;
 }
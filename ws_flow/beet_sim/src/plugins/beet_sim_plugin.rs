@@ -10,6 +10,7 @@ impl Plugin for BeetSimPlugin {
 			// emoji_plugin,
 			walk_plugin,
 			stat_plugin,
+			utility_plugin,
 		))
 		.add_systems(
 			Update,
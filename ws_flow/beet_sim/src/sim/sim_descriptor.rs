@@ -1,9 +1,204 @@
 use crate::prelude::*;
+use anyhow::Result;
+use bevy::platform::collections::HashMap;
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 
 
-
-#[derive(Debug, Default, Clone, PartialEq, Reflect)]
+/// A catalogue of stats, usually authored in ron by designers:
+/// ```ron
+/// (
+/// 	stats: [
+/// 		(
+/// 			name: "Stress",
+/// 			global_range: (start: 0.0, end: 1.0),
+/// 			default_value: 0.0,
+/// 		),
+/// 		(
+/// 			name: "Calm",
+/// 			global_range: (start: 0.0, end: 1.0),
+/// 			default_value: 1.0,
+/// 			formula: Some(Sub(Const(1.0), Stat("Stress"))),
+/// 		),
+/// 	],
+/// )
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Reflect, Serialize, Deserialize)]
 pub struct SimDescriptor {
 	pub stats: Vec<StatDescriptor>,
 }
+
+impl SimDescriptor {
+	/// Parse and [validate](Self::validate) a descriptor.
+	pub fn from_ron(ron: &str) -> Result<Self> {
+		let descriptor: Self = ron::from_str(ron)?;
+		descriptor.validate()?;
+		Ok(descriptor)
+	}
+
+	pub fn to_ron(&self) -> Result<String> {
+		Ok(ron::ser::to_string_pretty(
+			self,
+			ron::ser::PrettyConfig::default(),
+		)?)
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+		Self::from_ron(&std::fs::read_to_string(path)?)
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+		std::fs::write(path, self.to_ron()?)?;
+		Ok(())
+	}
+
+	/// Check that stat names are unique, ranges are ordered and not NaN,
+	/// default values are within their range, and formulas have valid
+	/// clamp bounds and only reference existing stats without cycles.
+	pub fn validate(&self) -> Result<()> {
+		let mut stats = HashMap::default();
+		for stat in &self.stats {
+			if stats.insert(stat.name.as_str(), stat).is_some() {
+				anyhow::bail!("duplicate stat name: {}", stat.name);
+			}
+			let (start, end) =
+				(*stat.global_range.start, *stat.global_range.end);
+			if start.is_nan() || end.is_nan() || start > end {
+				anyhow::bail!(
+					"invalid range for {}: {start}..{end}",
+					stat.name
+				);
+			}
+			if !stat.global_range.contains(&stat.default_value)
+				&& stat.default_value != stat.global_range.end
+			{
+				anyhow::bail!(
+					"default value of {} is outside its range: {}",
+					stat.name,
+					stat.default_value
+				);
+			}
+		}
+		for stat in &self.stats {
+			let Some(formula) = &stat.formula else {
+				continue;
+			};
+			if let Err(err) = formula.validate() {
+				anyhow::bail!("formula for {} is invalid: {err}", stat.name);
+			}
+			for name in formula.dependencies() {
+				if !stats.contains_key(name) {
+					anyhow::bail!(
+						"formula for {} references unknown stat: {name}",
+						stat.name
+					);
+				}
+			}
+		}
+		let mut visited = HashSet::default();
+		for stat in &self.stats {
+			Self::check_cycles(&stats, stat, &mut Vec::new(), &mut visited)?;
+		}
+		Ok(())
+	}
+
+	/// Depth first search through formula dependencies.
+	fn check_cycles<'a>(
+		stats: &HashMap<&'a str, &'a StatDescriptor>,
+		stat: &'a StatDescriptor,
+		path: &mut Vec<&'a str>,
+		visited: &mut HashSet<&'a str>,
+	) -> Result<()> {
+		if path.contains(&stat.name.as_str()) {
+			path.push(&stat.name);
+			anyhow::bail!("cyclic stat formulas: {}", path.join(" -> "));
+		}
+		if !visited.insert(&stat.name) {
+			return Ok(());
+		}
+		let Some(formula) = &stat.formula else {
+			return Ok(());
+		};
+		path.push(&stat.name);
+		for name in formula.dependencies() {
+			if let Some(dependency) = stats.get(name) {
+				Self::check_cycles(stats, dependency, path, visited)?;
+			}
+		}
+		path.pop();
+		Ok(())
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	const CATALOGUE: &str = r#"(
+	stats: [
+		(
+			name: "Stress",
+			global_range: (start: 0.0, end: 1.0),
+			default_value: 0.0,
+		),
+		(
+			name: "Calm",
+			global_range: (start: 0.0, end: 1.0),
+			default_value: 1.0,
+			formula: Some(Sub(Const(1.0), Stat("Stress"))),
+		),
+	],
+)"#;
+
+	#[test]
+	fn ron() {
+		let descriptor = SimDescriptor::from_ron(CATALOGUE).unwrap();
+		expect(descriptor.stats.len()).to_be(2);
+		expect(&descriptor.stats[1].formula).to_be(&Some(StatFormula::Sub(
+			Box::new(StatFormula::Const(1.)),
+			Box::new(StatFormula::stat("Stress")),
+		)));
+
+		let ron = descriptor.to_ron().unwrap();
+		expect(SimDescriptor::from_ron(&ron).unwrap()).to_be(descriptor);
+	}
+
+	#[test]
+	fn validate() {
+		let invalid = |find: &str, replace: &str| {
+			SimDescriptor::from_ron(&CATALOGUE.replace(find, replace))
+				.unwrap_err()
+				.to_string()
+		};
+		expect(invalid("\"Calm\"", "\"Stress\"")).to_contain("duplicate");
+		expect(invalid("default_value: 0.0", "default_value: 2.0"))
+			.to_contain("outside its range");
+		expect(invalid("Stat(\"Stress\")", "Stat(\"Joy\")"))
+			.to_contain("unknown stat: Joy");
+		expect(invalid(
+			"default_value: 0.0,",
+			"default_value: 0.0, formula: Some(Stat(\"Calm\")),",
+		))
+		.to_contain("cyclic");
+		expect(invalid("(start: 0.0, end: 1.0)", "(start: 1.0, end: 0.0)"))
+			.to_contain("invalid range");
+		expect(invalid("(start: 0.0, end: 1.0)", "(start: NaN, end: 1.0)"))
+			.to_contain("invalid range");
+		expect(invalid(
+			"Sub(Const(1.0), Stat(\"Stress\"))",
+			"Clamp(value: Stat(\"Stress\"), min: 1.0, max: 0.0)",
+		))
+		.to_contain("invalid clamp bounds");
+		expect(invalid(
+			"Sub(Const(1.0), Stat(\"Stress\"))",
+			"Clamp(value: Stat(\"Stress\"), min: NaN, max: 1.0)",
+		))
+		.to_contain("invalid clamp bounds");
+	}
+}
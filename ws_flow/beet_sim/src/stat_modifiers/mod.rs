@@ -1,6 +1,9 @@
 pub mod collectable;
 #[allow(unused_imports)]
 pub use self::collectable::*;
+pub mod modifier_stack;
+#[allow(unused_imports)]
+pub use self::modifier_stack::*;
 pub mod modify_over_time;
 #[allow(unused_imports)]
 pub use self::modify_over_time::*;
@@ -0,0 +1,236 @@
+use crate::prelude::*;
+use bevy::ecs::entity::MapEntities;
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// How a [`StatModifier`] changes the value of a stat.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum StatModifierOp {
+	/// Add to the value, use a negative amount to subtract.
+	Add(f32),
+	/// Multiply the value.
+	Multiply(f32),
+	/// Replace the value, ignoring all modifiers applied before it.
+	Override(f32),
+}
+
+impl StatModifierOp {
+	pub fn apply(&self, value: f32) -> f32 {
+		match self {
+			Self::Add(amount) => value + amount,
+			Self::Multiply(amount) => value * amount,
+			Self::Override(amount) => *amount,
+		}
+	}
+}
+
+/// A single entry in a [`StatModifiers`] stack.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct StatModifier {
+	pub op: StatModifierOp,
+	/// Modifiers are applied in ascending order of priority,
+	/// those with equal priority are applied in the order they were added.
+	pub priority: i32,
+	/// Remove this modifier once the timer finishes,
+	/// if `None` it lasts until removed.
+	pub expiry: Option<Timer>,
+	/// The entity that applied this modifier, ie a zone,
+	/// used to remove its modifiers with [`StatModifiers::remove_source`].
+	pub source: Option<Entity>,
+}
+
+impl StatModifier {
+	/// By default additions are applied first.
+	pub const ADD_PRIORITY: i32 = 0;
+	/// By default multiplications are applied after additions.
+	pub const MULTIPLY_PRIORITY: i32 = 100;
+	/// By default overrides are applied last.
+	pub const OVERRIDE_PRIORITY: i32 = 200;
+
+	pub fn new(op: StatModifierOp, priority: i32) -> Self {
+		Self {
+			op,
+			priority,
+			expiry: None,
+			source: None,
+		}
+	}
+
+	pub fn add(amount: f32) -> Self {
+		Self::new(StatModifierOp::Add(amount), Self::ADD_PRIORITY)
+	}
+	pub fn multiply(amount: f32) -> Self {
+		Self::new(StatModifierOp::Multiply(amount), Self::MULTIPLY_PRIORITY)
+	}
+	pub fn set(value: f32) -> Self {
+		Self::new(StatModifierOp::Override(value), Self::OVERRIDE_PRIORITY)
+	}
+
+	pub fn with_priority(mut self, priority: i32) -> Self {
+		self.priority = priority;
+		self
+	}
+	/// Remove this modifier after the given duration.
+	pub fn with_duration(mut self, duration: Duration) -> Self {
+		self.expiry = Some(Timer::new(duration, TimerMode::Once));
+		self
+	}
+	pub fn with_source(mut self, source: Entity) -> Self {
+		self.source = Some(source);
+		self
+	}
+
+	pub fn is_expired(&self) -> bool {
+		self.expiry.as_ref().is_some_and(|timer| timer.finished())
+	}
+}
+
+/// A stack of [`StatModifier`] applied to the `base` value of a stat,
+/// the result is clamped to the stat's global range and written to its
+/// [`StatValue`] by [`update_stat_modifiers`].
+/// For derived stats the `base` is calculated from the stat's formula.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component, MapEntities)]
+#[require(StatValue)]
+pub struct StatModifiers {
+	pub base: f32,
+	/// Kept sorted by priority.
+	modifiers: Vec<StatModifier>,
+}
+
+impl StatModifiers {
+	pub fn new(base: f32) -> Self {
+		Self {
+			base,
+			modifiers: Vec::new(),
+		}
+	}
+
+	pub fn with(mut self, modifier: StatModifier) -> Self {
+		self.push(modifier);
+		self
+	}
+
+	/// Insert a modifier after all others with the same or
+	/// lower priority.
+	pub fn push(&mut self, modifier: StatModifier) {
+		let index = self
+			.modifiers
+			.partition_point(|other| other.priority <= modifier.priority);
+		self.modifiers.insert(index, modifier);
+	}
+
+	/// Remove all modifiers applied by the source entity.
+	pub fn remove_source(&mut self, source: Entity) {
+		self.modifiers
+			.retain(|modifier| modifier.source != Some(source));
+	}
+
+	pub fn clear(&mut self) { self.modifiers.clear(); }
+
+	pub fn iter(&self) -> impl Iterator<Item = &StatModifier> {
+		self.modifiers.iter()
+	}
+
+	pub fn len(&self) -> usize { self.modifiers.len() }
+	pub fn is_empty(&self) -> bool { self.modifiers.is_empty() }
+
+	/// Advance the expiry timers, removing expired modifiers.
+	pub fn tick(&mut self, delta: Duration) {
+		for modifier in self.modifiers.iter_mut() {
+			if let Some(timer) = &mut modifier.expiry {
+				timer.tick(delta);
+			}
+		}
+		self.modifiers.retain(|modifier| !modifier.is_expired());
+	}
+
+	/// Apply each modifier to the base value in order of priority.
+	pub fn evaluate(&self) -> f32 {
+		self.modifiers
+			.iter()
+			.fold(self.base, |value, modifier| modifier.op.apply(value))
+	}
+}
+
+impl MapEntities for StatModifiers {
+	fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+		for modifier in self.modifiers.iter_mut() {
+			modifier.source.map_entities(entity_mapper);
+		}
+	}
+}
+
+/// Tick modifier expiry and update the [`StatValue`] of every stat
+/// with [`StatModifiers`].
+pub fn update_stat_modifiers(
+	time: Res<Time>,
+	stat_map: Res<StatMap>,
+	mut query: Query<(&StatId, &mut StatModifiers, &mut StatValue)>,
+) {
+	for (stat_id, mut modifiers, mut value) in query.iter_mut() {
+		if modifiers.iter().any(|modifier| modifier.expiry.is_some()) {
+			modifiers.tick(time.delta());
+		}
+		let new_value = match stat_map.get(stat_id) {
+			Some(stat) => stat.clamp(modifiers.evaluate()),
+			None => modifiers.evaluate(),
+		};
+		value.set_if_neq(StatValue(new_value));
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use std::time::Duration;
+	use sweet::prelude::*;
+
+	#[test]
+	fn evaluate() {
+		let source = Entity::from_raw(7);
+		let mut modifiers = StatModifiers::new(0.2)
+			.with(StatModifier::multiply(2.))
+			.with(StatModifier::add(0.1).with_source(source));
+		// added after the multiply but applied before it
+		expect(modifiers.evaluate()).to_be_close_to(0.6);
+
+		modifiers.push(StatModifier::set(0.9));
+		expect(modifiers.evaluate()).to_be_close_to(0.9);
+		modifiers.push(StatModifier::multiply(0.5).with_priority(300));
+		expect(modifiers.evaluate()).to_be_close_to(0.45);
+
+		modifiers.remove_source(source);
+		expect(modifiers.len()).to_be(3);
+	}
+
+	#[test]
+	fn system() {
+		let mut app = App::new();
+		app.insert_resource(StatMap::default_with_test_stats())
+			.add_systems(Update, update_stat_modifiers)
+			.insert_time();
+
+		let stat = app
+			.world_mut()
+			.spawn((
+				StatMap::TEST_HEALTH_ID,
+				StatModifiers::new(0.5).with(StatModifier::add(1.)).with(
+					StatModifier::multiply(0.5)
+						.with_duration(Duration::from_secs(2)),
+				),
+			))
+			.id();
+
+		app.update_with_secs(1);
+		// clamped to the global range of 0..1
+		expect(**app.world().get::<StatValue>(stat).unwrap())
+			.to_be_close_to(0.75);
+		app.update_with_secs(2);
+		expect(**app.world().get::<StatValue>(stat).unwrap()).to_be(1.);
+		expect(app.world().get::<StatModifiers>(stat).unwrap().len()).to_be(1);
+	}
+}
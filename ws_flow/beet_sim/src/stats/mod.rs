@@ -13,6 +13,9 @@ pub use self::stat::*;
 pub mod stat_descriptor;
 #[allow(unused_imports)]
 pub use self::stat_descriptor::*;
+pub mod stat_formula;
+#[allow(unused_imports)]
+pub use self::stat_formula::*;
 pub mod stat_id;
 #[allow(unused_imports)]
 pub use self::stat_id::*;
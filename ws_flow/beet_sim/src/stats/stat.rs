@@ -1,5 +1,8 @@
 use crate::prelude::*;
+use beet_flow::prelude::*;
 use bevy::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 use std::fmt;
 use std::ops::Range;
 
@@ -19,7 +22,10 @@ use std::ops::Range;
 	PartialOrd,
 	Deref,
 	DerefMut,
+	Serialize,
+	Deserialize,
 )]
+#[serde(transparent)]
 pub struct StatValue(pub f32);
 impl Into<StatValue> for f32 {
 	fn into(self) -> StatValue { StatValue(self) }
@@ -68,7 +74,13 @@ impl StatValue {
 
 
 pub fn stat_plugin(app: &mut App) {
+	let schedule = TickSchedule::get(app);
 	app.register_type::<StatValue>()
+		.register_type::<StatModifiers>()
+		.add_systems(
+			schedule,
+			(update_derived_stats, update_stat_modifiers).chain(),
+		)
 		.world_mut()
 		.register_component_hooks::<StatValue>()
 		.on_add(|mut world, cx| {
@@ -1,18 +1,28 @@
 use super::StatValue;
+use crate::prelude::*;
 use bevy::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 use std::ops::Range;
 
 
-#[derive(Debug, Clone, PartialEq, Reflect)]
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
 pub struct StatDescriptor {
 	pub name: String,
+	#[serde(default)]
 	pub description: String,
+	#[serde(default)]
 	pub emoji_hexcode: String,
 	/// The absolute range for this stat
 	/// Individual values may have a local subset range
 	pub global_range: Range<StatValue>,
 	/// Unless overridden this is the default value for this stat
 	pub default_value: StatValue,
+	/// When set this is a derived stat, its value is calculated from
+	/// the other stats of the same agent by [`update_derived_stats`].
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[reflect(ignore)]
+	pub formula: Option<StatFormula>,
 }
 
 
@@ -20,4 +30,19 @@ impl StatDescriptor {
 	pub fn total_range(&self) -> StatValue {
 		StatValue(*self.global_range.end - *self.global_range.start)
 	}
+
+	/// Clamp a value to the [`Self::global_range`].
+	/// Unlike [`f32::clamp`] this does not panic for an invalid range,
+	/// which is rejected by [`SimDescriptor::validate`].
+	pub fn clamp(&self, value: f32) -> f32 {
+		value
+			.max(*self.global_range.start)
+			.min(*self.global_range.end)
+	}
+
+	/// Make this a derived stat, calculated by the formula.
+	pub fn with_formula(mut self, formula: StatFormula) -> Self {
+		self.formula = Some(formula);
+		self
+	}
 }
@@ -0,0 +1,287 @@
+use crate::prelude::*;
+use anyhow::Result;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// An expression calculating a derived stat from other stats of the
+/// same agent, referenced by name so formulas can be written in ron:
+/// ```ron
+/// Clamp(
+/// 	value: Sub(Const(1.0), Mul([Stat("Stress"), Const(0.5)])),
+/// 	min: 0.0,
+/// 	max: 1.0,
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StatFormula {
+	Const(f32),
+	/// The value of another stat, if the agent does not have the stat
+	/// its default value is used.
+	Stat(String),
+	Add(Vec<StatFormula>),
+	Mul(Vec<StatFormula>),
+	Sub(Box<StatFormula>, Box<StatFormula>),
+	/// Division by zero evaluates to zero.
+	Div(Box<StatFormula>, Box<StatFormula>),
+	Min(Vec<StatFormula>),
+	Max(Vec<StatFormula>),
+	Clamp {
+		value: Box<StatFormula>,
+		min: f32,
+		max: f32,
+	},
+}
+
+impl StatFormula {
+	pub fn stat(name: impl Into<String>) -> Self { Self::Stat(name.into()) }
+
+	/// Evaluate the formula, getting stat values by name.
+	/// # Errors
+	/// If the stat lookup fails.
+	pub fn evaluate(
+		&self,
+		stat: &mut impl FnMut(&str) -> Result<f32>,
+	) -> Result<f32> {
+		Ok(match self {
+			Self::Const(value) => *value,
+			Self::Stat(name) => stat(name)?,
+			Self::Add(formulas) => Self::evaluate_all(formulas, stat)?.sum(),
+			Self::Mul(formulas) => {
+				Self::evaluate_all(formulas, stat)?.product()
+			}
+			Self::Sub(a, b) => a.evaluate(stat)? - b.evaluate(stat)?,
+			Self::Div(a, b) => {
+				let (a, b) = (a.evaluate(stat)?, b.evaluate(stat)?);
+				if b == 0. { 0. } else { a / b }
+			}
+			Self::Min(formulas) => Self::evaluate_all(formulas, stat)?
+				.fold(f32::INFINITY, f32::min),
+			Self::Max(formulas) => Self::evaluate_all(formulas, stat)?
+				.fold(f32::NEG_INFINITY, f32::max),
+			// unlike f32::clamp this does not panic for invalid bounds
+			Self::Clamp { value, min, max } => {
+				value.evaluate(stat)?.max(*min).min(*max)
+			}
+		})
+	}
+
+	fn evaluate_all(
+		formulas: &[StatFormula],
+		stat: &mut impl FnMut(&str) -> Result<f32>,
+	) -> Result<impl Iterator<Item = f32>> {
+		formulas
+			.iter()
+			.map(|formula| formula.evaluate(stat))
+			.collect::<Result<Vec<_>>>()
+			.map(|values| values.into_iter())
+	}
+
+	/// Check that the bounds of every [`Self::Clamp`] are ordered
+	/// and not NaN.
+	pub fn validate(&self) -> Result<()> {
+		match self {
+			Self::Const(_) | Self::Stat(_) => {}
+			Self::Add(formulas)
+			| Self::Mul(formulas)
+			| Self::Min(formulas)
+			| Self::Max(formulas) => {
+				for formula in formulas {
+					formula.validate()?;
+				}
+			}
+			Self::Sub(a, b) | Self::Div(a, b) => {
+				a.validate()?;
+				b.validate()?;
+			}
+			Self::Clamp { value, min, max } => {
+				if min.is_nan() || max.is_nan() || min > max {
+					anyhow::bail!("invalid clamp bounds: {min}..{max}");
+				}
+				value.validate()?;
+			}
+		}
+		Ok(())
+	}
+
+	/// The names of all stats referenced by this formula.
+	pub fn dependencies(&self) -> Vec<&str> {
+		let mut names = Vec::new();
+		self.collect_dependencies(&mut names);
+		names
+	}
+
+	fn collect_dependencies<'a>(&'a self, names: &mut Vec<&'a str>) {
+		match self {
+			Self::Const(_) => {}
+			Self::Stat(name) => names.push(name),
+			Self::Add(formulas)
+			| Self::Mul(formulas)
+			| Self::Min(formulas)
+			| Self::Max(formulas) => {
+				for formula in formulas {
+					formula.collect_dependencies(names);
+				}
+			}
+			Self::Sub(a, b) | Self::Div(a, b) => {
+				a.collect_dependencies(names);
+				b.collect_dependencies(names);
+			}
+			Self::Clamp { value, .. } => value.collect_dependencies(names),
+		}
+	}
+}
+
+/// Recalculate every stat with a [`StatDescriptor::formula`] from the
+/// sibling stats of the same agent. If the stat has [`StatModifiers`]
+/// the result is used as their base value.
+pub fn update_derived_stats(
+	stat_map: Res<StatMap>,
+	children: Query<&Children>,
+	mut stats: Query<(
+		Entity,
+		&StatId,
+		&ChildOf,
+		&mut StatValue,
+		Option<&mut StatModifiers>,
+	)>,
+) {
+	let mut results = Vec::new();
+	for (entity, stat_id, child_of, ..) in stats.iter() {
+		if !stat_map
+			.get(stat_id)
+			.is_some_and(|stat| stat.formula.is_some())
+		{
+			continue;
+		}
+		let values = children
+			.get(child_of.parent())
+			.into_iter()
+			.flat_map(|siblings| siblings.iter())
+			.filter_map(|sibling| {
+				let (_, id, _, value, _) = stats.get(sibling).ok()?;
+				Some((*id, value.0))
+			})
+			.collect::<HashMap<_, _>>();
+		match stat_map.evaluate_derived(*stat_id, &values) {
+			Ok(value) => results.push((entity, value)),
+			Err(err) => log::error!("failed to derive stat: {err}"),
+		}
+	}
+	for (entity, value) in results {
+		let Ok((_, _, _, mut stat_value, modifiers)) = stats.get_mut(entity)
+		else {
+			continue;
+		};
+		match modifiers {
+			Some(mut modifiers) => {
+				if modifiers.base != value {
+					modifiers.base = value;
+				}
+			}
+			None => {
+				stat_value.set_if_neq(StatValue(value));
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn stat(name: &str, default_value: f32) -> StatDescriptor {
+		StatDescriptor {
+			name: name.to_string(),
+			description: String::new(),
+			emoji_hexcode: String::new(),
+			global_range: StatValue::range(0.0..10.),
+			default_value: StatValue(default_value),
+			formula: None,
+		}
+	}
+
+	#[test]
+	fn evaluate() {
+		let formula = StatFormula::Clamp {
+			value: Box::new(StatFormula::Add(vec![
+				StatFormula::stat("a"),
+				StatFormula::Mul(vec![
+					StatFormula::stat("b"),
+					StatFormula::Const(2.),
+				]),
+			])),
+			min: 0.,
+			max: 5.,
+		};
+		let mut values = |name: &str| match name {
+			"a" => Ok(1.),
+			"b" => Ok(1.5),
+			_ => anyhow::bail!("unknown"),
+		};
+		expect(formula.evaluate(&mut values).unwrap()).to_be(4.);
+		expect(formula.dependencies()).to_be(vec!["a", "b"]);
+		expect(
+			StatFormula::Div(
+				Box::new(StatFormula::Const(1.)),
+				Box::new(StatFormula::Const(0.)),
+			)
+			.evaluate(&mut values)
+			.unwrap(),
+		)
+		.to_be(0.);
+		expect(StatFormula::stat("c").evaluate(&mut values).is_err())
+			.to_be_true();
+	}
+
+	#[test]
+	fn system() {
+		let mut stat_map = StatMap::default();
+		let strength = stat_map.add_stat(stat("Strength", 2.));
+		stat_map.add_stat(stat("Agility", 1.));
+		let power = stat_map.add_stat(stat("Power", 0.).with_formula(
+			StatFormula::Add(vec![
+				StatFormula::stat("Strength"),
+				StatFormula::stat("Agility"),
+			]),
+		));
+		let rage =
+			stat_map.add_stat(stat("Rage", 0.).with_formula(StatFormula::Mul(
+				vec![StatFormula::stat("Power"), StatFormula::Const(2.)],
+			)));
+
+		let mut app = App::new();
+		app.insert_resource(stat_map)
+			.add_systems(
+				Update,
+				(update_derived_stats, update_stat_modifiers).chain(),
+			)
+			.insert_time();
+		let world = app.world_mut();
+		let mut entities = Vec::new();
+		world.spawn_empty().with_children(|parent| {
+			parent.spawn((strength, StatValue(3.)));
+			entities.push(parent.spawn((power, StatValue(0.))).id());
+			entities.push(
+				parent
+					.spawn((
+						rage,
+						StatModifiers::new(0.).with(StatModifier::add(1.)),
+					))
+					.id(),
+			);
+		});
+		app.update();
+
+		// agility is missing so uses its default
+		expect(**app.world().get::<StatValue>(entities[0]).unwrap()).to_be(4.);
+		// derived from another derived stat, then modified
+		expect(**app.world().get::<StatValue>(entities[1]).unwrap()).to_be(9.);
+		expect(app.world().get::<StatModifiers>(entities[1]).unwrap().base)
+			.to_be(8.);
+	}
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use anyhow::Result;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 
@@ -53,6 +54,54 @@ impl StatMap {
 		self.get_by_name(name).map(|(_, desc)| desc.default_value)
 	}
 
+	/// Create a [`SimDescriptor`] with the stats ordered by id.
+	pub fn to_sim_descriptor(&self) -> SimDescriptor {
+		let mut stats = self.map.iter().collect::<Vec<_>>();
+		stats.sort_by_key(|(id, _)| **id);
+		SimDescriptor {
+			stats: stats.into_iter().map(|(_, stat)| stat.clone()).collect(),
+		}
+	}
+
+	/// Calculate the value of a stat from an agent's stat `values`.
+	/// Derived stats are evaluated from their [`StatFormula`], recursing
+	/// into other derived stats, and clamped to their global range.
+	/// Stats missing from `values` use their default value.
+	/// # Errors
+	/// If a formula references an unknown stat or the formulas are cyclic.
+	pub fn evaluate_derived(
+		&self,
+		id: StatId,
+		values: &HashMap<StatId, f32>,
+	) -> Result<f32> {
+		self.evaluate_recursive(id, values, 0)
+	}
+
+	fn evaluate_recursive(
+		&self,
+		id: StatId,
+		values: &HashMap<StatId, f32>,
+		depth: usize,
+	) -> Result<f32> {
+		let Some(stat) = self.map.get(&id) else {
+			anyhow::bail!("StatId not found: {id:?}");
+		};
+		let Some(formula) = &stat.formula else {
+			return Ok(values.get(&id).copied().unwrap_or(*stat.default_value));
+		};
+		// there cannot be a longer chain of formulas without a cycle
+		if depth > self.map.len() {
+			anyhow::bail!("cyclic formula for stat: {}", stat.name);
+		}
+		let value = formula.evaluate(&mut |name| {
+			let id = self.get_id_by_name(name).ok_or_else(|| {
+				anyhow::anyhow!("formula references unknown stat: {name}")
+			})?;
+			self.evaluate_recursive(id, values, depth + 1)
+		})?;
+		Ok(stat.clamp(value))
+	}
+
 	#[cfg(test)]
 	pub fn default_with_test_stats() -> Self {
 		let mut stat_map = StatMap::default();
@@ -62,6 +111,7 @@ impl StatMap {
 			emoji_hexcode: "2764".to_string(),
 			global_range: StatValue::range(0.0..1.),
 			default_value: StatValue(1.),
+			formula: None,
 		});
 		stat_map.add_stat(StatDescriptor {
 			name: "Pleasantness".to_string(),
@@ -69,6 +119,7 @@ impl StatMap {
 			emoji_hexcode: "1F600".to_string(),
 			global_range: StatValue::range(-5.0..5.0),
 			default_value: StatValue(0.),
+			formula: None,
 		});
 		stat_map
 	}
@@ -95,6 +146,7 @@ mod test {
 			emoji_hexcode: "❤️".to_string(),
 			global_range: StatValue::range(0.0..1.),
 			default_value: StatValue(1.),
+			formula: None,
 		};
 
 		sim_descriptor.stats.push(stat.clone());
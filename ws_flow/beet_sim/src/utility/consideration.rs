@@ -0,0 +1,157 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+use bevy::reflect::GetPath;
+use std::ops::Range;
+
+/// Where a [`Consideration`] reads its value from,
+/// all inputs are read from the [`origin`](OnRun::origin) entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum ConsiderationInput {
+	/// A child of the origin with a matching [`StatId`],
+	/// normalized by the [`StatDescriptor::global_range`].
+	Stat(StatId),
+	/// A numeric or boolean field of a reflected component,
+	/// normalized by the range.
+	Component {
+		/// The full type path of the component,
+		/// it must be registered with `#[reflect(Component)]`.
+		type_path: String,
+		/// The reflect path to the field, ie `.0` for a tuple struct,
+		/// or an empty string if the component is itself a number.
+		field_path: String,
+		/// The range of the value, used to normalize it to `0..1`.
+		/// An empty range is a step, `1` at or above the start
+		/// and `0` below it.
+		range: Range<f32>,
+	},
+	/// A fixed value, useful for a baseline option like `Idle`.
+	Constant(f32),
+}
+
+impl ConsiderationInput {
+	/// Read a numeric field from the component with the type path `T`,
+	/// ie `ConsiderationInput::component::<Health>(".0", 0.0..100.)`.
+	pub fn component<T: Component + TypePath>(
+		field_path: impl Into<String>,
+		range: Range<f32>,
+	) -> Self {
+		Self::Component {
+			type_path: T::type_path().to_string(),
+			field_path: field_path.into(),
+			range,
+		}
+	}
+
+	/// Get the normalized value for this input,
+	/// returning `None` if it could not be found.
+	pub fn normalized_value(
+		&self,
+		world: &World,
+		origin: Entity,
+	) -> Option<f32> {
+		match self {
+			Self::Stat(stat_id) => {
+				let children = world.get::<Children>(origin)?;
+				let value = children.iter().find_map(|child| {
+					(world.get::<StatId>(child)? == stat_id)
+						.then(|| world.get::<StatValue>(child))
+						.flatten()
+				})?;
+				let descriptor =
+					world.get_resource::<StatMap>()?.get(stat_id)?;
+				Some(value.normalize(descriptor.global_range.clone()))
+			}
+			Self::Component {
+				type_path,
+				field_path,
+				range,
+			} => {
+				let registry = world.resource::<AppTypeRegistry>().read();
+				let component = registry
+					.get_with_type_path(type_path)?
+					.data::<ReflectComponent>()?
+					.reflect(world.get_entity(origin).ok()?)?;
+				let value = component
+					.reflect_path(field_path.as_str())
+					.ok()
+					.and_then(reflect_to_blackboard_value)?
+					.as_f32()?;
+				let span = range.end - range.start;
+				if span == 0. {
+					Some(if value >= range.start { 1. } else { 0. })
+				} else {
+					Some((value - range.start) / span)
+				}
+			}
+			Self::Constant(value) => Some(*value),
+		}
+	}
+}
+
+/// A single input passed through a [`ResponseCurve`],
+/// several of these are combined by [`Considerations`].
+#[derive(Debug, Clone, Reflect)]
+pub struct Consideration {
+	/// The value to read.
+	pub input: ConsiderationInput,
+	/// The curve mapping the normalized value to a score.
+	pub curve: ResponseCurve,
+	/// The score to use if the input could not be found.
+	pub fallback: f32,
+}
+
+impl Consideration {
+	/// Create a consideration with a linear curve and a fallback of 0.
+	pub fn new(input: ConsiderationInput) -> Self {
+		Self {
+			input,
+			curve: default(),
+			fallback: 0.,
+		}
+	}
+	/// Specify the response curve.
+	pub fn with_curve(mut self, curve: impl Into<ResponseCurve>) -> Self {
+		self.curve = curve.into();
+		self
+	}
+	/// Specify the score to use if the input could not be found.
+	pub fn with_fallback(mut self, fallback: f32) -> Self {
+		self.fallback = fallback;
+		self
+	}
+	/// Read the input and pass it through the curve.
+	pub fn score(&self, world: &World, origin: Entity) -> f32 {
+		self.input
+			.normalized_value(world, origin)
+			.map(|value| self.curve.sample(value))
+			.unwrap_or(self.fallback)
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[derive(Component, Reflect)]
+	#[reflect(Component)]
+	struct Health(f32);
+
+	#[test]
+	fn component() {
+		let mut app = App::new();
+		app.register_type::<Health>();
+		let world = app.world_mut();
+		let origin = world.spawn(Health(25.)).id();
+
+		let input = ConsiderationInput::component::<Health>(".0", 0.0..100.);
+		expect(input.normalized_value(world, origin)).to_be(Some(0.25));
+		let empty = ConsiderationInput::component::<Health>(".0", 25.0..25.);
+		expect(empty.normalized_value(world, origin)).to_be(Some(1.));
+		let empty = ConsiderationInput::component::<Health>(".0", 50.0..50.);
+		expect(empty.normalized_value(world, origin)).to_be(Some(0.));
+	}
+}
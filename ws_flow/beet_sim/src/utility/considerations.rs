@@ -0,0 +1,180 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+
+/// How the scores of several [`Consideration`] are combined.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Aggregation {
+	/// Multiply the scores, so any score of zero will veto the option.
+	#[default]
+	Product,
+	/// The mean of the scores.
+	Average,
+	/// The lowest score.
+	Min,
+}
+
+/// Respond to a [`RequestScore`] by combining the scores of each [`Consideration`].
+/// This can be used as a child of a [`UtilitySelector`] or [`HighestScore`].
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// ## Example
+/// ```
+/// # use beet_flow::prelude::*;
+/// # use beet_sim::prelude::*;
+/// # use bevy::prelude::*;
+/// # let mut app = App::new();
+/// # app.add_plugins(BeetFlowPlugin::default());
+/// # let world = app.world_mut();
+/// world.spawn((
+/// 	Name::new("Eat"),
+/// 	Considerations::new(vec![
+/// 		Consideration::new(ConsiderationInput::Stat(StatId(0))),
+/// 		Consideration::new(ConsiderationInput::Stat(StatId(1)))
+/// 			.with_curve(ResponseCurve::logistic(10.)),
+/// 	]),
+/// ));
+/// ```
+#[action(score_considerations)]
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct Considerations {
+	/// The inputs and curves to score.
+	pub considerations: Vec<Consideration>,
+	/// How the scores are combined.
+	pub aggregation: Aggregation,
+	/// When using [`Aggregation::Product`], adjust each score so that
+	/// options with many considerations are not penalized for it.
+	pub compensate: bool,
+}
+
+impl Default for Considerations {
+	fn default() -> Self { Self::new(Vec::new()) }
+}
+
+impl Considerations {
+	/// Combine the considerations with [`Aggregation::Product`] and compensation.
+	pub fn new(considerations: Vec<Consideration>) -> Self {
+		Self {
+			considerations,
+			aggregation: Aggregation::Product,
+			compensate: true,
+		}
+	}
+	/// Specify the [`Aggregation`].
+	pub fn with_aggregation(mut self, aggregation: Aggregation) -> Self {
+		self.aggregation = aggregation;
+		self
+	}
+	/// Specify whether to apply the compensation factor.
+	pub fn with_compensation(mut self, compensate: bool) -> Self {
+		self.compensate = compensate;
+		self
+	}
+
+	/// Combine the scores, an empty list of scores will fail.
+	pub fn combine(&self, scores: &[f32]) -> ScoreValue {
+		if scores.is_empty() {
+			return ScoreValue::FAIL;
+		}
+		let score = match self.aggregation {
+			Aggregation::Product if self.compensate => {
+				let modification = 1. - 1. / scores.len() as f32;
+				scores
+					.iter()
+					.map(|score| score + (1. - score) * modification * score)
+					.product()
+			}
+			Aggregation::Product => scores.iter().product(),
+			Aggregation::Average => {
+				scores.iter().sum::<f32>() / scores.len() as f32
+			}
+			Aggregation::Min => scores.iter().copied().fold(1., f32::min),
+		};
+		ScoreValue(score)
+	}
+
+	/// Score each consideration for the origin and combine them.
+	pub fn score(&self, world: &World, origin: Entity) -> ScoreValue {
+		let scores = self
+			.considerations
+			.iter()
+			.map(|consideration| consideration.score(world, origin))
+			.collect::<Vec<_>>();
+		self.combine(&scores)
+	}
+}
+
+fn score_considerations(
+	ev: Trigger<OnRun<RequestScore>>,
+	world: &World,
+	mut commands: Commands,
+) {
+	let action = world
+		.get::<Considerations>(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	let score = action.score(world, ev.origin);
+	ev.trigger_result(&mut commands, score);
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn combine() {
+		let scores = [0.5, 0.8];
+		let product = Considerations::default().with_compensation(false);
+		expect(*product.combine(&scores)).to_be_close_to(0.4);
+		let compensated = Considerations::default();
+		// each score is raised towards 1 by half its distance, scaled by itself
+		expect(*compensated.combine(&scores)).to_be_close_to(0.625 * 0.88);
+		let average =
+			Considerations::default().with_aggregation(Aggregation::Average);
+		expect(*average.combine(&scores)).to_be_close_to(0.65);
+		let min = Considerations::default().with_aggregation(Aggregation::Min);
+		expect(*min.combine(&scores)).to_be(0.5);
+		expect(*min.combine(&[])).to_be(0.);
+	}
+
+	#[test]
+	fn scores_stats() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default())
+			.insert_resource(StatMap::default_with_test_stats());
+		let world = app.world_mut();
+		let on_score = observe_triggers::<OnResultAction<ScoreValue>>(world);
+
+		let agent = world
+			.spawn_empty()
+			.with_child((StatMap::TEST_HEALTH_ID, StatValue(0.25)))
+			.id();
+		let action = world
+			.spawn(Considerations::new(vec![
+				Consideration::new(ConsiderationInput::Stat(
+					StatMap::TEST_HEALTH_ID,
+				))
+				.with_curve(ResponseCurve::inverse_linear()),
+				// missing stat
+				Consideration::new(ConsiderationInput::Stat(
+					StatMap::TEST_PLEASENTNESS_ID,
+				))
+				.with_fallback(1.),
+			]))
+			.id();
+		world.flush_trigger(OnRunAction::new(action, agent, RequestScore));
+
+		// 0.75 compensated by half its distance to 1, and 1
+		expect(&on_score).to_have_returned_nth_with(
+			0,
+			&OnResultAction::new(
+				action,
+				agent,
+				ScoreValue(0.75 + 0.25 * 0.5 * 0.75),
+			),
+		);
+	}
+}
@@ -0,0 +1,19 @@
+pub mod consideration;
+#[allow(unused_imports)]
+pub use self::consideration::*;
+pub mod considerations;
+#[allow(unused_imports)]
+pub use self::considerations::*;
+pub mod response_curve;
+#[allow(unused_imports)]
+pub use self::response_curve::*;
+pub mod utility_selector;
+#[allow(unused_imports)]
+pub use self::utility_selector::*;
+use bevy::prelude::*;
+
+/// Registers the [`UtilitySelector`] and [`Considerations`] actions.
+pub fn utility_plugin(app: &mut App) {
+	app.register_type::<Considerations>()
+		.register_type::<UtilitySelector>();
+}
@@ -0,0 +1,96 @@
+use beet_spatial::prelude::*;
+use bevy::prelude::*;
+
+/// Maps a normalized input value to a score, both in the range `0..1`.
+/// The output is always clamped to `0..1`.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Default)]
+pub enum ResponseCurve {
+	/// `slope * x + intercept`
+	Linear { slope: f32, intercept: f32 },
+	/// `a * x^2 + b * x + c`
+	Quadratic { a: f32, b: f32, c: f32 },
+	/// An s-curve centered on the `midpoint`,
+	/// with a negative `steepness` for a descending curve.
+	Logistic { steepness: f32, midpoint: f32 },
+	/// The y component of the curve sampled at the input.
+	Custom(SerdeCurve),
+}
+
+impl Default for ResponseCurve {
+	fn default() -> Self {
+		Self::Linear {
+			slope: 1.,
+			intercept: 0.,
+		}
+	}
+}
+
+impl ResponseCurve {
+	/// Decreases linearly from 1 to 0.
+	pub fn inverse_linear() -> Self {
+		Self::Linear {
+			slope: -1.,
+			intercept: 1.,
+		}
+	}
+	/// A quadratic curve `x^2`, with low values mattering much less than high ones.
+	pub fn quadratic() -> Self {
+		Self::Quadratic {
+			a: 1.,
+			b: 0.,
+			c: 0.,
+		}
+	}
+	/// A logistic curve with the given steepness, centered on 0.5.
+	pub fn logistic(steepness: f32) -> Self {
+		Self::Logistic {
+			steepness,
+			midpoint: 0.5,
+		}
+	}
+
+	/// Sample the curve, the input is clamped to `0..1`.
+	pub fn sample(&self, x: f32) -> f32 {
+		let x = x.clamp(0., 1.);
+		let y = match self {
+			Self::Linear { slope, intercept } => slope * x + intercept,
+			Self::Quadratic { a, b, c } => a * x * x + b * x + c,
+			Self::Logistic {
+				steepness,
+				midpoint,
+			} => 1. / (1. + (-steepness * (x - midpoint)).exp()),
+			Self::Custom(curve) => curve.sample_unchecked(x).y,
+		};
+		y.clamp(0., 1.)
+	}
+}
+
+impl From<SerdeCurve> for ResponseCurve {
+	fn from(curve: SerdeCurve) -> Self { Self::Custom(curve) }
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_spatial::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		expect(ResponseCurve::default().sample(0.3)).to_be(0.3);
+		expect(ResponseCurve::default().sample(2.)).to_be(1.);
+		expect(ResponseCurve::inverse_linear().sample(0.25)).to_be(0.75);
+		expect(ResponseCurve::quadratic().sample(0.5)).to_be(0.25);
+		expect(ResponseCurve::logistic(10.).sample(0.5)).to_be(0.5);
+		expect(ResponseCurve::logistic(10.).sample(1.))
+			.to_be_greater_than(0.99);
+		expect(ResponseCurve::logistic(-10.).sample(1.)).to_be_less_than(0.01);
+
+		let custom = ResponseCurve::from(SerdeCurve::EaseVec3(
+			EasingCurve::new(Vec3::ZERO, Vec3::ONE, EaseFunction::Linear),
+		));
+		expect(custom.sample(0.4)).to_be_close_to(0.4);
+	}
+}
@@ -0,0 +1,185 @@
+use beet_flow::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::cmp::Ordering;
+
+/// Like [`HighestScore`], runs the child with the highest score,
+/// but the child that was last selected for the [`origin`](OnRun::origin)
+/// gets a bonus of [`Self::hysteresis`]. This stops the selection from
+/// flapping between options with similar scores when the selector
+/// is run every tick.
+///
+/// Every child must respond to [`RequestScore`], usually with [`Considerations`].
+/// ## Tags
+/// - [ControlFlow](ActionTag::ControlFlow)
+/// ## Example
+/// ```
+/// # use beet_flow::prelude::*;
+/// # use beet_sim::prelude::*;
+/// # use bevy::prelude::*;
+/// # let mut app = App::new();
+/// # app.add_plugins(BeetFlowPlugin::default());
+/// # let world = app.world_mut();
+/// world
+/// 	.spawn((
+/// 		Repeat::default(),
+/// 		UtilitySelector::new(0.1),
+/// 	))
+/// 	.with_child((
+/// 		Name::new("Idle"),
+/// 		Considerations::new(vec![
+/// 			Consideration::new(ConsiderationInput::Constant(0.2)),
+/// 		]),
+/// 		ReturnWith(RunResult::Success),
+/// 	))
+/// 	.with_child((
+/// 		Name::new("Eat"),
+/// 		Considerations::new(vec![
+/// 			Consideration::new(ConsiderationInput::Stat(StatId(0))),
+/// 		]),
+/// 		ReturnWith(RunResult::Success),
+/// 	));
+/// ```
+#[action(utility_on_run, utility_on_score)]
+#[derive(Debug, Default, Clone, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(BubbleResult)]
+pub struct UtilitySelector {
+	/// The bonus added to the score of the previously selected child,
+	/// another child must beat it by more than this amount to be selected.
+	pub hysteresis: f32,
+	/// The scores received for the current run.
+	#[reflect(ignore)]
+	scores: HashMap<Entity, ScoreValue>,
+	/// The last selected child for each origin.
+	#[reflect(ignore)]
+	selected: HashMap<Entity, Entity>,
+}
+
+impl UtilitySelector {
+	/// Create a new selector with the given hysteresis.
+	pub fn new(hysteresis: f32) -> Self {
+		Self {
+			hysteresis,
+			..default()
+		}
+	}
+	/// The last child selected for the given origin.
+	pub fn selected(&self, origin: Entity) -> Option<Entity> {
+		self.selected.get(&origin).copied()
+	}
+	/// Clear the selection for the given origin, ie when it is despawned.
+	pub fn clear_selected(&mut self, origin: Entity) {
+		self.selected.remove(&origin);
+	}
+
+	/// The child with the highest score, including the hysteresis bonus.
+	fn best(&self, origin: Entity) -> Option<Entity> {
+		let prev = self.selected(origin);
+		self.scores
+			.iter()
+			.map(|(child, score)| {
+				let bonus = if Some(*child) == prev {
+					self.hysteresis
+				} else {
+					0.
+				};
+				(*child, score.0 + bonus)
+			})
+			.max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+			.map(|(child, _)| child)
+	}
+}
+
+fn utility_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	mut query: Query<(&mut UtilitySelector, Option<&Children>)>,
+) {
+	let (mut action, children) = query
+		.get_mut(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	action.scores.clear();
+	let Some(children) = children.filter(|children| !children.is_empty())
+	else {
+		ev.trigger_result(&mut commands, RunResult::Failure);
+		return;
+	};
+	for child in children.iter() {
+		commands.trigger(OnRunAction::new(child, ev.origin, RequestScore));
+	}
+}
+
+fn utility_on_score(
+	ev: Trigger<OnChildResult<ScoreValue>>,
+	mut commands: Commands,
+	mut query: Query<(&mut UtilitySelector, &Children)>,
+) {
+	let (mut action, children) = query
+		.get_mut(ev.parent)
+		.expect(&expect_action::to_have_action(&ev));
+
+	action.scores.insert(ev.child, ev.payload);
+	if action.scores.len() < children.len() {
+		return;
+	}
+	let best = action
+		.best(ev.origin)
+		.expect(&expect_action::to_have_children(&ev));
+	action.selected.insert(ev.origin, best);
+	commands.trigger(OnRunAction::new(best, ev.origin, ()));
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[derive(Component, Reflect)]
+	#[reflect(Component)]
+	struct Hunger(f32);
+
+	fn option(input: ConsiderationInput) -> impl Bundle {
+		(
+			Considerations::new(vec![Consideration::new(input)]),
+			ReturnWith(RunResult::Success),
+		)
+	}
+
+	#[test]
+	fn hysteresis() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default())
+			.register_type::<Hunger>();
+		let world = app.world_mut();
+
+		let agent = world.spawn(Hunger(0.4)).id();
+		let action = world.spawn(UtilitySelector::new(0.1)).id();
+		let idle = world
+			.spawn((ChildOf(action), option(ConsiderationInput::Constant(0.5))))
+			.id();
+		let eat = world
+			.spawn((
+				ChildOf(action),
+				option(ConsiderationInput::component::<Hunger>(".0", 0.0..1.)),
+			))
+			.id();
+		let run = |world: &mut World, hunger: f32| {
+			world.get_mut::<Hunger>(agent).unwrap().0 = hunger;
+			world.flush_trigger(OnRunAction::new(action, agent, ()));
+			world
+				.get::<UtilitySelector>(action)
+				.unwrap()
+				.selected(agent)
+		};
+
+		expect(run(world, 0.4)).to_be(Some(idle));
+		// within the hysteresis margin
+		expect(run(world, 0.55)).to_be(Some(idle));
+		expect(run(world, 0.65)).to_be(Some(eat));
+		expect(run(world, 0.45)).to_be(Some(eat));
+		expect(run(world, 0.35)).to_be(Some(idle));
+	}
+}
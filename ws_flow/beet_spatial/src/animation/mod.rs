@@ -23,12 +23,13 @@ pub struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
 	fn build(&self, app: &mut App) {
+		let schedule = TickSchedule::get(app);
 		app.add_systems(
-			Update,
+			schedule,
 			(init_animators, run_on_animation_ready::<()>).chain(),
 		)
 		.add_systems(
-			Update,
+			schedule,
 			(
 				// play_animation_on_load,
 				return_on_animation_end::<RunResult>,
@@ -11,7 +11,7 @@ pub fn insert_on_asset_event_plugin<T: Component + Clone, A: Asset>(
 	app: &mut App,
 ) {
 	app.add_systems(
-		Update,
+		TickSchedule::get(app),
 		(
 			insert_on_asset_status::<T, A>,
 			insert_on_asset_event::<T, A>,
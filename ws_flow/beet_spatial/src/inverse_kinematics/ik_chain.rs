@@ -0,0 +1,285 @@
+use bevy::prelude::*;
+
+/// The algorithm used to solve an [`IkChain`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+pub enum IkSolver {
+	/// [Forward And Backward Reaching Inverse Kinematics](http://www.andreasaristidou.com/FABRIK.html),
+	/// fast to converge and produces natural poses.
+	#[default]
+	Fabrik,
+	/// [Cyclic Coordinate Descent](https://en.wikipedia.org/wiki/Inverse_kinematics#Heuristic_methods),
+	/// tends to curl toward the end effector.
+	Ccd,
+}
+
+/// Limit the angle a joint can bend relative to its parent segment,
+/// in radians. Add this to joints in a chain solved by [`ReachTarget`](crate::prelude::ReachTarget).
+/// The limit of the first joint in a chain is ignored
+/// as it has no parent segment.
+#[derive(
+	Debug, Copy, Clone, PartialEq, Deref, DerefMut, Component, Reflect,
+)]
+#[reflect(Component, Default)]
+pub struct IkJointLimit(pub f32);
+
+impl Default for IkJointLimit {
+	fn default() -> Self { Self(std::f32::consts::FRAC_PI_2) }
+}
+
+/// A chain of joint positions of arbitrary length, from the root
+/// to the end effector. Segment lengths are preserved while solving.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// let mut chain = IkChain::new(vec![Vec3::ZERO, Vec3::Y, Vec3::Y * 2.]);
+/// let error = chain.solve(IkSolver::Fabrik, Vec3::new(1., 1., 0.), None, 0.001, 10);
+/// assert!(error < 0.001);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IkChain {
+	/// The world position of each joint.
+	pub positions: Vec<Vec3>,
+	/// The [`IkJointLimit`] of each joint, if any.
+	pub limits: Vec<Option<f32>>,
+	lengths: Vec<f32>,
+}
+
+impl IkChain {
+	/// Create a new chain without joint limits.
+	pub fn new(positions: Vec<Vec3>) -> Self {
+		let limits = vec![None; positions.len()];
+		Self::new_with_limits(positions, limits)
+	}
+	/// Create a new chain with a limit for each joint.
+	pub fn new_with_limits(
+		positions: Vec<Vec3>,
+		limits: Vec<Option<f32>>,
+	) -> Self {
+		let lengths = positions
+			.windows(2)
+			.map(|pair| pair[0].distance(pair[1]))
+			.collect();
+		Self {
+			positions,
+			limits,
+			lengths,
+		}
+	}
+
+	/// The position of the last joint.
+	pub fn end_effector(&self) -> Vec3 {
+		self.positions.last().copied().unwrap_or_default()
+	}
+
+	/// Solve the chain with the given algorithm, bending the middle joints
+	/// toward the pole if provided. Returns the remaining distance between
+	/// the end effector and the target.
+	pub fn solve(
+		&mut self,
+		solver: IkSolver,
+		target: Vec3,
+		pole: Option<Vec3>,
+		tolerance: f32,
+		max_iterations: usize,
+	) -> f32 {
+		if self.positions.len() < 2 {
+			return self.end_effector().distance(target);
+		}
+		let root = self.positions[0];
+		if root.distance(target) >= self.lengths.iter().sum::<f32>() {
+			// out of reach, stretch toward the target
+			let dir = (target - root).normalize_or_zero();
+			for i in 0..self.lengths.len() {
+				self.positions[i + 1] =
+					self.positions[i] + dir * self.lengths[i];
+			}
+			return self.end_effector().distance(target);
+		}
+		for _ in 0..max_iterations {
+			match solver {
+				IkSolver::Fabrik => self.fabrik_iteration(target),
+				IkSolver::Ccd => self.ccd_iteration(target),
+			}
+			if let Some(pole) = pole {
+				self.apply_pole(pole);
+			}
+			if self.end_effector().distance(target) <= tolerance {
+				break;
+			}
+		}
+		self.end_effector().distance(target)
+	}
+
+	/// A single backward and forward pass.
+	fn fabrik_iteration(&mut self, target: Vec3) {
+		let root = self.positions[0];
+		let last = self.positions.len() - 1;
+		// backward, from the end effector to the root
+		self.positions[last] = target;
+		for i in (0..last).rev() {
+			let dir =
+				(self.positions[i] - self.positions[i + 1]).normalize_or_zero();
+			self.positions[i] = self.positions[i + 1] + dir * self.lengths[i];
+		}
+		// forward, from the root to the end effector
+		self.positions[0] = root;
+		for i in 0..last {
+			let dir =
+				(self.positions[i + 1] - self.positions[i]).normalize_or_zero();
+			let dir = self.constrain(i, dir);
+			self.positions[i + 1] = self.positions[i] + dir * self.lengths[i];
+		}
+	}
+
+	/// Rotate each joint in turn, from the end effector to the root,
+	/// so that the end effector points at the target.
+	fn ccd_iteration(&mut self, target: Vec3) {
+		let last = self.positions.len() - 1;
+		for i in (0..last).rev() {
+			let pivot = self.positions[i];
+			let to_end = (self.positions[last] - pivot).normalize_or_zero();
+			let to_target = (target - pivot).normalize_or_zero();
+			if to_end == Vec3::ZERO || to_target == Vec3::ZERO {
+				continue;
+			}
+			self.rotate_from(i, Quat::from_rotation_arc(to_end, to_target));
+
+			let dir = (self.positions[i + 1] - pivot).normalize_or_zero();
+			let constrained = self.constrain(i, dir);
+			if constrained != dir {
+				self.rotate_from(i, Quat::from_rotation_arc(dir, constrained));
+			}
+		}
+	}
+
+	/// Rotate every joint after `index` around it.
+	fn rotate_from(&mut self, index: usize, rotation: Quat) {
+		let pivot = self.positions[index];
+		for position in self.positions.iter_mut().skip(index + 1) {
+			*position = pivot + rotation * (*position - pivot);
+		}
+	}
+
+	/// Clamp the direction of the segment starting at `index`
+	/// to be within the joint limit of its parent segment.
+	fn constrain(&self, index: usize, dir: Vec3) -> Vec3 {
+		let Some(max_angle) = self.limits.get(index).copied().flatten() else {
+			return dir;
+		};
+		if index == 0 || dir == Vec3::ZERO {
+			return dir;
+		}
+		let parent = (self.positions[index] - self.positions[index - 1])
+			.normalize_or_zero();
+		if parent == Vec3::ZERO || parent.angle_between(dir) <= max_angle {
+			return dir;
+		}
+		let axis = parent
+			.cross(dir)
+			.normalize_or(parent.any_orthonormal_vector());
+		Quat::from_axis_angle(axis, max_angle) * parent
+	}
+
+	/// Rotate each middle joint around the line between its neighbors
+	/// so that it points toward the pole, this preserves segment lengths.
+	fn apply_pole(&mut self, pole: Vec3) {
+		for i in 1..self.positions.len() - 1 {
+			let start = self.positions[i - 1];
+			let axis = (self.positions[i + 1] - start).normalize_or_zero();
+			if axis == Vec3::ZERO {
+				continue;
+			}
+			let project = |v: Vec3| v - axis * v.dot(axis);
+			let joint = project(self.positions[i] - start);
+			let pole = project(pole - start);
+			if joint.length_squared() < f32::EPSILON
+				|| pole.length_squared() < f32::EPSILON
+			{
+				continue;
+			}
+			let angle = joint.angle_between(pole)
+				* axis.dot(joint.cross(pole)).signum();
+			self.positions[i] = start
+				+ Quat::from_axis_angle(axis, angle)
+					* (self.positions[i] - start);
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn chain() -> IkChain {
+		IkChain::new(vec![Vec3::ZERO, Vec3::Y, Vec3::Y * 2., Vec3::Y * 3.])
+	}
+
+	fn expect_lengths(chain: &IkChain) {
+		for pair in chain.positions.windows(2) {
+			expect(pair[0].distance(pair[1])).to_be_close_to(1.);
+		}
+	}
+
+	#[test]
+	fn fabrik() {
+		let mut chain = chain();
+		let target = Vec3::new(1., 1.5, 0.5);
+		let error = chain.solve(IkSolver::Fabrik, target, None, 0.001, 20);
+		expect(error).to_be_less_than(0.001);
+		expect(chain.positions[0]).to_be(Vec3::ZERO);
+		expect_lengths(&chain);
+	}
+
+	#[test]
+	fn ccd() {
+		let mut chain = chain();
+		let target = Vec3::new(1., 1.5, 0.5);
+		let error = chain.solve(IkSolver::Ccd, target, None, 0.001, 50);
+		expect(error).to_be_less_than(0.001);
+		expect_lengths(&chain);
+	}
+
+	#[test]
+	fn unreachable() {
+		let mut chain = chain();
+		let error = chain.solve(
+			IkSolver::Fabrik,
+			Vec3::new(10., 0., 0.),
+			None,
+			0.001,
+			10,
+		);
+		expect(error).to_be_close_to(7.);
+		expect(chain.end_effector()).to_be_close_to(Vec3::new(3., 0., 0.));
+	}
+
+	#[test]
+	fn limits() {
+		let mut chain = IkChain::new_with_limits(
+			vec![Vec3::ZERO, Vec3::Y, Vec3::Y * 2.],
+			vec![None, Some(0.1), None],
+		);
+		chain.solve(IkSolver::Fabrik, Vec3::new(1., 0., 0.), None, 0.001, 10);
+		let parent = chain.positions[1] - chain.positions[0];
+		let child = chain.positions[2] - chain.positions[1];
+		expect(parent.angle_between(child)).to_be_less_than(0.1 + 0.001);
+	}
+
+	#[test]
+	fn pole() {
+		let mut chain = IkChain::new(vec![Vec3::ZERO, Vec3::Y, Vec3::Y * 2.]);
+		let pole = Vec3::new(0., 1., 5.);
+		chain.solve(
+			IkSolver::Fabrik,
+			Vec3::new(1., 1., 0.),
+			Some(pole),
+			0.001,
+			10,
+		);
+		// the elbow bends toward the pole
+		expect(chain.positions[1].z).to_be_greater_than(0.5);
+	}
+}
@@ -1,12 +1,17 @@
 use crate::prelude::*;
+use beet_flow::prelude::*;
 use bevy::prelude::*;
 
 /// Add the update methods for IK.
 pub fn ik_plugin(app: &mut App) {
+	let schedule = TickSchedule::get(app);
 	app /*-*/
-		.add_systems(Update, update_ik_arm_transforms)
+		.add_systems(schedule, update_ik_arm_transforms)
+		.add_systems(schedule, reach_target.in_set(TickSet))
 		// .add_systems(Update, ik_2dof_transforms_test)
 		.register_type::<IkArm4DofTransforms>()
+		.register_type::<IkJointLimit>()
+		.register_type::<ReachTarget>()
 		/*-*/;
 
 	#[cfg(feature = "bevy_default")]
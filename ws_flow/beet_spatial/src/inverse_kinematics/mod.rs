@@ -1,14 +1,20 @@
 //! Various functions and structs for inverse kinematics.
 //! These are rendering independent so can be used in robotics.
 //!
+//! For arbitrary joint hierarchies use [`ReachTarget`] which solves
+//! an [`IkChain`] of any length.
 mod ik_arm_4dof;
 pub use self::ik_arm_4dof::*;
 mod ik_arm_4dof_transforms;
 pub use self::ik_arm_4dof_transforms::*;
+mod ik_chain;
+pub use self::ik_chain::*;
 mod ik_plugin;
 pub use self::ik_plugin::*;
 mod ik_segment;
 pub use self::ik_segment::*;
+mod reach_target;
+pub use self::reach_target::*;
 #[cfg(feature = "bevy_default")]
 mod ik_spawner;
 #[cfg(feature = "bevy_default")]
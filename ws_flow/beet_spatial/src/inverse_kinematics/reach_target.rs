@@ -0,0 +1,335 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::ecs::entity::MapEntities;
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::prelude::*;
+
+/// Rotate the joints of a hierarchy so that the end effector reaches
+/// the target, using an [`IkChain`]. The chain includes every entity from the
+/// `root` to the `end_effector`, which must be a descendant of it.
+/// Joints may have an [`IkJointLimit`].
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// - [MutateWorld](ActionTag::MutateWorld)
+/// ## Logic
+/// - Succeeds when the end effector is within [`Self::tolerance`] of the target.
+/// - Fails if the chain is invalid or the target is not found.
+/// - Fails if the distance has not improved for [`Self::max_stalled_ticks`],
+/// 	ie the target is out of reach or blocked by joint limits.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// # let mut world = world();
+/// let root = world.spawn(Transform::default()).id();
+/// let elbow = world.spawn((ChildOf(root), Transform::from_xyz(0., 1., 0.))).id();
+/// let hand = world.spawn((ChildOf(elbow), Transform::from_xyz(0., 1., 0.))).id();
+/// world
+/// 	.spawn(ReachTarget::new(Vec3::new(1., 1., 0.), root, hand))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(reach_target_on_run)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component, MapEntities)]
+#[require(ContinueRun)]
+pub struct ReachTarget {
+	/// The position for the end effector to reach.
+	pub target: SteerTarget,
+	/// The first joint of the chain, this will not be moved.
+	pub root: Entity,
+	/// The last joint of the chain.
+	pub end_effector: Entity,
+	/// Middle joints will bend toward this target, ie knees and elbows.
+	pub pole: Option<SteerTarget>,
+	/// The algorithm used to solve the chain.
+	pub solver: IkSolver,
+	/// The distance at which the end effector has reached the target,
+	/// defaults to `0.01`.
+	pub tolerance: f32,
+	/// The maximum solver iterations per tick, defaults to `10`.
+	pub max_iterations: usize,
+	/// Fail after this many ticks without the distance improving by at
+	/// least a hundredth of the [`Self::tolerance`], defaults to `10`.
+	pub max_stalled_ticks: usize,
+	/// The smallest distance to the target in the current run.
+	#[reflect(ignore)]
+	best_error: f32,
+	/// The number of ticks since the distance last improved.
+	#[reflect(ignore)]
+	stalled_ticks: usize,
+}
+
+impl ReachTarget {
+	/// Reach the target with the chain from `root` to `end_effector`.
+	pub fn new(
+		target: impl Into<SteerTarget>,
+		root: Entity,
+		end_effector: Entity,
+	) -> Self {
+		Self {
+			target: target.into(),
+			root,
+			end_effector,
+			pole: None,
+			solver: IkSolver::default(),
+			tolerance: 0.01,
+			max_iterations: 10,
+			max_stalled_ticks: 10,
+			best_error: f32::INFINITY,
+			stalled_ticks: 0,
+		}
+	}
+	/// Bend the middle joints toward this target.
+	pub fn with_pole(mut self, pole: impl Into<SteerTarget>) -> Self {
+		self.pole = Some(pole.into());
+		self
+	}
+	/// Specify the solver algorithm.
+	pub fn with_solver(mut self, solver: IkSolver) -> Self {
+		self.solver = solver;
+		self
+	}
+	/// Specify the distance at which the target has been reached.
+	pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+		self.tolerance = tolerance;
+		self
+	}
+	/// Specify the number of ticks without improvement before failing.
+	pub fn with_max_stalled_ticks(mut self, ticks: usize) -> Self {
+		self.max_stalled_ticks = ticks;
+		self
+	}
+
+	/// Every joint from the root to the end effector,
+	/// or `None` if the end effector is not a descendant of the root.
+	pub fn joints(&self, parents: &Query<&ChildOf>) -> Option<Vec<Entity>> {
+		let mut joints = vec![self.end_effector];
+		if self.end_effector != self.root {
+			for ancestor in parents.iter_ancestors(self.end_effector) {
+				joints.push(ancestor);
+				if ancestor == self.root {
+					break;
+				}
+			}
+		}
+		if joints.last() != Some(&self.root) {
+			return None;
+		}
+		joints.reverse();
+		Some(joints)
+	}
+}
+
+impl MapEntities for ReachTarget {
+	fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+		self.target.map_entities(entity_mapper);
+		self.root = entity_mapper.get_mapped(self.root);
+		self.end_effector = entity_mapper.get_mapped(self.end_effector);
+		if let Some(pole) = self.pole.as_mut() {
+			pole.map_entities(entity_mapper);
+		}
+	}
+}
+
+fn reach_target_on_run(ev: Trigger<OnRun>, mut query: Query<&mut ReachTarget>) {
+	let mut reach = query
+		.get_mut(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	reach.best_error = f32::INFINITY;
+	reach.stalled_ticks = 0;
+}
+
+pub(crate) fn reach_target(
+	mut commands: Commands,
+	parents: Query<&ChildOf>,
+	globals: Query<&GlobalTransform>,
+	limits: Query<&IkJointLimit>,
+	mut transforms: Query<&mut Transform>,
+	mut query: Populated<(Entity, &Running, &mut ReachTarget)>,
+) {
+	for (action, running, mut reach) in query.iter_mut() {
+		let Some(joints) = reach.joints(&parents) else {
+			log::warn!("ReachTarget: end effector is not a descendant of root");
+			running.trigger_result(&mut commands, action, RunResult::Failure);
+			continue;
+		};
+		let Ok(target) = reach.target.get_position(&globals) else {
+			running.trigger_result(&mut commands, action, RunResult::Failure);
+			continue;
+		};
+		let pole = reach.pole.and_then(|pole| pole.get_position(&globals).ok());
+		let Ok(poses) = joints
+			.iter()
+			.map(|joint| {
+				globals.get(*joint).map(|pose| pose.compute_transform())
+			})
+			.collect::<Result<Vec<_>, _>>()
+		else {
+			running.trigger_result(&mut commands, action, RunResult::Failure);
+			continue;
+		};
+
+		let mut chain = IkChain::new_with_limits(
+			poses.iter().map(|pose| pose.translation).collect(),
+			joints
+				.iter()
+				.map(|joint| limits.get(*joint).ok().map(|limit| **limit))
+				.collect(),
+		);
+		let error = chain.solve(
+			reach.solver,
+			target,
+			pole,
+			reach.tolerance,
+			reach.max_iterations,
+		);
+
+		// rotate each joint so its segment points at the next solved position,
+		// the parent of the root is not moved
+		let root_local = transforms
+			.get(joints[0])
+			.map(|transform| transform.rotation)
+			.unwrap_or_default();
+		let mut parent_rotation = poses[0].rotation * root_local.inverse();
+		for i in 0..joints.len() - 1 {
+			let old_dir = (poses[i + 1].translation - poses[i].translation)
+				.normalize_or_zero();
+			let new_dir = (chain.positions[i + 1] - chain.positions[i])
+				.normalize_or_zero();
+			let rotation = if old_dir == Vec3::ZERO || new_dir == Vec3::ZERO {
+				poses[i].rotation
+			} else {
+				Quat::from_rotation_arc(old_dir, new_dir) * poses[i].rotation
+			};
+			if let Ok(mut transform) = transforms.get_mut(joints[i]) {
+				transform.rotation = parent_rotation.inverse() * rotation;
+			}
+			parent_rotation = rotation;
+		}
+
+		if error <= reach.tolerance {
+			running.trigger_result(&mut commands, action, RunResult::Success);
+		} else if error < reach.best_error - reach.tolerance * 0.01 {
+			reach.best_error = error;
+			reach.stalled_ticks = 0;
+		} else {
+			reach.stalled_ticks += 1;
+			if reach.stalled_ticks >= reach.max_stalled_ticks {
+				running.trigger_result(
+					&mut commands,
+					action,
+					RunResult::Failure,
+				);
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut app = App::new();
+		app.add_plugins((
+			TransformPlugin,
+			BeetFlowPlugin::default(),
+			BeetSpatialPlugins,
+		));
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let root = world.spawn(Transform::default()).id();
+		let elbow = world
+			.spawn((ChildOf(root), Transform::from_xyz(0., 1., 0.)))
+			.id();
+		let wrist = world
+			.spawn((ChildOf(elbow), Transform::from_xyz(0., 1., 0.)))
+			.id();
+		let hand = world
+			.spawn((ChildOf(wrist), Transform::from_xyz(0., 1., 0.)))
+			.id();
+		// propagate transforms
+		app.update();
+
+		let target = Vec3::new(1., 1.5, 0.5);
+		app.world_mut()
+			.spawn((
+				Name::new("reach"),
+				ReachTarget::new(target, root, hand)
+					.with_solver(IkSolver::Ccd)
+					.with_tolerance(0.01),
+			))
+			.flush_trigger(OnRun::local());
+		for _ in 0..10 {
+			app.update();
+		}
+
+		expect(on_result())
+			.to_be(vec![("reach".to_string(), RunResult::Success)]);
+		let hand = app.world().get::<GlobalTransform>(hand).unwrap();
+		expect(hand.translation()).to_be_close_to(target);
+	}
+
+	#[test]
+	fn fails_when_unreachable() {
+		let mut app = App::new();
+		app.add_plugins((
+			TransformPlugin,
+			BeetFlowPlugin::default(),
+			BeetSpatialPlugins,
+		));
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let root = world.spawn(Transform::default()).id();
+		let elbow = world
+			.spawn((ChildOf(root), Transform::from_xyz(0., 1., 0.)))
+			.id();
+		let hand = world
+			.spawn((ChildOf(elbow), Transform::from_xyz(0., 1., 0.)))
+			.id();
+		app.update();
+
+		app.world_mut()
+			.spawn((
+				Name::new("reach"),
+				ReachTarget::new(Vec3::new(10., 0., 0.), root, hand)
+					.with_max_stalled_ticks(5),
+			))
+			.flush_trigger(OnRun::local());
+		for _ in 0..3 {
+			app.update();
+		}
+		expect(on_result()).to_be(vec![]);
+		for _ in 0..10 {
+			app.update();
+		}
+		expect(on_result())
+			.to_be(vec![("reach".to_string(), RunResult::Failure)]);
+		// the arm still points at the target
+		let hand = app.world().get::<GlobalTransform>(hand).unwrap();
+		expect(hand.translation()).to_be_close_to(Vec3::new(2., 0., 0.));
+	}
+
+	#[test]
+	fn fails_on_invalid_chain() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), BeetSpatialPlugins));
+		let world = app.world_mut();
+		let on_result = collect_on_result(world);
+
+		let root = world.spawn(Transform::default()).id();
+		let hand = world.spawn(Transform::default()).id();
+		world
+			.spawn((Name::new("reach"), ReachTarget::new(Vec3::X, root, hand)))
+			.flush_trigger(OnRun::local());
+		app.update();
+		expect(on_result())
+			.to_be(vec![("reach".to_string(), RunResult::Failure)]);
+	}
+}
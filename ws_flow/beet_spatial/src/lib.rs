@@ -9,6 +9,7 @@ pub mod asset_actions;
 mod extensions;
 pub mod inverse_kinematics;
 pub mod movement;
+pub mod navigation;
 pub mod procedural_animation;
 pub mod robotics;
 pub mod steer;
@@ -28,6 +29,7 @@ pub mod prelude {
 	pub use crate::extensions::*;
 	pub use crate::inverse_kinematics::*;
 	pub use crate::movement::*;
+	pub use crate::navigation::*;
 	pub use crate::procedural_animation::*;
 	pub use crate::robotics::*;
 	pub use crate::steer::*;
@@ -68,6 +70,7 @@ impl PluginGroup for BeetSpatialPlugins {
 		#[allow(unused_mut)]
 		let mut builder = PluginGroupBuilder::start::<Self>()
 		.add(movement_plugin)
+		.add(navigation_plugin)
 		.add(procedural_animation_plugin)
 		.add(steer_plugin)
 		.add(ik_plugin)
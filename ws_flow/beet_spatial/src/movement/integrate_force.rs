@@ -1,60 +1,78 @@
 use crate::prelude::*;
+use bevy::ecs::query::QueryData;
 use bevy::prelude::*;
 use sweet::prelude::When;
 
 
-/// Implementation of position, velocity, force integration
-/// as described by Daniel Shiffman
-/// https://natureofcode.com/vectors/#acceleration
-pub fn integrate_force(
-	time: When<Res<Time>>,
-	mut query: Populated<(
-		&mut Transform,
-		&mut Velocity,
-		Option<&Mass>,
-		Option<&VelocityScalar>,
-		Option<&mut Force>,
-		Option<&mut Impulse>,
-		Option<&mut MaxForce>,
-		Option<&mut MaxSpeed>,
-	)>,
-) {
-	for (
-		mut transform,
-		mut velocity,
-		mass,
-		scalar,
-		mut force,
-		mut impulse,
-		max_force,
-		max_velocity,
-	) in query.iter_mut()
-	{
+/// The components used to integrate [`Force`] and [`Impulse`] into [`Velocity`].
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct ForceQuery {
+	/// The velocity to integrate into.
+	pub velocity: &'static mut Velocity,
+	/// Scales the effect of forces, defaults to `1.0`.
+	pub mass: Option<&'static Mass>,
+	/// Applied to the velocity every frame.
+	pub scalar: Option<&'static VelocityScalar>,
+	/// Cleared after integration.
+	pub force: Option<&'static mut Force>,
+	/// Cleared after integration.
+	pub impulse: Option<&'static mut Impulse>,
+	/// Clamps the summed force.
+	pub max_force: Option<&'static MaxForce>,
+	/// Clamps the velocity.
+	pub max_speed: Option<&'static MaxSpeed>,
+}
+
+impl ForceQueryItem<'_> {
+	/// Sum and clear the [`Force`] and [`Impulse`], applying them
+	/// to the [`Velocity`].
+	pub fn integrate(&mut self, delta_secs: f32) {
 		let mut summed_force = Vec3::ZERO;
-		if let Some(force) = force.as_mut() {
-			summed_force += ***force * time.delta_secs();
+		if let Some(force) = self.force.as_mut() {
+			summed_force += ***force * delta_secs;
 			***force = Vec3::ZERO;
 		}
-		if let Some(impulse) = impulse.as_mut() {
+		if let Some(impulse) = self.impulse.as_mut() {
 			summed_force += ***impulse;
 			***impulse = Vec3::ZERO;
 		}
 		if summed_force != Vec3::ZERO {
-			if let Some(max_force) = max_force {
+			if let Some(max_force) = self.max_force {
 				summed_force = summed_force.clamp_length_max(**max_force);
 			}
-			let mass = mass.map(|m| **m).unwrap_or(1.0);
+			let mass = self.mass.map(|m| **m).unwrap_or(1.0);
 			let acceleration = summed_force / mass;
-			**velocity += acceleration;
+			**self.velocity += acceleration;
 		}
-		if let Some(scalar) = scalar {
-			**velocity *= **scalar;
+		if let Some(scalar) = self.scalar {
+			**self.velocity *= **scalar;
 		}
-		if **velocity != Vec3::ZERO {
-			if let Some(max_velocity) = max_velocity {
-				**velocity = velocity.0.clamp_length_max(**max_velocity);
-			}
-			transform.translation += **velocity * time.delta_secs();
+		if let Some(max_speed) = self.max_speed {
+			**self.velocity = self.velocity.0.clamp_length_max(**max_speed);
+		}
+	}
+}
+
+#[cfg(feature = "avian")]
+type IntegrateForceFilter = Without<avian3d::prelude::RigidBody>;
+#[cfg(not(feature = "avian"))]
+type IntegrateForceFilter = ();
+
+/// Implementation of position, velocity, force integration
+/// as described by Daniel Shiffman
+/// https://natureofcode.com/vectors/#acceleration
+///
+/// With the `avian` feature, rigid bodies are instead
+/// handled by `integrate_force_avian`.
+pub fn integrate_force(
+	time: When<Res<Time>>,
+	mut query: Populated<(&mut Transform, ForceQuery), IntegrateForceFilter>,
+) {
+	for (mut transform, mut item) in query.iter_mut() {
+		item.integrate(time.delta_secs());
+		if **item.velocity != Vec3::ZERO {
+			transform.translation += **item.velocity * time.delta_secs();
 		}
 	}
 }
@@ -0,0 +1,107 @@
+use crate::prelude::*;
+use avian3d::prelude::LinearVelocity;
+use avian3d::prelude::RigidBody;
+use bevy::prelude::*;
+use sweet::prelude::When;
+
+/// Copy the [`LinearVelocity`] of each rigid body into its [`Velocity`],
+/// so steering actions see the result of collisions, gravity etc.
+/// This runs in the [`PreTickSet`].
+pub fn sync_avian_velocity(
+	mut query: Populated<(&LinearVelocity, &mut Velocity), With<RigidBody>>,
+) {
+	for (linear_velocity, mut velocity) in query.iter_mut() {
+		**velocity = **linear_velocity;
+	}
+}
+
+/// Integrate [`Force`] and [`Impulse`] into the [`Velocity`] as in
+/// [`integrate_force`], then write it to the [`LinearVelocity`]
+/// of the rigid body instead of moving the [`Transform`] directly,
+/// leaving the physics engine to resolve collisions.
+/// Note that the [`MaxSpeed`] applies to the entire velocity,
+/// including that from gravity.
+pub fn integrate_force_avian(
+	time: When<Res<Time>>,
+	mut query: Populated<(&mut LinearVelocity, ForceQuery), With<RigidBody>>,
+) {
+	for (mut linear_velocity, mut item) in query.iter_mut() {
+		item.integrate(time.delta_secs());
+		**linear_velocity = **item.velocity;
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use avian3d::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use bevy::time::TimeUpdateStrategy;
+	use std::time::Duration;
+	use sweet::prelude::*;
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.add_plugins((
+			MinimalPlugins,
+			TransformPlugin,
+			PhysicsPlugins::default(),
+			BeetFlowPlugin::default(),
+			BeetSpatialPlugins,
+		))
+		.insert_resource(Gravity(Vec3::ZERO));
+		app
+	}
+
+	#[test]
+	fn writes_linear_velocity() {
+		let mut app = app();
+		let entity = app
+			.world_mut()
+			.spawn((
+				Transform::default(),
+				RigidBody::Dynamic,
+				Collider::sphere(0.5),
+				ForceBundle {
+					impulse: Impulse(Vec3::new(1., 0., 0.)),
+					..default()
+				},
+			))
+			.id();
+
+		app.update();
+		expect(**app.world().get::<LinearVelocity>(entity).unwrap())
+			.to_be(Vec3::new(1., 0., 0.));
+		expect(**app.world().get::<Impulse>(entity).unwrap()).to_be(Vec3::ZERO);
+	}
+
+	#[test]
+	fn steers_rigid_body() {
+		let mut app = app();
+		let agent = app
+			.world_mut()
+			.spawn((
+				Transform::default(),
+				RigidBody::Dynamic,
+				Collider::sphere(0.5),
+				ForceBundle::default(),
+				SteerBundle::default(),
+				SteerTarget::Position(Vec3::new(10., 0., 0.)),
+				Seek::default(),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+
+		// advance past at least one fixed physics step per update
+		app.insert_resource(TimeUpdateStrategy::ManualDuration(
+			Duration::from_millis(20),
+		));
+		for _ in 0..20 {
+			app.update();
+		}
+		expect(app.world().get::<Transform>(agent).unwrap().translation.x)
+			.to_be_greater_than(0.);
+	}
+}
@@ -6,6 +6,10 @@ mod hover;
 pub use self::hover::*;
 mod integrate_force;
 pub use self::integrate_force::*;
+#[cfg(feature = "avian")]
+mod integrate_force_avian;
+#[cfg(feature = "avian")]
+pub use self::integrate_force_avian::*;
 mod rotate_to_velocity;
 pub use self::rotate_to_velocity::*;
 mod translate;
@@ -18,9 +22,13 @@ use bevy::prelude::*;
 /// - [`Hover`]
 /// - [`RotateToVelocity2d`]
 /// - [`RotateToVelocity3d`]
+///
+/// With the `avian` feature, any entity with a `RigidBody` will have its
+/// forces integrated into the `LinearVelocity` instead of the [`Transform`],
+/// see `integrate_force_avian`.
 pub fn movement_plugin(app: &mut App) {
 	app.add_systems(
-		Update,
+		TickSchedule::get(app),
 		(
 			(
 				integrate_force,
@@ -38,6 +46,17 @@ pub fn movement_plugin(app: &mut App) {
 	.register_type::<RotateToVelocity2d>()
 	.register_type::<RotateToVelocity3d>()
 	.register_type::<VelocityScalar>();
+	#[cfg(feature = "avian")]
+	app.add_systems(
+		TickSchedule::get(app),
+		(
+			sync_avian_velocity.in_set(PreTickSet),
+			integrate_force_avian
+				.before(rotate_to_velocity_2d)
+				.before(rotate_to_velocity_3d)
+				.in_set(PostTickSet),
+		),
+	);
 	let world = app.world_mut();
 	world.register_bundle::<ForceBundle>();
 }
@@ -0,0 +1,123 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Find the shortest path between two cells of a [`NavGrid`]
+/// using the [A* search algorithm](https://en.wikipedia.org/wiki/A*_search_algorithm),
+/// with the euclidean distance as the heuristic.
+/// The returned path includes both the start and end cells.
+/// Returns `None` if either cell is not walkable or there is no path.
+pub fn astar(grid: &NavGrid, start: IVec3, end: IVec3) -> Option<Vec<IVec3>> {
+	if !grid.is_walkable(start) || !grid.is_walkable(end) {
+		return None;
+	}
+	let start_index = grid.index(start)?;
+	let end_index = grid.index(end)?;
+
+	let mut cost = vec![f32::INFINITY; grid.blocked.len()];
+	let mut came_from = vec![None; grid.blocked.len()];
+	let mut open = BinaryHeap::new();
+
+	cost[start_index] = 0.;
+	open.push(OpenNode {
+		estimate: start.as_vec3().distance(end.as_vec3()),
+		index: start_index,
+	});
+
+	while let Some(OpenNode { estimate, index }) = open.pop() {
+		if index == end_index {
+			let mut path = vec![end];
+			let mut current = index;
+			while let Some(prev) = came_from[current] {
+				path.push(grid.cell(prev));
+				current = prev;
+			}
+			path.reverse();
+			return Some(path);
+		}
+		let cell = grid.cell(index);
+		// skip stale entries
+		if estimate > cost[index] + cell.as_vec3().distance(end.as_vec3()) {
+			continue;
+		}
+		for neighbor in grid.neighbors(cell) {
+			let neighbor_index = grid.index(neighbor)?;
+			let next_cost = cost[index] + (neighbor - cell).as_vec3().length();
+			if next_cost < cost[neighbor_index] {
+				cost[neighbor_index] = next_cost;
+				came_from[neighbor_index] = Some(index);
+				open.push(OpenNode {
+					estimate: next_cost
+						+ neighbor.as_vec3().distance(end.as_vec3()),
+					index: neighbor_index,
+				});
+			}
+		}
+	}
+	None
+}
+
+/// An entry in the open set, ordered so that the [`BinaryHeap`]
+/// pops the lowest estimate first, breaking ties by index
+/// for deterministic paths.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct OpenNode {
+	estimate: f32,
+	index: usize,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other
+			.estimate
+			.total_cmp(&self.estimate)
+			.then_with(|| other.index.cmp(&self.index))
+	}
+}
+
+impl PartialOrd for OpenNode {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn straight_line() {
+		let grid = NavGrid::new_2d(UVec2::new(4, 1), 1.);
+		expect(astar(&grid, IVec3::ZERO, IVec3::new(3, 0, 0))).to_be(Some(
+			vec![
+				IVec3::new(0, 0, 0),
+				IVec3::new(1, 0, 0),
+				IVec3::new(2, 0, 0),
+				IVec3::new(3, 0, 0),
+			],
+		));
+	}
+
+	#[test]
+	fn diagonal_3d() {
+		let grid = NavGrid::new_3d(UVec3::splat(3), 1.);
+		expect(astar(&grid, IVec3::ZERO, IVec3::splat(2))).to_be(Some(vec![
+			IVec3::ZERO,
+			IVec3::ONE,
+			IVec3::splat(2),
+		]));
+	}
+
+	#[test]
+	fn blocked_end() {
+		let mut grid = NavGrid::new_2d(UVec2::new(4, 1), 1.);
+		grid.set_blocked(IVec3::new(3, 0, 0), true);
+		expect(astar(&grid, IVec3::ZERO, IVec3::new(3, 0, 0))).to_be_none();
+	}
+}
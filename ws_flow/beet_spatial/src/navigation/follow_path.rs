@@ -0,0 +1,294 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+
+/// Go to the agent's [`SteerTarget`] via a path planned on the [`NavGrid`],
+/// seeking each waypoint in turn and arriving at the last one.
+/// The path is planned once when the action is run.
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// - [MutateOrigin](ActionTag::MutateOrigin)
+/// ## Logic
+/// - Succeeds when the agent is within [`Self::end_radius`] of the target.
+/// 	For 2D grids distances are measured in the XY plane.
+/// - Fails if there is no [`NavGrid`], the target is not found,
+/// 	or there is no path to the target.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// # let mut world = world();
+/// world.insert_resource(NavGrid::new_2d(UVec2::new(10, 10), 1.));
+/// world
+/// 	.spawn((
+/// 		Transform::from_xyz(0.5, 0.5, 0.),
+/// 		ForceBundle::default(),
+/// 		SteerBundle::default(),
+/// 		SteerTarget::Position(Vec3::new(8.5, 8.5, 0.)),
+/// 		FollowPath::default(),
+/// 	))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(follow_path_on_run)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(ContinueRun)]
+pub struct FollowPath {
+	/// The distance at which a waypoint is considered reached,
+	/// defaults to `0.5`
+	pub waypoint_radius: f32,
+	/// The distance at which the agent has arrived at the target,
+	/// defaults to `0.5`
+	pub end_radius: f32,
+}
+
+impl Default for FollowPath {
+	fn default() -> Self {
+		Self {
+			waypoint_radius: 0.5,
+			end_radius: 0.5,
+		}
+	}
+}
+
+impl FollowPath {
+	/// Scale the radii, ie when using pixel space.
+	pub fn scaled_dist(mut self, val: f32) -> Self {
+		self.waypoint_radius *= val;
+		self.end_radius *= val;
+		self
+	}
+}
+
+/// The waypoints planned by a [`FollowPath`], this is
+/// inserted on the action entity each time it is run.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct NavPath {
+	/// The world position of each waypoint.
+	pub waypoints: Vec<Vec3>,
+	/// Index of the waypoint currently being sought.
+	pub index: usize,
+	/// Whether the path was planned on a 2D [`NavGrid`], in which case
+	/// waypoints are sought in the XY plane at the agent's own z.
+	pub is_2d: bool,
+}
+
+impl NavPath {
+	/// The waypoint currently being sought.
+	pub fn current(&self) -> Option<Vec3> {
+		self.waypoints.get(self.index).copied()
+	}
+	/// The waypoint currently being sought from the given position,
+	/// for 2D paths its z is that of the position.
+	pub fn current_from(&self, position: Vec3) -> Option<Vec3> {
+		self.current().map(|mut waypoint| {
+			if self.is_2d {
+				waypoint.z = position.z;
+			}
+			waypoint
+		})
+	}
+	/// Whether the current waypoint is the last one.
+	pub fn is_last(&self) -> bool { self.index + 1 >= self.waypoints.len() }
+}
+
+fn follow_path_on_run(
+	ev: Trigger<OnRun>,
+	mut commands: Commands,
+	grid: Option<Res<NavGrid>>,
+	transforms: Query<&GlobalTransform>,
+	agents: Query<(&GlobalTransform, &SteerTarget)>,
+) {
+	let (transform, steer_target) = agents
+		.get(ev.origin)
+		.expect(&expect_action::to_have_origin(&ev));
+	let Some(grid) = grid else {
+		log::warn!("FollowPath: no NavGrid resource");
+		ev.trigger_result(&mut commands, RunResult::Failure);
+		return;
+	};
+	let path = steer_target
+		.get_position(&transforms)
+		.ok()
+		.and_then(|target| grid.find_path(transform.translation(), target));
+	match path {
+		Some(waypoints) => {
+			commands.entity(ev.action).insert(NavPath {
+				waypoints,
+				index: 0,
+				is_2d: grid.is_2d(),
+			});
+		}
+		None => {
+			ev.trigger_result(&mut commands, RunResult::Failure);
+		}
+	}
+}
+
+pub(crate) fn follow_path(
+	mut commands: Commands,
+	mut agents: Query<(
+		&GlobalTransform,
+		&Velocity,
+		&MaxSpeed,
+		&mut Impulse,
+		Option<&ArriveRadius>,
+	)>,
+	mut query: Query<(Entity, &Running, &FollowPath, &mut NavPath)>,
+) {
+	for (action, running, follow_path, mut path) in query.iter_mut() {
+		let (transform, velocity, max_speed, mut impulse, arrive_radius) =
+			agents
+				.get_mut(running.origin)
+				.expect(&expect_action::to_have_origin(&running));
+		let position = transform.translation();
+
+		while !path.is_last()
+			&& path.current_from(position).is_some_and(|waypoint| {
+				position.distance_squared(waypoint)
+					<= follow_path.waypoint_radius.powi(2)
+			}) {
+			path.index += 1;
+		}
+		let Some(waypoint) = path.current_from(position) else {
+			running.trigger_result(&mut commands, action, RunResult::Failure);
+			continue;
+		};
+
+		if path.is_last()
+			&& position.distance_squared(waypoint)
+				<= follow_path.end_radius.powi(2)
+		{
+			running.trigger_result(&mut commands, action, RunResult::Success);
+			continue;
+		}
+
+		*impulse = seek_impulse(
+			&position,
+			&velocity,
+			&waypoint,
+			*max_speed,
+			// only slow down for the last waypoint
+			arrive_radius.copied().filter(|_| path.is_last()),
+		);
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), BeetSpatialPlugins))
+			.insert_time();
+		app
+	}
+
+	#[test]
+	fn plans_path() {
+		let mut app = app();
+		let world = app.world_mut();
+		world.insert_resource(
+			NavGrid::new_2d(UVec2::new(5, 5), 1.).with_blocked_aabb(
+				Vec3::new(2., 0., 0.),
+				Vec3::new(2., 3., 0.),
+			),
+		);
+		let agent = world
+			.spawn((
+				Transform::from_xyz(0.5, 0.5, 0.),
+				GlobalTransform::from_xyz(0.5, 0.5, 0.),
+				ForceBundle::default(),
+				SteerBundle::default(),
+				SteerTarget::Position(Vec3::new(4.5, 0.5, 0.)),
+				FollowPath::default(),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+
+		let path = world.get::<NavPath>(agent).unwrap();
+		expect(path.waypoints.iter().any(|p| p.y > 4.)).to_be_true();
+
+		app.update_with_secs(1);
+		let impulse = **app.world().get::<Impulse>(agent).unwrap();
+		// heading up and around the wall
+		expect(impulse.y).to_be_greater_than(0.);
+	}
+
+	#[test]
+	fn succeeds_on_arrive() {
+		let mut app = app();
+		let world = app.world_mut();
+		world.insert_resource(NavGrid::new_2d(UVec2::new(5, 5), 1.));
+		let on_result = collect_on_result(world);
+		world
+			.spawn((
+				Name::new("agent"),
+				Transform::from_xyz(0.5, 0.5, 0.),
+				GlobalTransform::from_xyz(0.5, 0.5, 0.),
+				ForceBundle::default(),
+				SteerBundle::default(),
+				SteerTarget::Position(Vec3::new(0.6, 0.5, 0.)),
+				FollowPath::default(),
+			))
+			.flush_trigger(OnRun::local());
+		app.update_with_secs(1);
+		expect(on_result())
+			.to_be(vec![("agent".to_string(), RunResult::Success)]);
+	}
+
+	#[test]
+	fn ignores_z_on_2d_grid() {
+		let mut app = app();
+		let world = app.world_mut();
+		world.insert_resource(NavGrid::new_2d(UVec2::new(5, 5), 1.));
+		let on_result = collect_on_result(world);
+		let agent = world
+			.spawn((
+				Name::new("agent"),
+				Transform::from_xyz(0.5, 0.5, 10.),
+				GlobalTransform::from_xyz(0.5, 0.5, 10.),
+				ForceBundle::default(),
+				SteerBundle::default(),
+				SteerTarget::Position(Vec3::new(0.6, 0.5, 0.)),
+				FollowPath::default(),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+		expect(app.world().get::<NavPath>(agent).unwrap().is_2d).to_be_true();
+		app.update_with_secs(1);
+		expect(on_result())
+			.to_be(vec![("agent".to_string(), RunResult::Success)]);
+	}
+
+	#[test]
+	fn fails_when_unreachable() {
+		let mut app = app();
+		let world = app.world_mut();
+		world.insert_resource(
+			NavGrid::new_2d(UVec2::new(5, 5), 1.).with_blocked_aabb(
+				Vec3::new(2., 0., 0.),
+				Vec3::new(2., 5., 0.),
+			),
+		);
+		let on_result = collect_on_result(world);
+		world
+			.spawn((
+				Name::new("agent"),
+				Transform::from_xyz(0.5, 0.5, 0.),
+				GlobalTransform::from_xyz(0.5, 0.5, 0.),
+				ForceBundle::default(),
+				SteerBundle::default(),
+				SteerTarget::Position(Vec3::new(4.5, 0.5, 0.)),
+				FollowPath::default(),
+			))
+			.flush_trigger(OnRun::local());
+		expect(on_result())
+			.to_be(vec![("agent".to_string(), RunResult::Failure)]);
+	}
+}
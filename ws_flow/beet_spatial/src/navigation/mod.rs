@@ -0,0 +1,31 @@
+//! Pathfinding for steering agents, use [`FollowPath`] instead of
+//! [`Seek`] to go to a [`SteerTarget`] without walking through walls.
+mod astar;
+pub use self::astar::*;
+mod follow_path;
+pub use self::follow_path::*;
+mod nav_grid;
+pub use self::nav_grid::*;
+#[allow(unused, reason = "docs")]
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+
+/// Add all systems and types for navigation:
+/// - [`NavGrid`]
+/// - [`NavPath`]
+/// - [`FollowPath`]
+/// Required Resources:
+/// - [`NavGrid`], for [`FollowPath`] to succeed
+pub fn navigation_plugin(app: &mut App) {
+	app.add_systems(
+		TickSchedule::get(app),
+		follow_path
+			.before(avoid_obstacles)
+			.before(contain)
+			.in_set(TickSet),
+	)
+	.register_type::<NavGrid>()
+	.register_type::<NavPath>()
+	.register_type::<FollowPath>();
+}
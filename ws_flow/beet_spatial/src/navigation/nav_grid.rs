@@ -0,0 +1,253 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// A uniform grid of walkable and blocked cells used for pathfinding,
+/// see [`FollowPath`] for usage.
+/// 2D grids have a depth of one and lie on the XY plane, ignoring
+/// the z position of agents and targets.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// let grid = NavGrid::new_2d(UVec2::new(10, 10), 1.)
+/// 	.with_blocked_aabb(Vec3::new(4., 0., 0.), Vec3::new(5., 8., 0.));
+/// let path = grid
+/// 	.find_path(Vec3::new(0.5, 0.5, 0.), Vec3::new(8.5, 0.5, 0.))
+/// 	.unwrap();
+/// assert_eq!(path.last(), Some(&Vec3::new(8.5, 0.5, 0.)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct NavGrid {
+	/// World position of the minimum corner of the first cell.
+	pub origin: Vec3,
+	/// Width of each cell in world units.
+	pub cell_size: f32,
+	/// Number of cells along each axis.
+	pub size: UVec3,
+	/// Blocked flag for each cell, indexed by [`Self::index`].
+	pub blocked: Vec<bool>,
+}
+
+impl Default for NavGrid {
+	fn default() -> Self { Self::new_2d(UVec2::splat(10), 1.) }
+}
+
+impl NavGrid {
+	/// Create a fully walkable 2D grid on the XY plane.
+	pub fn new_2d(size: UVec2, cell_size: f32) -> Self {
+		Self::new_3d(size.extend(1), cell_size)
+	}
+	/// Create a fully walkable 3D grid.
+	pub fn new_3d(size: UVec3, cell_size: f32) -> Self {
+		Self {
+			origin: Vec3::ZERO,
+			cell_size,
+			size,
+			blocked: vec![false; (size.x * size.y * size.z) as usize],
+		}
+	}
+	/// Set the world position of the minimum corner of the grid.
+	pub fn with_origin(mut self, origin: Vec3) -> Self {
+		self.origin = origin;
+		self
+	}
+	/// Block every cell overlapping the world space box, see [`Self::block_aabb`].
+	pub fn with_blocked_aabb(mut self, min: Vec3, max: Vec3) -> Self {
+		self.block_aabb(min, max);
+		self
+	}
+
+	/// Whether this grid has a depth of one.
+	pub fn is_2d(&self) -> bool { self.size.z == 1 }
+
+	/// Index into [`Self::blocked`] for the cell,
+	/// or `None` if it is out of bounds.
+	pub fn index(&self, cell: IVec3) -> Option<usize> {
+		if cell.cmplt(IVec3::ZERO).any()
+			|| cell.cmpge(self.size.as_ivec3()).any()
+		{
+			return None;
+		}
+		let cell = cell.as_uvec3();
+		Some(
+			(cell.z * self.size.x * self.size.y + cell.y * self.size.x + cell.x)
+				as usize,
+		)
+	}
+
+	/// The cell for a given index into [`Self::blocked`].
+	pub fn cell(&self, index: usize) -> IVec3 {
+		let index = index as u32;
+		let layer = self.size.x * self.size.y;
+		IVec3::new(
+			(index % self.size.x) as i32,
+			(index % layer / self.size.x) as i32,
+			(index / layer) as i32,
+		)
+	}
+
+	/// Whether the cell is in bounds and not blocked.
+	pub fn is_walkable(&self, cell: IVec3) -> bool {
+		self.index(cell).map(|i| !self.blocked[i]).unwrap_or(false)
+	}
+
+	/// Set whether a cell is blocked, out of bounds cells are ignored.
+	pub fn set_blocked(&mut self, cell: IVec3, blocked: bool) {
+		if let Some(index) = self.index(cell) {
+			self.blocked[index] = blocked;
+		}
+	}
+
+	/// Block every cell overlapping the world space box,
+	/// any part of the box outside of the grid is ignored.
+	pub fn block_aabb(&mut self, min: Vec3, max: Vec3) {
+		let last = self.size.as_ivec3() - IVec3::ONE;
+		let min = self.world_to_cell_unchecked(min).max(IVec3::ZERO);
+		let max = self.world_to_cell_unchecked(max).min(last);
+		for z in min.z..=max.z {
+			for y in min.y..=max.y {
+				for x in min.x..=max.x {
+					self.set_blocked(IVec3::new(x, y, z), true);
+				}
+			}
+		}
+	}
+
+	fn world_to_cell_unchecked(&self, position: Vec3) -> IVec3 {
+		let mut cell = ((position - self.origin) / self.cell_size)
+			.floor()
+			.as_ivec3();
+		if self.is_2d() {
+			cell.z = 0;
+		}
+		cell
+	}
+
+	/// The cell containing the world position, or `None` if it is out of bounds.
+	pub fn world_to_cell(&self, position: Vec3) -> Option<IVec3> {
+		let cell = self.world_to_cell_unchecked(position);
+		self.index(cell).map(|_| cell)
+	}
+
+	/// The world position of the center of the cell.
+	/// For 2D grids the z position is that of the [`Self::origin`].
+	pub fn cell_to_world(&self, cell: IVec3) -> Vec3 {
+		let mut position =
+			self.origin + (cell.as_vec3() + Vec3::splat(0.5)) * self.cell_size;
+		if self.is_2d() {
+			position.z = self.origin.z;
+		}
+		position
+	}
+
+	/// Walkable neighbors of the cell, diagonal moves are
+	/// only allowed if they do not cut the corner of a blocked cell.
+	pub fn neighbors(&self, cell: IVec3) -> impl '_ + Iterator<Item = IVec3> {
+		let z_range = if self.is_2d() { 0..=0 } else { -1..=1 };
+		z_range
+			.flat_map(|z| {
+				(-1..=1).flat_map(move |y| {
+					(-1..=1).map(move |x| IVec3::new(x, y, z))
+				})
+			})
+			.filter(|offset| *offset != IVec3::ZERO)
+			.filter(move |offset| {
+				self.is_walkable(cell + *offset)
+					&& [IVec3::X, IVec3::Y, IVec3::Z].into_iter().all(|axis| {
+						let step = *offset * axis;
+						step == IVec3::ZERO || self.is_walkable(cell + step)
+					})
+			})
+			.map(move |offset| cell + offset)
+	}
+
+	/// Find a path between two world positions using [`astar`].
+	/// The path begins with the next cell after the start cell
+	/// and ends with the exact `end` position.
+	/// Returns `None` if either position is out of bounds or blocked,
+	/// or if there is no path between them.
+	pub fn find_path(&self, start: Vec3, end: Vec3) -> Option<Vec<Vec3>> {
+		let start_cell = self.world_to_cell(start)?;
+		let end_cell = self.world_to_cell(end)?;
+		let cells = astar(self, start_cell, end_cell)?;
+		let mut path = cells
+			.into_iter()
+			.skip(1)
+			.map(|cell| self.cell_to_world(cell))
+			.collect::<Vec<_>>();
+		let mut end = end;
+		if self.is_2d() {
+			end.z = self.origin.z;
+		}
+		match path.last_mut() {
+			Some(last) => *last = end,
+			None => path.push(end),
+		}
+		Some(path)
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn cells() {
+		let grid = NavGrid::new_3d(UVec3::new(3, 4, 5), 2.)
+			.with_origin(Vec3::splat(-1.));
+		let cell = IVec3::new(2, 3, 4);
+		expect(grid.cell(grid.index(cell).unwrap())).to_be(cell);
+		expect(grid.index(IVec3::new(3, 0, 0))).to_be_none();
+		expect(grid.index(IVec3::new(-1, 0, 0))).to_be_none();
+		expect(grid.world_to_cell(Vec3::new(0., 0., 0.)))
+			.to_be(Some(IVec3::ZERO));
+		expect(grid.cell_to_world(IVec3::ZERO)).to_be(Vec3::ZERO);
+	}
+
+	#[test]
+	fn no_corner_cutting() {
+		let mut grid = NavGrid::new_2d(UVec2::new(3, 3), 1.);
+		grid.set_blocked(IVec3::new(1, 0, 0), true);
+		let neighbors = grid.neighbors(IVec3::ZERO).collect::<Vec<_>>();
+		expect(neighbors).to_be(vec![IVec3::new(0, 1, 0)]);
+	}
+
+	#[test]
+	fn find_path() {
+		let grid = NavGrid::new_2d(UVec2::new(5, 5), 1.)
+			.with_blocked_aabb(Vec3::new(2., 0., 0.), Vec3::new(2., 3., 0.));
+		let path = grid
+			.find_path(Vec3::new(0.5, 0.5, 0.), Vec3::new(4.5, 0.5, 0.))
+			.unwrap();
+		// must go around the wall
+		expect(path.iter().any(|p| p.y > 4.)).to_be_true();
+		expect(*path.last().unwrap()).to_be(Vec3::new(4.5, 0.5, 0.));
+
+		// fully blocked
+		let grid = grid
+			.with_blocked_aabb(Vec3::new(2., 4., 0.), Vec3::new(2., 4., 0.));
+		expect(
+			grid.find_path(Vec3::new(0.5, 0.5, 0.), Vec3::new(4.5, 0.5, 0.)),
+		)
+		.to_be_none();
+	}
+
+	#[test]
+	fn block_aabb_outside_grid() {
+		let grid = NavGrid::new_2d(UVec2::new(3, 3), 1.).with_blocked_aabb(
+			Vec3::new(-1e9, 1., 0.),
+			Vec3::new(1e9, 1.5, 0.),
+		);
+		expect(grid.blocked.iter().filter(|b| **b).count()).to_be(3);
+		expect(grid.is_walkable(IVec3::new(0, 1, 0))).to_be_false();
+		expect(grid.is_walkable(IVec3::new(0, 0, 0))).to_be_true();
+
+		// entirely outside
+		let grid = NavGrid::new_2d(UVec2::new(3, 3), 1.)
+			.with_blocked_aabb(Vec3::splat(5.), Vec3::splat(10.));
+		expect(grid.blocked.iter().any(|b| *b)).to_be_false();
+	}
+}
@@ -13,5 +13,8 @@ use bevy::prelude::*;
 /// Add all systems and types for procedural animation actions:
 /// - [`PlayProceduralAnimation`]
 pub fn procedural_animation_plugin(app: &mut App) {
-	app.add_systems(Update, play_procedural_animation.in_set(TickSet));
+	app.add_systems(
+		TickSchedule::get(app),
+		play_procedural_animation.in_set(TickSet),
+	);
 }
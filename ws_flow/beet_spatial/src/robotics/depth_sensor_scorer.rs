@@ -3,7 +3,7 @@ use beet_flow::prelude::*;
 use bevy::prelude::*;
 
 /// Sets the [`Score`] based on the [`DepthValue`], usually
-/// updated by a sensor.
+/// updated by a sensor or a simulated [`RangeSensor`].
 /// ## Tags
 /// - [ControlFlow](ActionTag::ControlFlow)
 #[action(depth_sensor_scorer)]
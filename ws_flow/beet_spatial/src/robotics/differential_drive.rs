@@ -0,0 +1,246 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+use std::f32::consts::TAU;
+use sweet::prelude::When;
+
+/// The physical parameters of a differential drive robot, ie a robot
+/// with a left and right wheel driven by a [`DualMotorValue`].
+/// The robot drives on the XZ plane, forward is [`Transform::forward`]
+/// and it turns around the Y axis.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// # let mut world = world();
+/// world.spawn((
+/// 	Transform::default(),
+/// 	DifferentialDrive::default(),
+/// 	DualMotorValue::splat(MotorValue::forward_max()),
+/// ));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component, Default)]
+#[require(DualMotorValue, Odometry)]
+pub struct DifferentialDrive {
+	/// The distance between the two wheels in meters, defaults to `0.1`.
+	pub wheel_base: f32,
+	/// The radius of each wheel in meters, defaults to `0.03`.
+	pub wheel_radius: f32,
+	/// The revolutions per minute of a wheel at full speed,
+	/// defaults to `100.`.
+	pub max_rpm: f32,
+}
+
+impl Default for DifferentialDrive {
+	fn default() -> Self {
+		Self {
+			wheel_base: 0.1,
+			wheel_radius: 0.03,
+			max_rpm: 100.,
+		}
+	}
+}
+
+impl DifferentialDrive {
+	/// Create a new drive with the given dimensions in meters.
+	pub fn new(wheel_base: f32, wheel_radius: f32, max_rpm: f32) -> Self {
+		Self {
+			wheel_base,
+			wheel_radius,
+			max_rpm,
+		}
+	}
+
+	/// The ground speed of a wheel at full speed, in meters per second.
+	pub fn max_wheel_speed(&self) -> f32 {
+		self.max_rpm / 60. * TAU * self.wheel_radius
+	}
+
+	/// The linear velocity in meters per second and the angular velocity in
+	/// radians per second (counter-clockwise from above) for the motor values.
+	pub fn velocity(&self, motors: &DualMotorValue) -> (f32, f32) {
+		let max = self.max_wheel_speed();
+		let left = motors.left.to_signed_normal() * max;
+		let right = motors.right.to_signed_normal() * max;
+		((left + right) / 2., (right - left) / self.wheel_base)
+	}
+
+	/// The motor values required for the given linear and angular velocity.
+	/// If a wheel would exceed its max speed both wheels are scaled down,
+	/// preserving the ratio between them.
+	pub fn motor_values(&self, linear: f32, angular: f32) -> DualMotorValue {
+		let max = self.max_wheel_speed();
+		let half_turn = angular * self.wheel_base / 2.;
+		let mut left = (linear - half_turn) / max;
+		let mut right = (linear + half_turn) / max;
+		let largest = left.abs().max(right.abs());
+		if largest > 1. {
+			left /= largest;
+			right /= largest;
+		}
+		DualMotorValue::new(
+			MotorValue::from_signed_normal(left),
+			MotorValue::from_signed_normal(right),
+		)
+	}
+}
+
+/// The pose and velocity of a [`DifferentialDrive`] estimated by integrating
+/// its wheel speeds, relative to where it started.
+/// In simulation this matches the [`Transform`], on a real robot it
+/// will drift as wheels slip.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct Odometry {
+	/// The estimated position relative to the start.
+	pub translation: Vec3,
+	/// The estimated rotation around the Y axis relative to the start,
+	/// in radians.
+	pub heading: f32,
+	/// The current linear velocity in meters per second.
+	pub linear_velocity: f32,
+	/// The current angular velocity in radians per second.
+	pub angular_velocity: f32,
+	/// The total distance travelled in meters.
+	pub distance: f32,
+}
+
+impl Odometry {
+	/// Advance the pose using the midpoint heading, which is exact
+	/// for straight lines and a good approximation for arcs.
+	pub fn integrate(&mut self, linear: f32, angular: f32, delta_secs: f32) {
+		let mid_heading = self.heading + angular * delta_secs / 2.;
+		let forward = Quat::from_rotation_y(mid_heading) * Vec3::NEG_Z;
+		self.translation += forward * linear * delta_secs;
+		self.heading = wrap_angle(self.heading + angular * delta_secs);
+		self.linear_velocity = linear;
+		self.angular_velocity = angular;
+		self.distance += linear.abs() * delta_secs;
+	}
+}
+
+/// Wrap an angle in radians to the range `-PI..=PI`.
+pub fn wrap_angle(angle: f32) -> f32 { (angle + PI).rem_euclid(TAU) - PI }
+
+/// Integrate the [`DualMotorValue`] of each [`DifferentialDrive`]
+/// into its [`Odometry`] and [`Transform`], runs in the [`PostTickSet`].
+pub fn integrate_differential_drive(
+	time: When<Res<Time>>,
+	mut query: Populated<(
+		&DifferentialDrive,
+		&DualMotorValue,
+		&mut Odometry,
+		Option<&mut Transform>,
+	)>,
+) {
+	let delta_secs = time.delta_secs();
+	for (drive, motors, mut odometry, transform) in query.iter_mut() {
+		let (linear, angular) = drive.velocity(motors);
+		odometry.integrate(linear, angular, delta_secs);
+		if let Some(mut transform) = transform {
+			let half_turn = angular * delta_secs / 2.;
+			transform.rotate_y(half_turn);
+			let forward = transform.forward();
+			transform.translation += forward * linear * delta_secs;
+			transform.rotate_y(half_turn);
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use std::f32::consts::FRAC_PI_2;
+	use std::f32::consts::PI;
+	use std::f32::consts::TAU;
+	use sweet::prelude::*;
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), RoboticsPlugin))
+			.insert_time();
+		app
+	}
+
+	#[test]
+	fn kinematics() {
+		let drive = DifferentialDrive::new(0.2, 0.1, 60.);
+		expect(drive.max_wheel_speed()).to_be_close_to(TAU * 0.1);
+
+		let (linear, angular) =
+			drive.velocity(&DualMotorValue::splat(MotorValue::forward_max()));
+		expect(linear).to_be_close_to(TAU * 0.1);
+		expect(angular).to_be_close_to(0.);
+
+		let motors = drive.motor_values(0., 1.);
+		expect(motors.left.direction).to_be(MotorDirection::Backward);
+		expect(motors.right.direction).to_be(MotorDirection::Forward);
+		let (_, angular) = drive.velocity(&motors);
+		expect(angular).to_be_close_to(1.);
+	}
+
+	#[test]
+	fn drives_forward() {
+		let mut app = app();
+		let entity = app
+			.world_mut()
+			.spawn((
+				Transform::default(),
+				DifferentialDrive::new(0.2, 0.1, 60.),
+				DualMotorValue::splat(MotorValue::forward_max()),
+			))
+			.id();
+		app.update_with_secs(1);
+
+		let transform = app.world().get::<Transform>(entity).unwrap();
+		expect(transform.translation).to_be_close_to(Vec3::new(
+			0.,
+			0.,
+			-TAU * 0.1,
+		));
+		let odometry = app.world().get::<Odometry>(entity).unwrap();
+		expect(odometry.translation).to_be_close_to(transform.translation);
+		expect(odometry.distance).to_be_close_to(TAU * 0.1);
+	}
+
+	#[test]
+	fn spins_in_place() {
+		let mut app = app();
+		let entity = app
+			.world_mut()
+			.spawn((
+				Transform::default(),
+				DifferentialDrive::new(0.2, 0.1, 60.),
+				DualMotorValue::new(
+					MotorValue::backward_max(),
+					MotorValue::forward_max(),
+				),
+			))
+			.id();
+		// a quarter turn
+		app.update_with_millis(250);
+
+		let transform = app.world().get::<Transform>(entity).unwrap();
+		expect(transform.translation).to_be_close_to(Vec3::ZERO);
+		expect(*transform.forward()).to_be_close_to(Vec3::NEG_X);
+		let odometry = app.world().get::<Odometry>(entity).unwrap();
+		// 2 * wheel speed / wheel base
+		expect(odometry.angular_velocity).to_be_close_to(TAU);
+		expect(odometry.heading).to_be_close_to(FRAC_PI_2);
+
+		// a further half turn wraps to the other side
+		app.update_with_millis(500);
+		let odometry = app.world().get::<Odometry>(entity).unwrap();
+		expect(odometry.heading).to_be_close_to(-FRAC_PI_2);
+	}
+
+	#[test]
+	fn wraps_angle() {
+		expect(wrap_angle(PI * 1.5)).to_be_close_to(-PI * 0.5);
+		expect(wrap_angle(-PI * 1.5)).to_be_close_to(PI * 0.5);
+		expect(wrap_angle(0.5)).to_be_close_to(0.5);
+	}
+}
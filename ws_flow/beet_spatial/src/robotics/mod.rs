@@ -5,20 +5,43 @@ mod depth;
 pub use depth::*;
 mod depth_sensor_scorer;
 pub use depth_sensor_scorer::*;
+mod differential_drive;
+pub use differential_drive::*;
 mod dual_motor;
 pub use dual_motor::*;
 mod motor;
 use beet_flow::prelude::*;
 use bevy::prelude::*;
 pub use motor::*;
+use sweet::prelude::RandomSource;
+mod pid_controller;
+pub use pid_controller::*;
+mod pid_drive;
+pub use pid_drive::*;
+mod range_sensor;
+pub use range_sensor::*;
 
 /// A plugin that registers all robotics components and bundles:
 pub struct RoboticsPlugin;
 
 impl Plugin for RoboticsPlugin {
 	fn build(&self, app: &mut App) {
-		app.register_type::<DepthValue>()
-			.register_type::<DualMotorValue>();
+		app.add_systems(
+			TickSchedule::get(app),
+			(
+				update_range_sensors.in_set(PreTickSet),
+				pid_drive.in_set(TickSet),
+				integrate_differential_drive.in_set(PostTickSet),
+			),
+		)
+		.register_type::<DepthValue>()
+		.register_type::<DualMotorValue>()
+		.register_type::<DifferentialDrive>()
+		.register_type::<Odometry>()
+		.register_type::<PidDrive>()
+		.register_type::<RangeSensor>()
+		.register_type::<DepthScan>()
+		.init_resource::<RandomSource>();
 
 		let world = app.world_mut();
 		world.register_bundle::<DepthValue>();
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+/// A proportional-integral-derivative controller, producing an output
+/// that drives an error toward zero.
+/// ## Example
+/// ```
+/// # use beet_spatial::prelude::*;
+/// let mut pid = PidController::new(1., 0., 0.);
+/// assert_eq!(pid.update(2., 0.1), 2.);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct PidController {
+	/// Proportional gain, scales the current error.
+	pub kp: f32,
+	/// Integral gain, scales the accumulated error.
+	pub ki: f32,
+	/// Derivative gain, scales the rate of change of the error.
+	pub kd: f32,
+	/// The absolute limit of the accumulated error,
+	/// preventing windup when the output is saturated.
+	pub integral_limit: f32,
+	/// The accumulated error.
+	#[reflect(ignore)]
+	pub integral: f32,
+	/// The error of the previous update, if any.
+	#[reflect(ignore)]
+	pub prev_error: Option<f32>,
+}
+
+impl Default for PidController {
+	fn default() -> Self { Self::new(1., 0., 0.) }
+}
+
+impl PidController {
+	/// Create a new controller with the given gains.
+	pub fn new(kp: f32, ki: f32, kd: f32) -> Self {
+		Self {
+			kp,
+			ki,
+			kd,
+			integral_limit: f32::MAX,
+			integral: 0.,
+			prev_error: None,
+		}
+	}
+	/// Specify the absolute limit of the accumulated error.
+	pub fn with_integral_limit(mut self, limit: f32) -> Self {
+		self.integral_limit = limit;
+		self
+	}
+
+	/// Clear the accumulated and previous error.
+	pub fn reset(&mut self) {
+		self.integral = 0.;
+		self.prev_error = None;
+	}
+
+	/// Accumulate the error and return the control output.
+	/// The derivative is zero on the first update after a reset.
+	pub fn update(&mut self, error: f32, delta_secs: f32) -> f32 {
+		self.integral = (self.integral + error * delta_secs)
+			.clamp(-self.integral_limit, self.integral_limit);
+		let derivative = match self.prev_error {
+			Some(prev) if delta_secs > 0. => (error - prev) / delta_secs,
+			_ => 0.,
+		};
+		self.prev_error = Some(error);
+		self.kp * error + self.ki * self.integral + self.kd * derivative
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut pid = PidController::new(1., 0.5, 0.1);
+		// no derivative on the first update
+		expect(pid.update(1., 1.)).to_be_close_to(1.5);
+		// p: 0.5, i: 1.5 * 0.5, d: -0.5 * 0.1
+		expect(pid.update(0.5, 1.)).to_be_close_to(1.2);
+		pid.reset();
+		expect(pid.integral).to_be(0.);
+		expect(pid.prev_error).to_be_none();
+	}
+
+	#[test]
+	fn integral_limit() {
+		let mut pid = PidController::new(0., 1., 0.).with_integral_limit(2.);
+		for _ in 0..10 {
+			pid.update(1., 1.);
+		}
+		expect(pid.update(1., 1.)).to_be_close_to(2.);
+	}
+}
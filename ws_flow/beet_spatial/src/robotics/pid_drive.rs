@@ -0,0 +1,131 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+use sweet::prelude::When;
+
+/// Drive a [`DifferentialDrive`] toward a target heading and speed,
+/// using a [`PidController`] for each and the agent's [`Odometry`]
+/// as feedback. The resulting [`DualMotorValue`] is set on the agent.
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// - [MutateOrigin](ActionTag::MutateOrigin)
+/// ## Logic
+/// - Never completes, use with other actions like [`EndOnArrive`]
+/// 	or `ReturnInDuration`.
+/// - Both controllers are reset each time the action is run.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// # let mut world = world();
+/// world
+/// 	.spawn((
+/// 		Transform::default(),
+/// 		DifferentialDrive::default(),
+/// 		PidDrive::new(std::f32::consts::FRAC_PI_2, 0.1),
+/// 	))
+/// 	.trigger(OnRun::local());
+/// ```
+#[action(reset_pid_drive)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(ContinueRun)]
+pub struct PidDrive {
+	/// The target [`Odometry::heading`] in radians.
+	pub heading: f32,
+	/// The target [`Odometry::linear_velocity`] in meters per second.
+	pub speed: f32,
+	/// Outputs the angular velocity from the heading error,
+	/// defaults to `PidController::new(4., 0., 0.2)`.
+	pub heading_pid: PidController,
+	/// Corrects the linear velocity from the speed error, added to
+	/// [`Self::speed`], defaults to `PidController::new(0.5, 0.1, 0.)`.
+	pub speed_pid: PidController,
+}
+
+impl Default for PidDrive {
+	fn default() -> Self { Self::new(0., 0.) }
+}
+
+impl PidDrive {
+	/// Create a new action with the given target heading and speed.
+	pub fn new(heading: f32, speed: f32) -> Self {
+		Self {
+			heading,
+			speed,
+			heading_pid: PidController::new(4., 0., 0.2),
+			speed_pid: PidController::new(0.5, 0.1, 0.).with_integral_limit(1.),
+		}
+	}
+	/// Specify the controller used for the heading.
+	pub fn with_heading_pid(mut self, pid: PidController) -> Self {
+		self.heading_pid = pid;
+		self
+	}
+	/// Specify the controller used for the speed.
+	pub fn with_speed_pid(mut self, pid: PidController) -> Self {
+		self.speed_pid = pid;
+		self
+	}
+}
+
+fn reset_pid_drive(ev: Trigger<OnRun>, mut query: Query<&mut PidDrive>) {
+	let mut action = query
+		.get_mut(ev.action)
+		.expect(&expect_action::to_have_action(&ev));
+	action.heading_pid.reset();
+	action.speed_pid.reset();
+}
+
+pub(crate) fn pid_drive(
+	time: When<Res<Time>>,
+	mut agents: Query<(&DifferentialDrive, &Odometry, &mut DualMotorValue)>,
+	mut query: Populated<(&Running, &mut PidDrive)>,
+) {
+	let delta_secs = time.delta_secs();
+	for (running, mut action) in query.iter_mut() {
+		let (drive, odometry, mut motors) = agents
+			.get_mut(running.origin)
+			.expect(&expect_action::to_have_origin(&running));
+		let heading_error = wrap_angle(action.heading - odometry.heading);
+		let speed_error = action.speed - odometry.linear_velocity;
+		let angular = action.heading_pid.update(heading_error, delta_secs);
+		let linear =
+			action.speed + action.speed_pid.update(speed_error, delta_secs);
+		*motors = drive.motor_values(linear, angular);
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use std::f32::consts::FRAC_PI_2;
+	use sweet::prelude::*;
+
+	#[test]
+	fn turns_to_heading() {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), RoboticsPlugin))
+			.insert_time();
+		let agent = app
+			.world_mut()
+			.spawn((
+				Transform::default(),
+				DifferentialDrive::new(0.2, 0.1, 60.),
+				PidDrive::new(FRAC_PI_2, 0.),
+			))
+			.flush_trigger(OnRun::local())
+			.id();
+
+		for _ in 0..100 {
+			app.update_with_millis(20);
+		}
+		let odometry = app.world().get::<Odometry>(agent).unwrap();
+		expect(odometry.heading).to_be_close_to(FRAC_PI_2);
+		let transform = app.world().get::<Transform>(agent).unwrap();
+		// turned left, counter-clockwise from above
+		expect(transform.forward().x).to_be_less_than(-0.9);
+	}
+}
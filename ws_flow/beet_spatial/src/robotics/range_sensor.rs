@@ -0,0 +1,294 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+use sweet::prelude::RandomSource;
+use sweet::prelude::When;
+
+/// A simulated range sensor, ie an ultrasonic sensor or lidar,
+/// that casts a fan of beams against each [`SteerObstacle`].
+/// The beams are spread evenly across the [`Self::fov`], centered on
+/// [`GlobalTransform::forward`] and rotating around the local Y axis.
+/// Each update the nearest reading is written to the [`DepthValue`]
+/// and every reading is written to the [`DepthScan`].
+/// Obstacles on the sensor entity or its ancestors are ignored.
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// # let mut world = world();
+/// world.spawn((Transform::default(), RangeSensor::ultrasonic()));
+/// world.spawn((Transform::default(), RangeSensor::lidar(180)));
+/// world.spawn((
+/// 	Transform::from_xyz(0., 0., -1.),
+/// 	SteerObstacle::Sphere(0.2),
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(DepthValue, DepthScan)]
+pub struct RangeSensor {
+	/// Readings closer than this are invalid.
+	pub min_range: f32,
+	/// Readings further than this are invalid.
+	pub max_range: f32,
+	/// The angle in radians between the first and last beam.
+	pub fov: f32,
+	/// The number of beams to cast, at least one.
+	pub beams: usize,
+	/// The maximum random offset in meters added to each reading.
+	pub noise: f32,
+	/// Seconds between each update, `0.` will update every tick.
+	pub interval: f32,
+	/// Seconds since the last update, this is initialized to the
+	/// [`Self::interval`] so the first tick updates immediately.
+	/// This is runtime state so is not serialized, a loaded sensor
+	/// waits one interval before its first update.
+	#[reflect(ignore)]
+	pub elapsed: f32,
+}
+
+impl Default for RangeSensor {
+	fn default() -> Self { Self::ultrasonic() }
+}
+
+impl RangeSensor {
+	/// Create a new sensor with the given range, fov and number of beams,
+	/// without noise and updating every tick.
+	pub fn new(min_range: f32, max_range: f32, fov: f32, beams: usize) -> Self {
+		Self {
+			min_range,
+			max_range,
+			fov,
+			beams,
+			noise: 0.,
+			interval: 0.,
+			elapsed: 0.,
+		}
+	}
+
+	/// Approximates a hobby ultrasonic sensor like the HC-SR04,
+	/// a narrow cone represented by five beams updating at ~16Hz.
+	pub fn ultrasonic() -> Self {
+		Self::new(0.02, DEFAULT_ULTRASOUND_MAX_DEPTH, 15_f32.to_radians(), 5)
+			.with_noise(0.003)
+			.with_interval(0.06)
+	}
+
+	/// Approximates a 2D lidar with a 270 degree sweep at 10Hz.
+	pub fn lidar(beams: usize) -> Self {
+		Self::new(0.1, 12., PI * 1.5, beams)
+			.with_noise(0.01)
+			.with_interval(0.1)
+	}
+
+	/// Specify the maximum random offset in meters added to each reading.
+	pub fn with_noise(mut self, noise: f32) -> Self {
+		self.noise = noise;
+		self
+	}
+	/// Specify the seconds between each update.
+	pub fn with_interval(mut self, interval: f32) -> Self {
+		self.interval = interval;
+		self.elapsed = interval;
+		self
+	}
+
+	/// The rotation around the local Y axis of the beam at the given index.
+	pub fn beam_angle(&self, index: usize) -> f32 {
+		if self.beams <= 1 {
+			return 0.;
+		}
+		-self.fov / 2. + self.fov * index as f32 / (self.beams - 1) as f32
+	}
+}
+
+/// Every reading of the last [`RangeSensor`] update, ordered
+/// from the rightmost beam to the leftmost.
+/// A reading is `None` when nothing was in range.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct DepthScan {
+	/// The distance measured by each beam.
+	pub readings: Vec<Option<f32>>,
+}
+
+impl DepthScan {
+	/// The nearest valid reading, if any.
+	pub fn nearest(&self) -> Option<f32> {
+		self.readings
+			.iter()
+			.flatten()
+			.copied()
+			.min_by(|a, b| a.total_cmp(b))
+	}
+}
+
+/// Cast the beams of each [`RangeSensor`] that is due for an update,
+/// runs in the [`PreTickSet`] so behaviors see fresh readings.
+pub(crate) fn update_range_sensors(
+	time: When<Res<Time>>,
+	mut rng: ResMut<RandomSource>,
+	parents: Query<&ChildOf>,
+	obstacles: Query<(Entity, &GlobalTransform, &SteerObstacle)>,
+	mut query: Populated<(
+		Entity,
+		&GlobalTransform,
+		&mut RangeSensor,
+		&mut DepthValue,
+		&mut DepthScan,
+	)>,
+) {
+	for (entity, transform, mut sensor, mut depth, mut scan) in query.iter_mut()
+	{
+		sensor.elapsed += time.delta_secs();
+		if sensor.elapsed < sensor.interval {
+			continue;
+		}
+		sensor.elapsed = 0.;
+
+		let ignored = std::iter::once(entity)
+			.chain(parents.iter_ancestors(entity))
+			.collect::<Vec<_>>();
+		let origin = transform.translation();
+		let rotation = transform.rotation();
+
+		scan.readings = (0..sensor.beams.max(1))
+			.map(|index| {
+				let direction = Dir3::new(
+					rotation
+						* Quat::from_rotation_y(sensor.beam_angle(index))
+						* Vec3::NEG_Z,
+				)
+				.ok()?;
+				let distance = obstacles
+					.iter()
+					.filter(|(obstacle, ..)| !ignored.contains(obstacle))
+					.filter_map(|(_, obstacle_transform, obstacle)| {
+						obstacle.raycast(
+							obstacle_transform.translation(),
+							origin,
+							direction,
+							sensor.max_range,
+							0.,
+						)
+					})
+					.map(|hit| hit.distance)
+					.min_by(|a, b| a.total_cmp(b))?;
+				let distance = if sensor.noise > 0. {
+					distance + rng.random_range(-sensor.noise..sensor.noise)
+				} else {
+					distance
+				};
+				(sensor.min_range..=sensor.max_range)
+					.contains(&distance)
+					.then_some(distance)
+			})
+			.collect();
+		**depth = scan.nearest();
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use std::f32::consts::PI;
+	use sweet::prelude::*;
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), RoboticsPlugin))
+			.insert_time();
+		app
+	}
+
+	#[test]
+	fn ultrasonic() {
+		let mut app = app();
+		let world = app.world_mut();
+		world.spawn((
+			GlobalTransform::from_xyz(0., 0., -1.),
+			SteerObstacle::Sphere(0.25),
+		));
+		let sensor = world
+			.spawn((
+				GlobalTransform::default(),
+				RangeSensor::ultrasonic().with_noise(0.),
+			))
+			.id();
+		app.update_with_millis(1);
+
+		expect(app.world().get::<DepthValue>(sensor).unwrap().unwrap())
+			.to_be_close_to(0.75);
+	}
+
+	#[test]
+	fn out_of_range() {
+		let mut app = app();
+		let world = app.world_mut();
+		world.spawn((
+			GlobalTransform::from_xyz(0., 0., -5.),
+			SteerObstacle::Sphere(0.25),
+		));
+		let sensor = world
+			.spawn((
+				GlobalTransform::default(),
+				RangeSensor::new(0., 2., 0., 1),
+			))
+			.id();
+		app.update_with_millis(1);
+
+		expect(**app.world().get::<DepthValue>(sensor).unwrap()).to_be_none();
+	}
+
+	#[test]
+	fn lidar() {
+		let mut app = app();
+		let world = app.world_mut();
+		// an obstacle to the left
+		world.spawn((
+			GlobalTransform::from_xyz(-2., 0., 0.),
+			SteerObstacle::Cuboid(Vec3::splat(0.5)),
+		));
+		let sensor = world
+			.spawn((
+				GlobalTransform::default(),
+				RangeSensor::new(0., 10., PI, 5),
+			))
+			.id();
+		app.update_with_millis(1);
+
+		let scan = app.world().get::<DepthScan>(sensor).unwrap();
+		expect(scan.readings.len()).to_be(5);
+		expect(scan.readings[4].unwrap()).to_be_close_to(1.5);
+		expect(scan.readings[0]).to_be_none();
+		expect(scan.nearest().unwrap()).to_be_close_to(1.5);
+	}
+
+	#[test]
+	fn interval() {
+		let mut app = app();
+		let world = app.world_mut();
+		let obstacle = world
+			.spawn((
+				GlobalTransform::from_xyz(0., 0., -1.),
+				SteerObstacle::Sphere(0.5),
+			))
+			.id();
+		let sensor = world
+			.spawn((
+				GlobalTransform::default(),
+				RangeSensor::new(0., 2., 0., 1).with_interval(1.),
+			))
+			.id();
+		app.update_with_millis(1);
+		app.world_mut().entity_mut(obstacle).despawn();
+		app.update_with_millis(500);
+		// not yet updated
+		expect(app.world().get::<DepthValue>(sensor).unwrap().is_some())
+			.to_be_true();
+		app.update_with_millis(600);
+		expect(**app.world().get::<DepthValue>(sensor).unwrap()).to_be_none();
+	}
+}
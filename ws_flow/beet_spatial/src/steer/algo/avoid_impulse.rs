@@ -0,0 +1,117 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Calculate an obstacle avoidance impulse
+/// as described [here](https://www.red3d.com/cwr/steer/gdc99/#obstacle-avoidance).
+/// A sphere is cast along the velocity, and the agent steers
+/// away from the nearest hit, more strongly the closer it is.
+pub fn avoid_obstacles_impulse<'a>(
+	position: Vec3,
+	velocity: &Velocity,
+	max_speed: MaxSpeed,
+	avoid: &AvoidObstacles,
+	obstacles: impl IntoIterator<Item = (&'a Transform, &'a SteerObstacle)>,
+) -> Impulse {
+	let Ok(direction) = Dir3::new(**velocity) else {
+		return Impulse::default();
+	};
+	let nearest = obstacles
+		.into_iter()
+		.filter_map(|(transform, obstacle)| {
+			obstacle.raycast(
+				transform.translation,
+				position,
+				direction,
+				avoid.look_ahead,
+				avoid.radius,
+			)
+		})
+		.min_by(|a, b| a.distance.total_cmp(&b.distance));
+
+	let Some(hit) = nearest else {
+		return Impulse::default();
+	};
+	// steer along the surface, removing the velocity into the obstacle
+	let lateral = (hit.normal - *direction * hit.normal.dot(*direction))
+		.normalize_or(hit.normal);
+	let urgency = 1. - hit.distance / avoid.look_ahead;
+	Impulse(lateral * *max_speed * urgency * avoid.scalar)
+}
+
+/// Calculate a containment impulse
+/// as described [here](https://www.red3d.com/cwr/steer/gdc99/#containment).
+/// The position is predicted along the velocity,
+/// if it leaves the bounds the agent is pushed back in,
+/// perpendicular to the walls it would cross.
+pub fn contain_impulse(
+	position: Vec3,
+	velocity: &Velocity,
+	max_speed: MaxSpeed,
+	contain: &Contain,
+) -> Impulse {
+	let predicted =
+		position + velocity.normalize_or_zero() * contain.look_ahead;
+	let inside = predicted.clamp(contain.min, contain.max);
+	if inside == predicted {
+		return Impulse::default();
+	}
+	Impulse(
+		(inside - predicted).normalize_or_zero() * *max_speed * contain.scalar,
+	)
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn avoids() {
+		let obstacle = (
+			Transform::from_xyz(1., 0.1, 0.),
+			SteerObstacle::Sphere(0.25),
+		);
+		let impulse = avoid_obstacles_impulse(
+			Vec3::ZERO,
+			&Velocity(Vec3::X),
+			MaxSpeed(1.),
+			&AvoidObstacles::default(),
+			[(&obstacle.0, &obstacle.1)],
+		);
+		// steer down, away from the obstacle
+		expect(impulse.x).to_be_close_to(0.);
+		expect(impulse.y).to_be_less_than(0.);
+
+		// nothing in the way
+		let impulse = avoid_obstacles_impulse(
+			Vec3::ZERO,
+			&Velocity(Vec3::NEG_X),
+			MaxSpeed(1.),
+			&AvoidObstacles::default(),
+			[(&obstacle.0, &obstacle.1)],
+		);
+		expect(*impulse).to_be(Vec3::ZERO);
+	}
+
+	#[test]
+	fn contains() {
+		let contain = Contain::new(Vec3::splat(-1.), Vec3::splat(1.));
+		expect(*contain_impulse(
+			Vec3::ZERO,
+			&Velocity(Vec3::X * 0.1),
+			MaxSpeed(1.),
+			&contain,
+		))
+		.to_be(Vec3::ZERO);
+
+		let impulse = contain_impulse(
+			Vec3::new(0.9, 0., 0.),
+			&Velocity(Vec3::X),
+			MaxSpeed(1.),
+			&contain,
+		);
+		expect(impulse.x).to_be_less_than(0.);
+	}
+}
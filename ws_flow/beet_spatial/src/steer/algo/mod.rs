@@ -1,5 +1,7 @@
 mod align_impulse;
 pub use self::align_impulse::*;
+mod avoid_impulse;
+pub use self::avoid_impulse::*;
 mod cohere_impulse;
 pub use self::cohere_impulse::*;
 mod seek_impulse;
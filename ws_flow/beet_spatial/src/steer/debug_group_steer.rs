@@ -6,7 +6,8 @@ use bevy::prelude::*;
 use std::marker::PhantomData;
 
 
-/// Provides debug visualization for the `Separate`, `Align`, and `Cohere` actions.
+/// Provides debug visualization for the `Separate`, `Align`, `Cohere`,
+/// `AvoidObstacles` and `Contain` actions, as well as any [`SteerObstacle`].
 pub struct DebugGroupSteerPlugin<M> {
 	toggle_key: KeyCode,
 	phantom: PhantomData<M>,
@@ -25,7 +26,7 @@ impl<M: 'static + Send + Sync> Plugin for DebugGroupSteerPlugin<M> {
 	fn build(&self, app: &mut App) {
 		app.add_systems(
 			Update,
-			debug_group_steer::<M>
+			(debug_group_steer::<M>, debug_obstacle_steer)
 				.run_if(input_toggle_active(false, self.toggle_key)),
 		);
 	}
@@ -68,3 +69,49 @@ fn debug_group_steer<M: 'static + Send + Sync>(
 		}
 	}
 }
+
+fn debug_obstacle_steer(
+	mut gizmos: Gizmos,
+	agents: Query<(&Transform, &Velocity)>,
+	obstacles: Query<(&Transform, &SteerObstacle)>,
+	avoid: Query<(&Running, &AvoidObstacles)>,
+	contain: Query<(&Running, &Contain)>,
+) {
+	for (transform, obstacle) in obstacles.iter() {
+		match obstacle {
+			SteerObstacle::Sphere(radius) => {
+				gizmos.circle_2d(
+					Isometry2d::from_translation(transform.translation.xy()),
+					*radius,
+					tailwind::RED_500,
+				);
+			}
+			SteerObstacle::Cuboid(half_extents) => {
+				gizmos.rect_2d(
+					Isometry2d::from_translation(transform.translation.xy()),
+					half_extents.xy() * 2.,
+					tailwind::RED_500,
+				);
+			}
+		}
+	}
+
+	for (running, params) in avoid.iter() {
+		if let Ok((transform, velocity)) = agents.get(running.origin) {
+			let start = transform.translation.xy();
+			gizmos.line_2d(
+				start,
+				start + velocity.xy().normalize_or_zero() * params.look_ahead,
+				tailwind::RED_300,
+			);
+		}
+	}
+
+	for (_, params) in contain.iter() {
+		gizmos.rect_2d(
+			Isometry2d::from_translation(((params.min + params.max) / 2.).xy()),
+			(params.max - params.min).xy(),
+			tailwind::PURPLE_500,
+		);
+	}
+}
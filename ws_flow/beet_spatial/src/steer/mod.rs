@@ -10,6 +10,8 @@ mod debug_group_steer;
 pub use self::debug_group_steer::*;
 mod steer_bundle;
 pub use steer_bundle::*;
+mod steer_obstacle;
+pub use steer_obstacle::*;
 mod steer_target;
 use crate::prelude::*;
 pub use algo::*;
@@ -26,11 +28,12 @@ type M = GroupSteerAgent;
 /// - [`MaxSpeed`]
 /// - [`ArriveRadius`]
 /// - [`GroupSteerAgent`]
+/// - [`SteerObstacle`]
 /// Required Resources:
 /// - [`Time`]
 pub fn steer_plugin(app: &mut App) {
 	app.add_systems(
-		Update,
+		TickSchedule::get(app),
 		(
 			find_steer_target,
 			end_on_arrive,
@@ -39,6 +42,8 @@ pub fn steer_plugin(app: &mut App) {
 			separate::<M>,
 			align::<M>,
 			cohere::<M>,
+			// these add to the impulse so must run after those that set it
+			(avoid_obstacles, contain).after(seek).after(wander),
 		)
 			.in_set(TickSet),
 	)
@@ -47,7 +52,10 @@ pub fn steer_plugin(app: &mut App) {
 	.register_type::<MaxForce>()
 	.register_type::<MaxSpeed>()
 	.register_type::<ArriveRadius>()
-	.register_type::<GroupSteerAgent>();
+	.register_type::<GroupSteerAgent>()
+	.register_type::<SteerObstacle>()
+	.register_type::<AvoidObstacles>()
+	.register_type::<Contain>();
 
 	let world = app.world_mut();
 	world.register_bundle::<SteerBundle>();
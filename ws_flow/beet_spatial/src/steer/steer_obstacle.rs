@@ -0,0 +1,118 @@
+use bevy::math::bounding::Aabb3d;
+use bevy::math::bounding::BoundingSphere;
+use bevy::math::bounding::RayCast3d;
+use bevy::prelude::*;
+
+/// A simple collider that steering agents will try to avoid,
+/// see [`AvoidObstacles`](crate::prelude::AvoidObstacles).
+/// The shape is centered on the entity's [`Transform::translation`],
+/// rotation and scale are ignored.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component, Default)]
+pub enum SteerObstacle {
+	/// A sphere, or circle in 2D, with the given radius.
+	Sphere(f32),
+	/// An axis aligned box with the given half extents.
+	Cuboid(Vec3),
+}
+
+impl Default for SteerObstacle {
+	fn default() -> Self { Self::Sphere(0.5) }
+}
+
+/// The result of a successful [`SteerObstacle::raycast`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ObstacleHit {
+	/// The distance along the ray to the hit,
+	/// `0.` if the ray started inside the obstacle.
+	pub distance: f32,
+	/// The direction pointing away from the obstacle at the hit point.
+	pub normal: Vec3,
+}
+
+impl SteerObstacle {
+	/// Cast a ray against this obstacle, inflated by `margin`
+	/// which is usually the radius of the agent.
+	pub fn raycast(
+		&self,
+		center: Vec3,
+		origin: Vec3,
+		direction: Dir3,
+		max_distance: f32,
+		margin: f32,
+	) -> Option<ObstacleHit> {
+		let ray = RayCast3d::new(origin, direction, max_distance);
+		let distance = match self {
+			Self::Sphere(radius) => ray.sphere_intersection_at(
+				&BoundingSphere::new(center, radius + margin),
+			),
+			Self::Cuboid(half_extents) => ray.aabb_intersection_at(
+				&Aabb3d::new(center, *half_extents + Vec3::splat(margin)),
+			),
+		}?;
+		let point = origin + *direction * distance;
+		let normal = if distance <= 0. {
+			(origin - center).normalize_or_zero()
+		} else {
+			self.normal_at(center, point)
+		};
+		Some(ObstacleHit { distance, normal })
+	}
+
+	/// The outward normal of the surface nearest to the point.
+	pub fn normal_at(&self, center: Vec3, point: Vec3) -> Vec3 {
+		let delta = point - center;
+		match self {
+			Self::Sphere(_) => delta.normalize_or_zero(),
+			Self::Cuboid(half_extents) => {
+				// the face whose plane the point is closest to
+				let relative =
+					delta.abs() / half_extents.max(Vec3::splat(1e-6));
+				let axis =
+					if relative.x >= relative.y && relative.x >= relative.z {
+						Vec3::X
+					} else if relative.y >= relative.z {
+						Vec3::Y
+					} else {
+						Vec3::Z
+					};
+				axis * delta.signum()
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn sphere() {
+		let hit = SteerObstacle::Sphere(1.)
+			.raycast(Vec3::new(3., 0., 0.), Vec3::ZERO, Dir3::X, 5., 0.)
+			.unwrap();
+		expect(hit.distance).to_be_close_to(2.);
+		expect(hit.normal).to_be_close_to(Vec3::NEG_X);
+
+		expect(SteerObstacle::Sphere(1.).raycast(
+			Vec3::new(3., 0., 0.),
+			Vec3::ZERO,
+			Dir3::Y,
+			5.,
+			0.,
+		))
+		.to_be_none();
+	}
+
+	#[test]
+	fn cuboid() {
+		let hit = SteerObstacle::Cuboid(Vec3::new(1., 2., 0.))
+			.raycast(Vec3::new(0., 3., 0.), Vec3::ZERO, Dir3::Y, 5., 0.5)
+			.unwrap();
+		expect(hit.distance).to_be_close_to(0.5);
+		expect(hit.normal).to_be_close_to(Vec3::NEG_Y);
+	}
+}
@@ -0,0 +1,85 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+
+/// Steer away from any [`SteerObstacle`] in the path of the agent,
+/// see [`avoid_obstacles_impulse`].
+/// This is added to the [`Impulse`] so should be used alongside
+/// other steering actions like [`Seek`] or [`Wander`].
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// - [MutateOrigin](ActionTag::MutateOrigin)
+/// ## Example
+/// ```
+/// # use beet_spatial::doctest::*;
+/// # let mut world = world();
+/// world.spawn((Transform::from_xyz(1., 0., 0.), SteerObstacle::Sphere(0.5)));
+/// world
+/// 	.spawn((
+/// 		Transform::default(),
+/// 		ForceBundle::default(),
+/// 		SteerBundle::default(),
+/// 		SteerTarget::Position(Vec3::new(2., 0., 0.)),
+/// 	))
+/// 	.with_children(|parent| {
+/// 		parent.spawn(Seek::default());
+/// 		parent.spawn(AvoidObstacles::new(2.));
+/// 	});
+/// ```
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(ContinueRun)]
+pub struct AvoidObstacles {
+	/// The scalar to apply to the impulse
+	pub scalar: f32,
+	/// How far ahead of the agent to look for obstacles
+	pub look_ahead: f32,
+	/// The radius of the agent, obstacles are inflated by this amount
+	pub radius: f32,
+}
+
+impl Default for AvoidObstacles {
+	fn default() -> Self {
+		Self {
+			scalar: 1.,
+			look_ahead: 1.,
+			radius: 0.25,
+		}
+	}
+}
+
+impl AvoidObstacles {
+	/// Set impulse strength with default distances
+	pub fn new(scalar: f32) -> Self {
+		Self {
+			scalar,
+			..default()
+		}
+	}
+	/// Scale all radius and distances by this value
+	pub fn scaled_dist(mut self, dist: f32) -> Self {
+		self.look_ahead *= dist;
+		self.radius *= dist;
+		self
+	}
+}
+
+pub(crate) fn avoid_obstacles(
+	obstacles: Query<(&Transform, &SteerObstacle)>,
+	mut agents: Query<(&Transform, &Velocity, &MaxSpeed, &mut Impulse)>,
+	query: Query<(&Running, &AvoidObstacles)>,
+) {
+	for (running, avoid) in query.iter() {
+		let (transform, velocity, max_speed, mut impulse) = agents
+			.get_mut(running.origin)
+			.expect(&expect_action::to_have_origin(&running));
+
+		**impulse += *avoid_obstacles_impulse(
+			transform.translation,
+			velocity,
+			*max_speed,
+			avoid,
+			obstacles.iter(),
+		);
+	}
+}
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+
+/// Keep the agent within an axis aligned box, steering away
+/// from the walls before reaching them, see [`contain_impulse`].
+/// This is added to the [`Impulse`] so should be used alongside
+/// other steering actions like [`Wander`].
+/// ## Tags
+/// - [LongRunning](ActionTag::LongRunning)
+/// - [MutateOrigin](ActionTag::MutateOrigin)
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+#[require(ContinueRun)]
+pub struct Contain {
+	/// The scalar to apply to the impulse
+	pub scalar: f32,
+	/// How far ahead of the agent to predict its position
+	pub look_ahead: f32,
+	/// The minimum corner of the bounds
+	pub min: Vec3,
+	/// The maximum corner of the bounds
+	pub max: Vec3,
+}
+
+impl Default for Contain {
+	fn default() -> Self { Self::new(Vec3::splat(-1.), Vec3::splat(1.)) }
+}
+
+impl Contain {
+	/// Contain the agent within the given bounds
+	pub fn new(min: Vec3, max: Vec3) -> Self {
+		Self {
+			scalar: 1.,
+			look_ahead: 0.5,
+			min,
+			max,
+		}
+	}
+	/// Set impulse strength
+	pub fn with_scalar(mut self, scalar: f32) -> Self {
+		self.scalar = scalar;
+		self
+	}
+	/// Scale all distances by this value
+	pub fn scaled_dist(mut self, dist: f32) -> Self {
+		self.look_ahead *= dist;
+		self.min *= dist;
+		self.max *= dist;
+		self
+	}
+}
+
+pub(crate) fn contain(
+	mut agents: Query<(&Transform, &Velocity, &MaxSpeed, &mut Impulse)>,
+	query: Query<(&Running, &Contain)>,
+) {
+	for (running, contain) in query.iter() {
+		let (transform, velocity, max_speed, mut impulse) = agents
+			.get_mut(running.origin)
+			.expect(&expect_action::to_have_origin(&running));
+
+		**impulse += *contain_impulse(
+			transform.translation,
+			velocity,
+			*max_speed,
+			contain,
+		);
+	}
+}
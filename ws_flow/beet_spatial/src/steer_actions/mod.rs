@@ -3,8 +3,12 @@ mod align;
 pub use self::align::*;
 mod arrive;
 pub use self::arrive::*;
+mod avoid_obstacles;
+pub use self::avoid_obstacles::*;
 mod cohere;
 pub use self::cohere::*;
+mod contain;
+pub use self::contain::*;
 mod end_on_arrive;
 pub use self::end_on_arrive::*;
 mod find_steer_target;
@@ -130,4 +130,36 @@ mod test {
 			.not()
 			.to_be(Vec3::ZERO);
 	}
+
+	#[test]
+	fn deterministic() {
+		check_deterministic(
+			60,
+			|app| {
+				app.add_plugins((
+					BeetFlowPlugin::deterministic(0),
+					BeetSpatialPlugins::default(),
+				));
+				let world = app.world_mut();
+				let agent = world
+					.spawn((
+						Transform::default(),
+						ForceBundle::default(),
+						SteerBundle::default(),
+					))
+					.id();
+				let wander =
+					world.spawn((ChildOf(agent), Wander::default())).id();
+				world.flush_trigger(OnRunAction::new(wander, agent, ()));
+			},
+			|world| {
+				world
+					.query_filtered::<&Transform, With<Velocity>>()
+					.single(world)
+					.unwrap()
+					.translation
+			},
+		)
+		.unwrap();
+	}
 }
@@ -0,0 +1,85 @@
+// BEHAVIOR TREE VIEW
+// renders the snapshots served by the beet_server FlowVisualizerPlugin
+
+
+document.querySelectorAll('.bm-c-flow-tree').forEach(initFlowTree)
+
+
+function initFlowTree(container) {
+	const src = container.getAttribute('data-flow-src') ?? ''
+	const status = container.querySelector('.bm-c-flow-tree__status')
+	const root = container.querySelector('.bm-c-flow-tree__root')
+	// entities the user has collapsed, preserved between updates
+	const collapsed = new Set()
+
+	const render = (snapshot) => {
+		status.textContent = `Time: ${snapshot.time.toFixed(2)}s`
+		root.replaceChildren(...snapshot.roots.map(action => renderAction(action, collapsed)))
+	}
+
+	fetch(`${src}/flow/snapshot`)
+		.then(res => res.json())
+		.then(render)
+		.catch(err => status.textContent = `Failed to fetch snapshot: ${err}`)
+
+	const stream = new EventSource(`${src}/flow/stream`)
+	stream.onmessage = (ev) => render(JSON.parse(ev.data))
+	stream.onerror = () => status.textContent = 'Disconnected, retrying...'
+}
+
+/** create a list item for the action and its children */
+function renderAction(action, collapsed) {
+	const li = document.createElement('li')
+	const label = renderLabel(action)
+
+	if (action.children.length === 0) {
+		li.append(label)
+		return li
+	}
+
+	const details = document.createElement('details')
+	details.open = !collapsed.has(action.entity)
+	details.addEventListener('toggle', () => {
+		if (details.open)
+			collapsed.delete(action.entity)
+		else
+			collapsed.add(action.entity)
+	})
+	const summary = document.createElement('summary')
+	summary.append(label)
+	const ul = document.createElement('ul')
+	ul.append(...action.children.map(child => renderAction(child, collapsed)))
+	details.append(summary, ul)
+	li.append(details)
+	return li
+}
+
+/** the name, action components, running state, result and timers */
+function renderLabel(action) {
+	const span = document.createElement('span')
+	span.className = 'bm-c-flow-tree__action'
+	if (action.running !== null)
+		span.setAttribute('data-running', 'true')
+	if (action.last_result !== null)
+		span.setAttribute('data-result', action.last_result)
+
+	const name = document.createElement('strong')
+	name.textContent = action.name ?? `Entity ${action.entity}`
+	const components = document.createElement('code')
+	components.textContent = action.actions.join(', ')
+	span.append(name, ' ', components)
+
+	const info = []
+	if (action.last_result !== null)
+		info.push(action.last_result)
+	if (action.running !== null && action.last_started !== null)
+		info.push(`running ${action.last_started.toFixed(2)}s`)
+	else if (action.last_stopped !== null)
+		info.push(`stopped ${action.last_stopped.toFixed(2)}s ago`)
+	if (info.length > 0) {
+		const small = document.createElement('small')
+		small.textContent = ` ${info.join(' | ')}`
+		span.append(small)
+	}
+	return span
+}
@@ -0,0 +1,13 @@
+use crate::prelude::*;
+
+
+pub fn get() -> WebNode {
+	rsx! {
+		<h2>Behavior Tree View</h2>
+		<p>
+			Run an app with the <code>FlowVisualizerPlugin</code>
+			and its routes on the same server to see the live tree.
+		</p>
+		<BehaviorTreeView />
+	}
+}
@@ -0,0 +1,26 @@
+use beet_rsx::as_beet::*;
+
+/// A live collapsible tree of every behavior tree in a running app,
+/// rendering the snapshots served by the `FlowVisualizerPlugin`
+/// in `beet_server`.
+/// Running actions are highlighted and each action shows its
+/// last result and run timer.
+#[derive(Node)]
+pub struct BehaviorTreeView {
+	/// The base url of the visualizer routes, the snapshot
+	/// is fetched from `{src}/flow/snapshot` and updates are
+	/// streamed from `{src}/flow/stream`.
+	#[field(default)]
+	pub src: String,
+}
+
+fn behavior_tree_view(BehaviorTreeView { src }: BehaviorTreeView) -> WebNode {
+	rsx! {
+		<div class="bm-c-flow-tree" data-flow-src=src>
+			<p class="bm-c-flow-tree__status">Connecting...</p>
+			<ul class="bm-c-flow-tree__root"></ul>
+		</div>
+		<script src="./behavior_tree_view.js"/>
+		<style src="./behavior_tree_view.css" />
+	}
+}
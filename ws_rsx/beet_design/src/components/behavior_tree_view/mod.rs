@@ -0,0 +1,2 @@
+mod behavior_tree_view;
+pub use behavior_tree_view::*;
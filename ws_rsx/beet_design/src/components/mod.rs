@@ -1,6 +1,8 @@
+mod behavior_tree_view;
 mod design_system;
 mod sidebar;
 mod style;
+pub use behavior_tree_view::*;
 pub use design_system::*;
 pub use sidebar::*;
 pub use style::*;
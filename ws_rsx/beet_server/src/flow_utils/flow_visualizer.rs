@@ -0,0 +1,215 @@
+use axum::Router;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
+use axum::response::sse::Sse;
+use axum::routing::get;
+use beet_flow::prelude::*;
+use bevy::prelude::*;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::sync::watch;
+
+/// Serves a live [`FlowSnapshot`] of every behavior tree in the app,
+/// to be rendered by the `BehaviorTreeView` in `beet_design`.
+///
+/// Add the plugin to the bevy app and merge the [`Self::routes`]
+/// into the server router:
+/// - `GET /flow/snapshot`: the latest snapshot as json
+/// - `GET /flow/stream`: server sent events, each containing a snapshot
+///   whenever it changes
+///
+/// ```rust ignore
+/// let visualizer = FlowVisualizerPlugin::default();
+/// let router = visualizer.routes();
+/// std::thread::spawn(move || {
+/// 	tokio::runtime::Runtime::new()
+/// 		.unwrap()
+/// 		.block_on(BeetServer { router, ..default() }.serve())
+/// });
+/// App::new()
+/// 	.add_plugins((DefaultPlugins, BeetFlowPlugin::default(), visualizer))
+/// 	.run();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FlowVisualizerPlugin {
+	/// The minimum duration between snapshots.
+	pub interval: Duration,
+	sender: Arc<watch::Sender<String>>,
+}
+
+impl Default for FlowVisualizerPlugin {
+	fn default() -> Self { Self::new(Duration::from_millis(100)) }
+}
+
+impl FlowVisualizerPlugin {
+	/// Create a visualizer that takes a snapshot at most once per `interval`.
+	pub fn new(interval: Duration) -> Self {
+		let (sender, _) =
+			watch::channel(FlowSnapshot::default().to_json().unwrap());
+		Self {
+			interval,
+			sender: Arc::new(sender),
+		}
+	}
+
+	/// The routes serving the snapshots, these can be added
+	/// before or after the plugin is built.
+	pub fn routes<S: Clone + Send + Sync + 'static>(&self) -> Router<S> {
+		let snapshot_receiver = self.sender.subscribe();
+		let stream_receiver = self.sender.subscribe();
+		Router::new()
+			.route(
+				"/flow/snapshot",
+				get(move || {
+					let json = snapshot_receiver.borrow().clone();
+					async move {
+						([(header::CONTENT_TYPE, "application/json")], json)
+					}
+				}),
+			)
+			.route(
+				"/flow/stream",
+				get(move || {
+					let receiver = stream_receiver.clone();
+					async move { snapshot_stream(receiver) }
+				}),
+			)
+	}
+}
+
+impl Plugin for FlowVisualizerPlugin {
+	fn build(&self, app: &mut App) {
+		if !app.is_plugin_added::<FlowSnapshotPlugin>() {
+			app.add_plugins(FlowSnapshotPlugin);
+		}
+		app.insert_resource(FlowVisualizer {
+			interval: self.interval,
+			last_sent: None,
+			sender: self.sender.clone(),
+		})
+		.add_systems(Last, send_flow_snapshot);
+	}
+}
+
+/// Sends the latest [`FlowSnapshot`] to each connected client,
+/// added by the [`FlowVisualizerPlugin`].
+#[derive(Debug, Resource)]
+pub struct FlowVisualizer {
+	interval: Duration,
+	last_sent: Option<Instant>,
+	sender: Arc<watch::Sender<String>>,
+}
+
+fn send_flow_snapshot(world: &mut World) {
+	let mut visualizer = world.resource_mut::<FlowVisualizer>();
+	if visualizer
+		.last_sent
+		.map(|last| last.elapsed() < visualizer.interval)
+		.unwrap_or(false)
+	{
+		return;
+	}
+	visualizer.last_sent = Some(Instant::now());
+	let json = match FlowSnapshot::new(world).to_json() {
+		Ok(json) => json,
+		Err(err) => {
+			tracing::error!("failed to serialize flow snapshot: {err}");
+			return;
+		}
+	};
+	world
+		.resource::<FlowVisualizer>()
+		.sender
+		.send_if_modified(|prev| {
+			if *prev == json {
+				false
+			} else {
+				*prev = json;
+				true
+			}
+		});
+}
+
+/// Send the current snapshot, then each subsequent change.
+fn snapshot_stream(receiver: watch::Receiver<String>) -> impl IntoResponse {
+	let stream = futures::stream::unfold(
+		(receiver, true),
+		|(mut receiver, first)| async move {
+			if !first {
+				// the sender was dropped, ie the app exited
+				receiver.changed().await.ok()?;
+			}
+			let json = receiver.borrow_and_update().clone();
+			Some((
+				Ok::<_, Infallible>(Event::default().data(json)),
+				(receiver, false),
+			))
+		},
+	);
+	Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use axum::Router;
+	use beet_flow::prelude::*;
+	use bevy::prelude::*;
+	use http::Request;
+	use http::StatusCode;
+	use http::header;
+	use http_body_util::BodyExt;
+	use std::time::Duration;
+	use sweet::prelude::*;
+	use tower::util::ServiceExt;
+
+	fn setup() -> Router {
+		let visualizer = FlowVisualizerPlugin::new(Duration::ZERO);
+		let router = visualizer.routes();
+		let mut app = App::new();
+		app.add_plugins((BeetFlowPlugin::default(), visualizer));
+		app.world_mut()
+			.spawn((Name::new("root"), Sequence))
+			.with_child(ReturnWith(RunResult::Success))
+			.trigger(OnRun::local());
+		app.update();
+		router
+	}
+
+	fn req(uri: &str) -> Request<String> {
+		Request::builder().uri(uri).body(String::default()).unwrap()
+	}
+
+	#[sweet::test]
+	async fn snapshot() {
+		let res = setup().oneshot(req("/flow/snapshot")).await.unwrap();
+		expect(res.status()).to_be(StatusCode::OK);
+		expect(res.headers().get(header::CONTENT_TYPE).unwrap())
+			.to_be("application/json");
+		let body = res.into_body().collect().await.unwrap().to_bytes();
+		let json = String::from_utf8(body.to_vec()).unwrap();
+		let snapshot = FlowSnapshot::from_json(&json).unwrap();
+		expect(snapshot.roots.len()).to_be(1);
+		expect(snapshot.roots[0].label()).to_be("root");
+		expect(snapshot.roots[0].last_result.clone())
+			.to_be(Some(RunResult::Success));
+	}
+
+	#[sweet::test]
+	async fn stream() {
+		let res = setup().oneshot(req("/flow/stream")).await.unwrap();
+		expect(res.status()).to_be(StatusCode::OK);
+		expect(res.headers().get(header::CONTENT_TYPE).unwrap())
+			.to_be("text/event-stream");
+		// the first event is the current snapshot
+		let frame = res.into_body().frame().await.unwrap().unwrap();
+		let event = String::from_utf8(frame.into_data().unwrap().to_vec())
+			.unwrap();
+		expect(&event).to_start_with("data: ");
+		expect(&event).to_contain("\"name\":\"root\"");
+	}
+}
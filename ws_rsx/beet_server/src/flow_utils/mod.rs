@@ -0,0 +1,2 @@
+mod flow_visualizer;
+pub use flow_visualizer::*;
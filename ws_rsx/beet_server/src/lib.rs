@@ -5,6 +5,8 @@
 
 mod axum_utils;
 mod beet_server;
+#[cfg(feature = "flow")]
+mod flow_utils;
 #[cfg(feature = "lambda")]
 mod lambda_utils;
 mod rsx;
@@ -12,6 +14,8 @@ mod rsx;
 pub mod prelude {
 	pub use crate::axum_utils::*;
 	pub use crate::beet_server::*;
+	#[cfg(feature = "flow")]
+	pub use crate::flow_utils::*;
 	#[cfg(feature = "lambda")]
 	pub use crate::lambda_utils::*;
 	pub use crate::rsx::*;